@@ -1,5 +1,12 @@
-use crate::input::events::{KeyPress, PlaybackEvent};
+use crate::{
+    daemon::RemoteCommand,
+    error::AppError,
+    input::events::{KeyPress, PlaybackEvent},
+    player::mp3_player::PlaybackReport,
+    queue::SongFile,
+};
 use crossterm::event::KeyCode;
+use std::time::Duration;
 
 /// Actions available in app
 #[derive(Debug)]
@@ -8,6 +15,22 @@ pub enum Action {
     ToggleHelp,
     ToggleLogs,
     ChangeViewFocus,
+    /// Switch pane shown in compact layout, or focus between lists in other layouts
+    NextPane,
+    ChangeLayout,
+    TogglePreview,
+    ChangeSortOrder,
+    ToggleDirectoriesFirst,
+    ToggleHiddenFiles,
+    ToggleNonAudioFiles,
+    /// Bookmark current directory of file viewer, or remove its bookmark
+    ToggleBookmark,
+    ShowBookmarks,
+    GoToPath,
+    /// Show history of status bar messages
+    ShowMessages,
+    /// Return to directory listed before last jump
+    JumpBack,
     ViewerUp,
     ViewerDown,
     FileViewerDirUp,
@@ -17,10 +40,29 @@ pub enum Action {
     StopPlayback,
     ChangeVisualization,
     ChangeColor,
+    ChangeTheme,
+    ToggleWaveformSeekbar,
+    OnPlaybackStarted(SongFile),
+    OnPlaybackStopped(PlaybackReport),
     OnSongFinished,
+    OnPositionChanged(Duration),
+    /// Playback of song failed
+    OnPlaybackError(AppError),
+    OnBufferUnderrun,
     DeleteFromQueue,
     PlayNextFromQueue,
     PlayPreviousFromQueue,
+    AddStationsToQueue,
+    TogglePodcasts,
+    RefreshPodcasts,
+    TogglePlayedEpisode,
+    ToggleStatistics,
+    ToggleSmartPlaylists,
+    /// Set rating of selected track to given number of stars, 0 removes rating
+    Rate(u8),
+    ToggleFavourite,
+    ChangeRatingFilter,
+    AddFilteredToQueue,
 }
 
 /// Translator for keypresses to actions inside of app
@@ -38,21 +80,66 @@ impl Actions {
             KeyCode::Up => Some(Action::ViewerUp),
             KeyCode::Right => Some(Action::FileViewerEnterDir),
             KeyCode::Char('f') => Some(Action::ChangeViewFocus),
+            KeyCode::Tab => Some(Action::NextPane),
+            KeyCode::Char('y') => Some(Action::ChangeLayout),
+            KeyCode::Char('e') => Some(Action::TogglePreview),
+            KeyCode::Char('n') => Some(Action::ChangeSortOrder),
+            KeyCode::Char('b') => Some(Action::ToggleDirectoriesFirst),
+            KeyCode::Char('.') => Some(Action::ToggleHiddenFiles),
+            KeyCode::Char('x') => Some(Action::ToggleNonAudioFiles),
+            KeyCode::Char('B') => Some(Action::ToggleBookmark),
+            KeyCode::Char('\'') => Some(Action::ShowBookmarks),
+            KeyCode::Char(':') => Some(Action::GoToPath),
+            KeyCode::Char('M') => Some(Action::ShowMessages),
+            KeyCode::Backspace => Some(Action::JumpBack),
             KeyCode::Enter => Some(Action::Select),
             KeyCode::Char('d') => Some(Action::DeleteFromQueue),
             KeyCode::Char('p') => Some(Action::TogglePlayback),
             KeyCode::Char('s') => Some(Action::StopPlayback),
             KeyCode::Char('v') => Some(Action::ChangeVisualization),
             KeyCode::Char('c') => Some(Action::ChangeColor),
+            KeyCode::Char('t') => Some(Action::ChangeTheme),
+            KeyCode::Char('w') => Some(Action::ToggleWaveformSeekbar),
             KeyCode::Char('j') => Some(Action::PlayPreviousFromQueue),
             KeyCode::Char('k') => Some(Action::PlayNextFromQueue),
+            KeyCode::Char('r') => Some(Action::AddStationsToQueue),
+            KeyCode::Char('o') => Some(Action::TogglePodcasts),
+            KeyCode::Char('u') => Some(Action::RefreshPodcasts),
+            KeyCode::Char('m') => Some(Action::TogglePlayedEpisode),
+            KeyCode::Char('i') => Some(Action::ToggleStatistics),
+            KeyCode::Char('g') => Some(Action::ToggleSmartPlaylists),
+            KeyCode::Char(digit @ '0'..='5') => Some(Action::Rate(digit as u8 - b'0')),
+            KeyCode::Char('*') => Some(Action::ToggleFavourite),
+            KeyCode::Char('+') => Some(Action::ChangeRatingFilter),
+            KeyCode::Char('a') => Some(Action::AddFilteredToQueue),
             _ => None,
         }
     }
 
-    pub fn from_event(event: PlaybackEvent) -> Action {
+    /// Returns action reacting to playback event,
+    /// or `None` if event only requires interface to be redrawn
+    pub fn from_event(event: PlaybackEvent) -> Option<Action> {
         match event {
-            PlaybackEvent::SongFinished => Action::OnSongFinished,
+            PlaybackEvent::Started(song) => Some(Action::OnPlaybackStarted(*song)),
+            PlaybackEvent::Paused | PlaybackEvent::Resumed => None,
+            PlaybackEvent::Stopped(report) => Some(Action::OnPlaybackStopped(*report)),
+            PlaybackEvent::SongFinished => Some(Action::OnSongFinished),
+            PlaybackEvent::PositionChanged(position) => Some(Action::OnPositionChanged(position)),
+            PlaybackEvent::Error(e) => Some(Action::OnPlaybackError(e)),
+            PlaybackEvent::BufferUnderrun => Some(Action::OnBufferUnderrun),
+        }
+    }
+
+    /// Returns action for command received by daemon,
+    /// or `None` if command is not translated into action
+    pub fn from_remote(command: &RemoteCommand) -> Option<Action> {
+        match command {
+            RemoteCommand::TogglePlayback => Some(Action::TogglePlayback),
+            RemoteCommand::Stop => Some(Action::StopPlayback),
+            RemoteCommand::Next => Some(Action::PlayNextFromQueue),
+            RemoteCommand::Previous => Some(Action::PlayPreviousFromQueue),
+            RemoteCommand::Quit => Some(Action::Quit),
+            RemoteCommand::Add(_) | RemoteCommand::Status => None,
         }
     }
 }
@@ -1,14 +1,51 @@
-use std::sync::{Arc, Mutex};
+use std::{
+    path::{Path, PathBuf},
+    sync::mpsc::Sender,
+    time::{Duration, Instant},
+};
 
-use events::EventBus;
-use log::{info, LevelFilter};
-use ratatui::style::Color;
+use crossterm::event::{KeyCode, MouseButton, MouseEvent, MouseEventKind};
 
-use super::visualization_state::BarChartData;
-use super::{actions::Action, visualization_state::ChartData};
-use crate::input::events;
-use crate::queue::queue_view::QueueView;
-use crate::{cli::config::Config, files::FileViewerList, player::Mp3Player};
+use log::{debug, error, info, log, warn, Level, LevelFilter};
+use ratatui::widgets::ListState;
+
+use super::actions::Action;
+use super::layout::{CompactPane, LayoutProfile};
+use super::mouse::{hit_test, LayoutAreas, MouseTarget};
+use super::popup::Popup;
+use super::status::{StatusMessages, MESSAGE_TIMEOUT};
+use super::theme::{load_themes, Theme};
+use super::visualization_state::{
+    BarChartData, ChartData, OscilloscopeData, SpectrogramData, SpectrumSettings, VuMeterData,
+};
+use crate::clock::unix_timestamp;
+use crate::external::notifier::notify_playback_start;
+use crate::external::scrobbler::Scrobbler;
+use crate::history::{ListeningHistory, PlayRecord, StatisticsView};
+use crate::input::{events::KeyPress, AppEvent};
+use crate::library::{ratings::RatingFilter, Ratings, SmartPlaylistView};
+use crate::podcast::PodcastView;
+use crate::queue::{
+    queue_view::{QueueColumn, QueueView},
+    station_list::read_station_list,
+    SongFile,
+};
+use crate::{
+    cli::config::Config,
+    error::AppError,
+    files::{
+        bookmarks::Bookmarks, path_completion::expand_home, preview::PreviewLoader,
+        watcher::DirectoryWatcher, FileEntry, FileViewerList,
+    },
+    player::{
+        mp3_player::PlaybackReport, stream::is_stream_url, waveform::WaveformCache, Mp3Player,
+    },
+};
+
+/// Maximal time between clicks on the same row treated as double click
+const DOUBLE_CLICK_INTERVAL: Duration = Duration::from_millis(400);
+/// Time between checks that files of queued songs still exist
+const MISSING_FILES_CHECK_INTERVAL: Duration = Duration::from_secs(5);
 
 pub struct AppState {
     pub help_visible: bool,
@@ -16,14 +53,79 @@ pub struct AppState {
     pub file_viewer_focused: bool,
     pub log_level: LevelFilter,
     pub visualization_style: VisualizationStyle,
-    pub color_style: Color,
-    pub band_count: usize,
+    /// Theme used to draw interface
+    pub theme: Theme,
+    /// Themes available for switching at runtime
+    pub themes: Vec<Theme>,
+    pub spectrum_settings: SpectrumSettings,
+    /// Directory with configuration files
+    pub config_directory: PathBuf,
+    /// View displayed in library pane, next to queue
+    pub library_view: LibraryView,
+    /// Filter applied when adding whole directory to queue
+    pub rating_filter: RatingFilter,
+    /// Areas of rendered interface, used to find elements clicked with mouse
+    pub layout: LayoutAreas,
+    /// Time and target of last mouse click, used to detect double click
+    pub last_click: Option<(Instant, MouseTarget)>,
+    /// Terminal window has focus, interface is updated less often without it
+    pub terminal_focused: bool,
+    /// Arrangement of panes selected by user
+    pub layout_profile: LayoutProfile,
+    /// Arrangement used for last render, resolved from profile and terminal size
+    pub active_layout: LayoutProfile,
+    /// Pane shown in compact layout
+    pub compact_pane: CompactPane,
+    /// Details of selected file are shown below file viewer
+    pub preview_visible: bool,
+    /// Modal window receiving key presses, if open
+    pub popup: Option<Popup>,
+    /// Columns shown in queue table
+    pub queue_columns: Vec<QueueColumn>,
+    /// Progress of song is drawn over waveform of whole track instead of plain gauge
+    pub waveform_seekbar: bool,
+    /// Messages shown in status bar, also kept for history popup
+    pub messages: StatusMessages,
+}
+
+/// Views which can be displayed in library pane
+#[derive(Clone, Copy, PartialEq)]
+pub enum LibraryView {
+    Files,
+    Podcasts,
+    Statistics,
+    SmartPlaylists,
 }
 
 /// Types of visualization style available in app
 pub enum VisualizationStyle {
-    Bar { data: BarChartData },
-    Chart { data: ChartData },
+    Bar {
+        data: BarChartData,
+    },
+    /// Separate bars of left and right channel, side by side
+    StereoBar {
+        left: BarChartData,
+        right: BarChartData,
+    },
+    /// Bars growing symmetrically up and down from the middle
+    MirroredBar {
+        data: BarChartData,
+    },
+    Chart {
+        data: ChartData,
+    },
+    /// Raw samples of played frame
+    Oscilloscope {
+        data: OscilloscopeData,
+    },
+    /// Scrolling history of spectrum, with intensity shown by color
+    Spectrogram {
+        data: SpectrogramData,
+    },
+    /// Level and peak meter of each channel
+    VuMeter {
+        data: VuMeterData,
+    },
 }
 
 /// Indicator used by app runner to continue running or terminate process
@@ -39,15 +141,46 @@ pub struct App {
     pub state: AppState,
     pub file_list: FileViewerList,
     pub queue_view: QueueView,
+    pub podcast_view: PodcastView,
+    pub statistics_view: StatisticsView,
+    pub history: ListeningHistory,
+    /// Submits played songs to scrobbling services
+    scrobbler: Scrobbler,
+    pub ratings: Ratings,
+    pub smart_playlist_view: SmartPlaylistView,
     pub player: Mp3Player,
+    /// Waveforms of played tracks, drawn as seekbar
+    pub waveforms: WaveformCache,
+    /// Details of file viewer entries, loaded when selected
+    pub previews: PreviewLoader,
+    /// Directories bookmarked for quick jumps
+    pub bookmarks: Bookmarks,
+    /// Watches current directory of file viewer, `None` if watching is not supported
+    watcher: Option<DirectoryWatcher>,
+    /// Time of last check for queued songs with missing files
+    last_missing_files_check: Instant,
+    /// Number of songs skipped in a row because they could not be played
+    failed_songs: usize,
+    /// Background work was running during last tick
+    background_busy: bool,
+    /// Status bar showed message during last tick
+    message_shown: bool,
 }
 
 impl App {
-    pub fn new(config: &Config, events: Arc<Mutex<EventBus>>) -> Option<Self> {
+    pub fn new(config: &Config, events: Sender<AppEvent>) -> Result<Self, AppError> {
         let log_level = match config.debug {
             true => log::LevelFilter::Debug,
             false => log::LevelFilter::Info,
         };
+        let history = ListeningHistory::load(&Path::new(&config.data_directory).join("history"));
+        let spectrum_settings = SpectrumSettings {
+            band_count: config.band_count,
+            scale: config.frequency_scale,
+            min_frequency: config.min_frequency,
+            max_frequency: config.max_frequency,
+        };
+        let themes = load_themes(&Path::new(&config.config_directory).join("themes"));
         FileViewerList::with_directory(&config.starting_directory).map(|file_list| App {
             state: AppState {
                 help_visible: true,
@@ -55,84 +188,708 @@ impl App {
                 file_viewer_focused: true,
                 log_level,
                 visualization_style: VisualizationStyle::Bar {
-                    data: BarChartData::new(config.band_count),
+                    data: BarChartData::new(spectrum_settings),
                 },
-                color_style: config.color.to_ratatui_color(),
-                band_count: config.band_count,
+                theme: initial_theme(config, &themes),
+                themes,
+                spectrum_settings,
+                config_directory: PathBuf::from(&config.config_directory),
+                library_view: LibraryView::Files,
+                rating_filter: RatingFilter::Any,
+                layout: LayoutAreas::default(),
+                last_click: None,
+                terminal_focused: true,
+                layout_profile: config.layout,
+                active_layout: LayoutProfile::Standard,
+                compact_pane: CompactPane::Library,
+                queue_columns: config.queue_columns.clone(),
+                preview_visible: false,
+                popup: None,
+                waveform_seekbar: config.waveform_seekbar,
+                messages: StatusMessages::default(),
             },
             file_list,
             queue_view: QueueView::new(),
+            podcast_view: PodcastView::new(
+                &Path::new(&config.config_directory).join("podcasts"),
+                &Path::new(&config.cache_directory).join("podcasts"),
+                &Path::new(&config.data_directory).join("podcast_episodes"),
+            ),
+            statistics_view: StatisticsView::new(),
+            ratings: Ratings::load(&Path::new(&config.data_directory).join("library_index")),
+            smart_playlist_view: SmartPlaylistView::new(
+                &Path::new(&config.config_directory).join("playlists"),
+                Path::new(
+                    config
+                        .library_directory
+                        .as_ref()
+                        .unwrap_or(&config.starting_directory),
+                ),
+            ),
             player: Mp3Player::new(events),
+            history,
+            scrobbler: Scrobbler::from_config(config),
+            previews: PreviewLoader::new(),
+            watcher: DirectoryWatcher::new(),
+            last_missing_files_check: Instant::now(),
+            failed_songs: 0,
+            background_busy: false,
+            message_shown: false,
+            bookmarks: Bookmarks::load(&Path::new(&config.config_directory).join("bookmarks")),
+            waveforms: WaveformCache::new(&Path::new(&config.cache_directory).join("waveforms")),
         })
     }
 
     /// Dispatch action and return information to continue or terminate app
     pub fn do_action(&mut self, action: Action) -> AppActionResult {
         match action {
-            Action::Quit => return AppActionResult::Exit,
+            Action::Quit => {
+                self.save_podcast_progress();
+                return AppActionResult::Exit;
+            }
             Action::ToggleHelp => self.state.help_visible = !self.state.help_visible,
+            Action::TogglePreview => self.state.preview_visible = !self.state.preview_visible,
             Action::ToggleLogs => self.state.logs_visible = !self.state.logs_visible,
             Action::ChangeViewFocus => self.handle_lists_focus_change(),
+            Action::NextPane => self.handle_next_pane(),
+            Action::ChangeLayout => {
+                self.state.layout_profile = self.state.layout_profile.next();
+                let message = format!("Layout: {}", self.state.layout_profile.display());
+                self.notify(Level::Info, &message);
+            }
             Action::ViewerUp | Action::ViewerDown => self.handle_list_item_change(action),
             Action::FileViewerDirUp | Action::FileViewerEnterDir => {
                 self.handle_file_list_directory_change(action)
             }
+            Action::ChangeSortOrder
+            | Action::ToggleDirectoriesFirst
+            | Action::ToggleHiddenFiles
+            | Action::ToggleNonAudioFiles => {
+                if let Err(e) = self.file_list.do_action(action) {
+                    self.report_error(e);
+                }
+            }
+            Action::ToggleBookmark => {
+                let directory = self.file_list.current_directory.clone();
+                let message = match self.bookmarks.toggle(&directory) {
+                    true => format!("Bookmarked {directory}"),
+                    false => format!("Removed bookmark of {directory}"),
+                };
+                self.notify(Level::Info, &message);
+            }
+            Action::ShowBookmarks => {
+                self.state.popup = Some(Popup::bookmarks(self.bookmarks.directories.len()))
+            }
+            Action::ShowMessages => {
+                self.state.popup = Some(Popup::messages(self.state.messages.len()))
+            }
+            Action::GoToPath => {
+                let input = format!(
+                    "{}/",
+                    self.file_list.current_directory.trim_end_matches('/')
+                );
+                self.state.popup = Some(Popup::go_to_path(&input));
+            }
+            Action::JumpBack => {
+                if self.file_list.jump_back() {
+                    self.show_file_viewer();
+                }
+            }
             Action::Select => self.handle_list_item_select(),
             Action::TogglePlayback | Action::StopPlayback => {
                 self.player.handle_action(action);
             }
             Action::ChangeVisualization => self.change_visualization_style(),
-            Action::ChangeColor => self.change_color(),
+            Action::ChangeColor => self.state.theme = self.state.theme.with_next_accent(),
+            Action::ChangeTheme => self.change_theme(),
+            Action::ToggleWaveformSeekbar => {
+                self.state.waveform_seekbar = !self.state.waveform_seekbar
+            }
+            Action::OnPlaybackStarted(song) => {
+                notify_playback_start(&song);
+                self.scrobbler.now_playing(&song);
+            }
+            Action::OnPlaybackStopped(report) => self.handle_playback_stopped(report),
             Action::OnSongFinished => self.handle_song_finished(),
+            Action::OnPositionChanged(position) => {
+                if let Some(path) = self.player.get_song_path() {
+                    self.podcast_view
+                        .episode_states
+                        .update_position(path, position);
+                }
+            }
+            Action::OnBufferUnderrun => {
+                self.notify(Level::Warn, "Stream ran out of data, buffering...")
+            }
+            Action::OnPlaybackError(e) => self.handle_playback_error(e),
             Action::DeleteFromQueue => self.handle_delete_from_queue(action),
             Action::PlayNextFromQueue | Action::PlayPreviousFromQueue => {
                 self.handle_play_from_queue(action)
             }
+            Action::AddStationsToQueue => self.add_stations_to_queue(),
+            Action::TogglePodcasts => self.toggle_library_view(LibraryView::Podcasts),
+            Action::ToggleStatistics => self.toggle_library_view(LibraryView::Statistics),
+            Action::ToggleSmartPlaylists => self.toggle_library_view(LibraryView::SmartPlaylists),
+            Action::RefreshPodcasts | Action::TogglePlayedEpisode => {
+                self.handle_podcast_action(action)
+            }
+            Action::Rate(stars) => {
+                if let Some(path) = self.get_selected_track_path() {
+                    self.ratings.set_stars(&path, stars);
+                }
+            }
+            Action::ToggleFavourite => {
+                if let Some(path) = self.get_selected_track_path() {
+                    self.ratings.toggle_favourite(&path);
+                }
+            }
+            Action::ChangeRatingFilter => {
+                self.state.rating_filter = self.state.rating_filter.next();
+            }
+            Action::AddFilteredToQueue => self.add_filtered_directory_to_queue(),
         };
 
         AppActionResult::Continue
     }
 
+    /// Pauses spectrum computation while terminal is not focused, as it's not visible anyway
+    pub fn set_terminal_focused(&mut self, focused: bool) {
+        debug!("Terminal focus changed, focused: {focused}");
+        self.state.terminal_focused = focused;
+        self.player.set_spectrum_enabled(focused);
+    }
+
+    /// Adds pasted paths or stream URLs to queue, one per line
+    pub fn handle_paste(&mut self, text: &str) {
+        if let Some(popup) = self.state.popup.as_mut() {
+            if let Popup::GoToPath { input, .. } = popup {
+                let text = format!("{input}{}", text.lines().next().unwrap_or_default());
+                popup.set_input(text);
+            }
+            return;
+        }
+        let added: usize = text
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(|line| self.enqueue_path(line))
+            .sum();
+        self.notify(
+            Level::Info,
+            &format!("Added {added} pasted song(s) to queue"),
+        );
+    }
+
+    /// Handles key pressed while popup is open, keys are not translated to actions then
+    pub fn handle_popup_key(&mut self, key_press: KeyPress) {
+        let Some(popup) = self.state.popup.as_mut() else {
+            return;
+        };
+        match popup {
+            Popup::Bookmarks { state } => {
+                let count = self.bookmarks.directories.len();
+                match key_press.key {
+                    KeyCode::Up if count > 0 => state.select(Some(
+                        state.selected().map_or(0, |i| (i + count - 1) % count),
+                    )),
+                    KeyCode::Down if count > 0 => {
+                        state.select(Some(state.selected().map_or(0, |i| (i + 1) % count)))
+                    }
+                    KeyCode::Enter => {
+                        let selected = state.selected();
+                        self.state.popup = None;
+                        if let Some(directory) = selected
+                            .and_then(|i| self.bookmarks.directories.get(i))
+                            .cloned()
+                        {
+                            self.jump_to_directory(&directory);
+                        }
+                    }
+                    KeyCode::Delete | KeyCode::Char('d') => {
+                        if let Some(index) = state.selected() {
+                            self.bookmarks.remove(index);
+                            state.select(match self.bookmarks.directories.len() {
+                                0 => None,
+                                count => Some(index.min(count - 1)),
+                            });
+                        }
+                    }
+                    KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('\'') => {
+                        self.state.popup = None
+                    }
+                    _ => {}
+                }
+            }
+            Popup::GoToPath { input, .. } => match key_press.key {
+                KeyCode::Char(c) => {
+                    let text = format!("{input}{c}");
+                    popup.set_input(text);
+                }
+                KeyCode::Backspace => {
+                    let mut text = input.clone();
+                    text.pop();
+                    popup.set_input(text);
+                }
+                KeyCode::Tab => popup.complete(),
+                KeyCode::Enter => {
+                    let directory = expand_home(input);
+                    self.state.popup = None;
+                    self.jump_to_directory(&directory);
+                }
+                KeyCode::Esc => self.state.popup = None,
+                _ => {}
+            },
+            Popup::Messages { state } => {
+                let count = self.state.messages.len();
+                match key_press.key {
+                    KeyCode::Up if count > 0 => {
+                        state.select(Some(state.selected().map_or(0, |i| i.saturating_sub(1))))
+                    }
+                    KeyCode::Down if count > 0 => {
+                        state.select(Some(state.selected().map_or(0, |i| (i + 1).min(count - 1))))
+                    }
+                    KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('M') => {
+                        self.state.popup = None
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    /// Lists given directory in file viewer and focuses it
+    fn jump_to_directory(&mut self, directory: &str) {
+        let directory = match directory.trim_end_matches('/') {
+            "" => "/",
+            trimmed => trimmed,
+        };
+        match self.file_list.jump_to(directory) {
+            Ok(()) => self.show_file_viewer(),
+            Err(e) => self.report_error(e),
+        }
+    }
+
+    /// Shows file viewer in library pane and moves focus to it
+    fn show_file_viewer(&mut self) {
+        self.state.library_view = LibraryView::Files;
+        if !self.state.file_viewer_focused {
+            self.handle_lists_focus_change();
+        }
+    }
+
+    /// Selects, plays or scrolls list rows, seeks and changes focus with mouse
+    pub fn handle_mouse(&mut self, event: MouseEvent) {
+        // Open popup captures mouse as it captures keys, so lists below it are left untouched
+        if self.state.popup.is_some() {
+            match event.kind {
+                MouseEventKind::ScrollUp => self.handle_popup_key(KeyPress { key: KeyCode::Up }),
+                MouseEventKind::ScrollDown => {
+                    self.handle_popup_key(KeyPress { key: KeyCode::Down })
+                }
+                MouseEventKind::Down(_) => self.state.popup = None,
+                _ => {}
+            }
+            return;
+        }
+        let target = hit_test(&self.state.layout, event.column, event.row);
+        match (event.kind, target) {
+            (MouseEventKind::ScrollUp, MouseTarget::Library(_) | MouseTarget::Queue(_)) => {
+                self.focus_pane(target);
+                self.handle_list_item_change(Action::ViewerUp);
+            }
+            (MouseEventKind::ScrollDown, MouseTarget::Library(_) | MouseTarget::Queue(_)) => {
+                self.focus_pane(target);
+                self.handle_list_item_change(Action::ViewerDown);
+            }
+            (MouseEventKind::Down(MouseButton::Left), _) => self.handle_click(target),
+            _ => {}
+        }
+    }
+
+    fn handle_click(&mut self, target: MouseTarget) {
+        let double_click = matches!(
+            self.state.last_click,
+            Some((time, last_target)) if last_target == target && time.elapsed() < DOUBLE_CLICK_INTERVAL
+        );
+        self.state.last_click = Some((Instant::now(), target));
+        match target {
+            MouseTarget::Library(row) | MouseTarget::Queue(row) => {
+                self.focus_pane(target);
+                let Some(row) = row else {
+                    return;
+                };
+                let (state, len) = self.focused_list_state();
+                let index = state.offset() + row;
+                if index >= len {
+                    return;
+                }
+                state.select(Some(index));
+                if double_click {
+                    self.state.last_click = None;
+                    self.handle_list_item_select();
+                }
+            }
+            MouseTarget::ProgressBar(fraction) => self.player.seek(fraction),
+            MouseTarget::PlayerTitle => self.player.handle_action(Action::TogglePlayback),
+            MouseTarget::Nothing => {}
+        }
+    }
+
+    /// Moves focus to list pane under mouse cursor
+    fn focus_pane(&mut self, target: MouseTarget) {
+        let library_clicked = matches!(target, MouseTarget::Library(_));
+        if library_clicked != self.state.file_viewer_focused {
+            self.handle_lists_focus_change();
+        }
+    }
+
+    /// Returns state and length of list in focused pane
+    fn focused_list_state(&mut self) -> (&mut ListState, usize) {
+        match (self.state.file_viewer_focused, self.state.library_view) {
+            (true, LibraryView::Files) => (&mut self.file_list.state, self.file_list.items.len()),
+            (true, LibraryView::Podcasts) => (
+                &mut self.podcast_view.state,
+                self.podcast_view.episodes.len(),
+            ),
+            (true, LibraryView::Statistics) => (
+                &mut self.statistics_view.state,
+                self.statistics_view.rows.len(),
+            ),
+            (true, LibraryView::SmartPlaylists) => (
+                &mut self.smart_playlist_view.state,
+                self.smart_playlist_view.playlists.len(),
+            ),
+            (false, _) => (&mut self.queue_view.state, self.queue_view.items.len()),
+        }
+    }
+
     fn handle_lists_focus_change(&mut self) {
         self.state.file_viewer_focused = !self.state.file_viewer_focused;
         self.file_list.toggle_focus();
         self.queue_view.toggle_focus();
+        self.state.compact_pane = match self.state.file_viewer_focused {
+            true => CompactPane::Library,
+            false => CompactPane::Queue,
+        };
+    }
+
+    /// Shows next pane of compact layout, focusing its list
+    fn handle_next_pane(&mut self) {
+        if self.state.active_layout != LayoutProfile::Compact {
+            return self.handle_lists_focus_change();
+        }
+        let pane = self.state.compact_pane.next();
+        let library_focused = match pane {
+            CompactPane::Library => Some(true),
+            CompactPane::Queue => Some(false),
+            CompactPane::Player => None,
+        };
+        if library_focused.is_some_and(|focused| focused != self.state.file_viewer_focused) {
+            self.handle_lists_focus_change();
+        }
+        self.state.compact_pane = pane;
+    }
+
+    /// Performs periodic updates of components working in background.
+    /// Returns true if interface changed and should be redrawn.
+    pub fn on_tick(&mut self) -> bool {
+        let mut changed = false;
+        if self.state.library_view == LibraryView::Statistics {
+            changed |= self.statistics_view.update(&self.history);
+        }
+        for episode in self.podcast_view.update() {
+            let message = format!("Adding downloaded episode {} to queue", episode.name);
+            self.notify(Level::Info, &message);
+            self.enqueue_file(&episode);
+        }
+        let playlist_tracks = self.smart_playlist_view.update(
+            &mut self.ratings,
+            self.history.records(),
+            unix_timestamp(),
+        );
+        if let Some(songs) = playlist_tracks {
+            self.enqueue_smart_playlist_tracks(songs);
+            changed = true;
+        }
+        let mut changed_paths = vec![];
+        if let Some(watcher) = self.watcher.as_mut() {
+            watcher.watch(&self.file_list.current_directory);
+            changed_paths = watcher.take_changes();
+        }
+        let files_changed = !changed_paths.is_empty();
+        if files_changed {
+            debug!("Directory {} changed", self.file_list.current_directory);
+            if let Err(e) = self.file_list.reload() {
+                self.report_error(e);
+            }
+            self.previews.invalidate(&changed_paths);
+        }
+        if files_changed || self.last_missing_files_check.elapsed() >= MISSING_FILES_CHECK_INTERVAL
+        {
+            self.last_missing_files_check = Instant::now();
+            changed |= self.queue_view.update_missing() > 0;
+        }
+        if self.state.waveform_seekbar {
+            if let Some(path) = self.player.get_song_path().filter(|p| !is_stream_url(p)) {
+                self.waveforms.request(path);
+            }
+        }
+        // Results of background work are drawn on the tick after it finishes
+        let busy = self.previews.is_loading()
+            || self.waveforms.is_loading()
+            || self.podcast_view.is_busy()
+            || self.smart_playlist_view.is_scanning()
+            || self.ratings.is_loading();
+        changed |= busy || self.background_busy;
+        self.background_busy = busy;
+        let message_shown = self.state.messages.current(MESSAGE_TIMEOUT).is_some();
+        changed |= message_shown != self.message_shown;
+        self.message_shown = message_shown;
+        changed || files_changed
+    }
+
+    /// Returns true if interface changes continuously, as song is played
+    pub fn is_animated(&self) -> bool {
+        self.player.is_running()
     }
 
     fn handle_list_item_change(&mut self, action: Action) {
-        match self.state.file_viewer_focused {
-            true => self.file_list.do_action(action),
-            false => self.queue_view.do_action(action),
+        match (self.state.file_viewer_focused, self.state.library_view) {
+            (true, LibraryView::Files) => {
+                if let Err(e) = self.file_list.do_action(action) {
+                    self.report_error(e);
+                }
+            }
+            (true, LibraryView::Podcasts) => self.podcast_view.do_action(action),
+            (true, LibraryView::Statistics) => self.statistics_view.do_action(action),
+            (true, LibraryView::SmartPlaylists) => self.smart_playlist_view.do_action(action),
+            (false, _) => self.queue_view.do_action(action),
         }
     }
 
     fn handle_file_list_directory_change(&mut self, action: Action) {
-        if self.state.file_viewer_focused {
-            self.file_list.do_action(action);
+        match (self.state.file_viewer_focused, self.state.library_view) {
+            (true, LibraryView::Files) => {
+                if let Err(e) = self.file_list.do_action(action) {
+                    self.report_error(e);
+                }
+            }
+            (true, LibraryView::Statistics) => {
+                self.statistics_view.do_action(action);
+                self.statistics_view.update(&self.history);
+            }
+            (true, LibraryView::Podcasts) | (true, LibraryView::SmartPlaylists) | (false, _) => {}
+        }
+    }
+
+    /// Shows given view in library pane, or file viewer if view is already shown
+    fn toggle_library_view(&mut self, view: LibraryView) {
+        self.state.library_view = match self.state.library_view == view {
+            true => LibraryView::Files,
+            false => view,
+        };
+        match self.state.library_view {
+            LibraryView::Podcasts if self.podcast_view.episodes.is_empty() => {
+                self.podcast_view.refresh()
+            }
+            LibraryView::Statistics => {
+                self.statistics_view.update(&self.history);
+            }
+            LibraryView::SmartPlaylists => self.smart_playlist_view.reload(),
+            LibraryView::Files | LibraryView::Podcasts => {}
+        }
+    }
+
+    fn handle_podcast_action(&mut self, action: Action) {
+        if self.state.library_view == LibraryView::Podcasts {
+            self.podcast_view.do_action(action);
+        }
+    }
+
+    fn save_podcast_progress(&mut self) {
+        if let Err(e) = self.podcast_view.episode_states.save() {
+            error!("Failed to save podcast progress: {e}");
         }
     }
 
     fn handle_list_item_select(&mut self) {
-        match self.state.file_viewer_focused {
-            true => {
-                if let Some(file_entry) = self.file_list.get_selected_file_entry() {
-                    if !file_entry.is_file {
-                        return;
-                    }
-                    self.queue_view.add(file_entry);
-                    if self.queue_view.items.len() > 1 {
-                        return;
-                    }
-                    self.queue_view.do_action(Action::ViewerDown);
-                    self.update_currently_playing_from_selection();
+        match (self.state.file_viewer_focused, self.state.library_view) {
+            (true, LibraryView::Podcasts) => {
+                if let Some(episode) = self.podcast_view.select() {
+                    self.enqueue_file(&episode);
                 }
             }
-            false => {
+            (true, LibraryView::Statistics) => {
+                for path in self.statistics_view.get_selected_paths() {
+                    self.enqueue_path(&path);
+                }
+            }
+            (true, LibraryView::SmartPlaylists) => self.enqueue_smart_playlist(),
+            (true, LibraryView::Files) => {
+                if let Some(file_entry) = self.file_list.get_selected_file_entry().cloned() {
+                    self.enqueue_file(&file_entry);
+                }
+            }
+            (false, _) => {
                 self.update_currently_playing_from_selection();
             }
         }
     }
 
+    /// Adds file to queue, or all mp3 files if path points to directory.
+    /// Returns number of songs added.
+    pub fn enqueue_path(&mut self, path: &str) -> usize {
+        if is_stream_url(path) {
+            self.enqueue(SongFile::stream(path, path));
+            return 1;
+        }
+        let file_entry = FileEntry::new(Path::new(path));
+        if file_entry.is_audio() {
+            self.enqueue_file(&file_entry);
+            return 1;
+        }
+        match FileViewerList::with_directory(path) {
+            Ok(directory) => {
+                let songs: Vec<&FileEntry> =
+                    directory.items.iter().filter(|e| e.is_audio()).collect();
+                songs.iter().for_each(|song| self.enqueue_file(song));
+                songs.len()
+            }
+            Err(e) => {
+                self.report_error(e);
+                0
+            }
+        }
+    }
+
+    /// Returns single line summary of player state
+    pub fn status(&self) -> String {
+        let song = self
+            .queue_view
+            .get_now_playing_entry()
+            .map(|s| s.display_short())
+            .unwrap_or_else(|| String::from("--"));
+        let progress = self
+            .player
+            .get_text_progress()
+            .unwrap_or_else(|| String::from("-/-"));
+        format!(
+            "{} | {song} | {progress} | queue: {}",
+            self.player.get_playback_status_string().trim(),
+            self.queue_view.items.len()
+        )
+    }
+
+    fn enqueue_file(&mut self, file_entry: &FileEntry) {
+        if file_entry.is_audio() {
+            match SongFile::new(file_entry) {
+                Ok(song) => self.enqueue(song),
+                Err(e) => self.report_error(e),
+            }
+        }
+    }
+
+    /// Logs message and shows it in status bar
+    fn notify(&mut self, level: Level, text: &str) {
+        log!(level, "{text}");
+        self.state.messages.push(level, text);
+    }
+
+    fn report_error(&mut self, error: AppError) {
+        self.notify(Level::Error, &error.to_string());
+    }
+
+    /// Adds song to queue and starts playback if queue was empty
+    fn enqueue(&mut self, song: SongFile) {
+        self.queue_view.add(song);
+        if self.queue_view.items.len() > 1 {
+            return;
+        }
+        self.queue_view.do_action(Action::ViewerDown);
+        self.update_currently_playing_from_selection();
+    }
+
+    fn add_stations_to_queue(&mut self) {
+        let station_file = self.state.config_directory.join("stations");
+        match read_station_list(&station_file) {
+            Ok(stations) => {
+                let message = format!("Adding {} radio stations to queue", stations.len());
+                self.notify(Level::Info, &message);
+                stations
+                    .into_iter()
+                    .for_each(|station| self.enqueue(station));
+            }
+            Err(e) => {
+                let message = format!(
+                    "Failed to read station list {}: {e}",
+                    station_file.to_string_lossy()
+                );
+                self.notify(Level::Error, &message);
+            }
+        }
+    }
+
+    /// Returns path of track selected in focused list
+    fn get_selected_track_path(&self) -> Option<String> {
+        match (self.state.file_viewer_focused, self.state.library_view) {
+            (true, LibraryView::Files) => self
+                .file_list
+                .get_selected_file_entry()
+                .filter(|e| e.is_audio())
+                .map(|e| e.path.clone()),
+            (false, _) => self
+                .queue_view
+                .get_selected_file_entry()
+                .filter(|s| !s.is_stream())
+                .map(|s| s.file_entry.path.clone()),
+            (true, LibraryView::Podcasts)
+            | (true, LibraryView::Statistics)
+            | (true, LibraryView::SmartPlaylists) => None,
+        }
+    }
+
+    /// Adds all tracks matching selected smart playlist to queue,
+    /// or once library scan finishes if it is not available yet
+    fn enqueue_smart_playlist(&mut self) {
+        let songs = self.smart_playlist_view.evaluate_selected(
+            &mut self.ratings,
+            self.history.records(),
+            unix_timestamp(),
+        );
+        match songs {
+            Some(songs) => self.enqueue_smart_playlist_tracks(songs),
+            None => self.notify(Level::Info, "Scanning library for smart playlist..."),
+        }
+    }
+
+    fn enqueue_smart_playlist_tracks(&mut self, songs: Vec<SongFile>) {
+        let message = format!("Adding {} tracks from smart playlist to queue", songs.len());
+        self.notify(Level::Info, &message);
+        for song in songs {
+            self.enqueue(song);
+        }
+    }
+
+    /// Adds tracks from current directory matching rating filter to queue
+    fn add_filtered_directory_to_queue(&mut self) {
+        let filter = self.state.rating_filter;
+        let matching: Vec<FileEntry> = self
+            .file_list
+            .items
+            .iter()
+            .filter(|e| e.is_audio())
+            .filter(|e| filter.matches(&self.ratings.get(&e.path)))
+            .cloned()
+            .collect();
+        let message = format!(
+            "Adding {} tracks rated {} to queue",
+            matching.len(),
+            filter.display()
+        );
+        self.notify(Level::Info, &message);
+        matching.iter().for_each(|e| self.enqueue_file(e));
+    }
+
     fn handle_delete_from_queue(&mut self, action: Action) {
         if self.state.file_viewer_focused {
             return;
@@ -145,21 +902,67 @@ impl App {
         }
     }
 
+    /// Scrobbles played song and records it in listening history.
+    /// Plays which failed or were stopped right away are recorded as not completed.
+    fn handle_playback_stopped(&mut self, report: PlaybackReport) {
+        self.scrobbler
+            .scrobble(&report.song, report.started_at, report.listened);
+        let record = PlayRecord::new(
+            &report.song,
+            report.started_at,
+            report.listened,
+            report.completed,
+        );
+        if let Err(e) = self.history.record(record) {
+            self.notify(
+                Level::Error,
+                &format!("Failed to save listening history: {e}"),
+            );
+        }
+    }
+
     fn handle_song_finished(&mut self) {
+        if let Some(path) = self.player.get_song_path().map(String::from) {
+            if self.podcast_view.episode_states.contains(&path) {
+                self.podcast_view.episode_states.mark_played(&path);
+                self.save_podcast_progress();
+            }
+        }
+        self.failed_songs = 0;
         info!("Playing next song from queue...");
         self.queue_view.do_action(Action::PlayNextFromQueue);
         self.update_currently_playing();
     }
 
+    /// Skips song which could not be played, unless all songs in queue failed in a row
+    fn handle_playback_error(&mut self, error: AppError) {
+        let skip = error.is_track_error();
+        self.report_error(error);
+        if !skip {
+            return;
+        }
+        self.failed_songs += 1;
+        if self.failed_songs >= self.queue_view.items.len() {
+            self.notify(Level::Warn, "No playable songs in queue, stopping playback");
+            self.failed_songs = 0;
+            return;
+        }
+        info!("Skipping to next song in queue...");
+        self.queue_view.do_action(Action::PlayNextFromQueue);
+        self.update_currently_playing();
+    }
+
     fn handle_play_from_queue(&mut self, action: Action) {
         self.queue_view.do_action(action);
         self.update_currently_playing();
     }
 
     fn update_currently_playing_from_selection(&mut self) {
+        self.failed_songs = 0;
         if let Some(selected_song) = self.queue_view.get_selected_file_entry() {
             self.player.set_song_file(selected_song.clone());
             self.queue_view.now_playing = self.queue_view.state.selected();
+            self.resume_episode_position();
             self.player.handle_action(Action::TogglePlayback);
         }
     }
@@ -167,46 +970,78 @@ impl App {
     fn update_currently_playing(&mut self) {
         if let Some(selected_song) = self.queue_view.get_now_playing_entry() {
             self.player.set_song_file(selected_song.clone());
+            self.resume_episode_position();
             self.player.handle_action(Action::TogglePlayback);
         }
     }
 
+    /// Starts podcast episode from position where its last playback ended
+    fn resume_episode_position(&mut self) {
+        let state = self
+            .player
+            .get_song_path()
+            .and_then(|path| self.podcast_view.episode_states.get(path));
+        if let Some(state) = state {
+            if !state.played && !state.position.is_zero() {
+                let position = state.position;
+                self.player.set_start_position(position);
+            }
+        }
+    }
+
     fn change_visualization_style(&mut self) {
-        match &self.state.visualization_style {
-            VisualizationStyle::Bar { data: _ } => {
-                self.state.visualization_style = VisualizationStyle::Chart {
-                    data: ChartData::new(self.state.band_count),
-                }
-            }
-            VisualizationStyle::Chart { data: _ } => {
-                self.state.visualization_style = VisualizationStyle::Bar {
-                    data: BarChartData::new(self.state.band_count),
-                }
-            }
-        }
-    }
-
-    fn change_color(&mut self) {
-        match &self.state.color_style {
-            Color::Cyan => self.state.color_style = Color::Red,
-            Color::Red => self.state.color_style = Color::Magenta,
-            Color::Magenta => self.state.color_style = Color::Blue,
-            Color::Blue => self.state.color_style = Color::Green,
-            Color::Green => self.state.color_style = Color::Cyan,
-            Color::Indexed(_)
-            | Color::Rgb(_, _, _)
-            | Color::White
-            | Color::LightRed
-            | Color::LightCyan
-            | Color::LightMagenta
-            | Color::LightBlue
-            | Color::LightYellow
-            | Color::LightGreen
-            | Color::DarkGray
-            | Color::Gray
-            | Color::Yellow
-            | Color::Black
-            | Color::Reset => (),
+        let settings = self.state.spectrum_settings;
+        self.state.visualization_style = match &self.state.visualization_style {
+            VisualizationStyle::Bar { data: _ } => VisualizationStyle::StereoBar {
+                left: BarChartData::new(settings),
+                right: BarChartData::new(settings),
+            },
+            VisualizationStyle::StereoBar { .. } => VisualizationStyle::MirroredBar {
+                data: BarChartData::new(settings),
+            },
+            VisualizationStyle::MirroredBar { data: _ } => VisualizationStyle::Chart {
+                data: ChartData::new(settings),
+            },
+            VisualizationStyle::Chart { data: _ } => VisualizationStyle::Oscilloscope {
+                data: OscilloscopeData::new(),
+            },
+            VisualizationStyle::Oscilloscope { data: _ } => VisualizationStyle::Spectrogram {
+                data: SpectrogramData::new(settings),
+            },
+            VisualizationStyle::Spectrogram { data: _ } => VisualizationStyle::VuMeter {
+                data: VuMeterData::new(),
+            },
+            VisualizationStyle::VuMeter { data: _ } => VisualizationStyle::Bar {
+                data: BarChartData::new(settings),
+            },
+        }
+    }
+
+    /// Switches to next available theme
+    fn change_theme(&mut self) {
+        let themes = &self.state.themes;
+        let next = themes
+            .iter()
+            .position(|t| t.name == self.state.theme.name)
+            .map(|i| (i + 1) % themes.len())
+            .unwrap_or(0);
+        self.state.theme = themes[next].clone();
+        let message = format!("Theme: {}", self.state.theme.name);
+        self.notify(Level::Info, &message);
+    }
+}
+
+/// Returns theme selected in configuration, with accent color override applied
+fn initial_theme(config: &Config, themes: &[Theme]) -> Theme {
+    let theme = match themes.iter().find(|t| t.name == config.theme) {
+        Some(theme) => theme.clone(),
+        None => {
+            warn!("Theme {} not found, using dark theme", config.theme);
+            Theme::dark()
         }
+    };
+    match &config.color {
+        Some(color) => theme.with_accent(color.to_ratatui_color()),
+        None => theme,
     }
 }
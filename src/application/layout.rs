@@ -0,0 +1,85 @@
+use clap::ValueEnum;
+
+/// Terminals narrower than this are drawn in compact layout
+const COMPACT_MAX_WIDTH: u16 = 80;
+/// Terminals lower than this are drawn in compact layout
+const COMPACT_MAX_HEIGHT: u16 = 24;
+/// Terminals at least this wide are drawn in wide layout
+const WIDE_MIN_WIDTH: u16 = 180;
+
+/// Arrangement of panes, selected with `--layout` and cycled with `y` key
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+pub enum LayoutProfile {
+    /// Chosen from terminal size on every render
+    Auto,
+    /// Single pane at a time, switched with tabs
+    Compact,
+    /// Library and queue above player panel
+    Standard,
+    /// Player panel next to library and queue, with song information beside visualization
+    Wide,
+}
+
+impl LayoutProfile {
+    /// Returns profile used for terminal of given size, never `Auto`
+    pub fn resolve(self, width: u16, height: u16) -> LayoutProfile {
+        match self {
+            LayoutProfile::Auto if width < COMPACT_MAX_WIDTH || height < COMPACT_MAX_HEIGHT => {
+                LayoutProfile::Compact
+            }
+            LayoutProfile::Auto if width >= WIDE_MIN_WIDTH => LayoutProfile::Wide,
+            LayoutProfile::Auto => LayoutProfile::Standard,
+            profile => profile,
+        }
+    }
+
+    pub fn next(self) -> LayoutProfile {
+        match self {
+            LayoutProfile::Auto => LayoutProfile::Compact,
+            LayoutProfile::Compact => LayoutProfile::Standard,
+            LayoutProfile::Standard => LayoutProfile::Wide,
+            LayoutProfile::Wide => LayoutProfile::Auto,
+        }
+    }
+
+    pub fn display(self) -> &'static str {
+        match self {
+            LayoutProfile::Auto => "auto",
+            LayoutProfile::Compact => "compact",
+            LayoutProfile::Standard => "standard",
+            LayoutProfile::Wide => "wide",
+        }
+    }
+}
+
+/// Pane shown in compact layout
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CompactPane {
+    Library,
+    Queue,
+    Player,
+}
+
+impl CompactPane {
+    pub const ALL: [CompactPane; 3] = [
+        CompactPane::Library,
+        CompactPane::Queue,
+        CompactPane::Player,
+    ];
+
+    pub fn next(self) -> CompactPane {
+        match self {
+            CompactPane::Library => CompactPane::Queue,
+            CompactPane::Queue => CompactPane::Player,
+            CompactPane::Player => CompactPane::Library,
+        }
+    }
+
+    pub fn title(self) -> &'static str {
+        match self {
+            CompactPane::Library => "Library",
+            CompactPane::Queue => "Queue",
+            CompactPane::Player => "Player",
+        }
+    }
+}
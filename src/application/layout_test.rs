@@ -0,0 +1,52 @@
+#[cfg(test)]
+mod tests {
+    use crate::application::layout::{CompactPane, LayoutProfile};
+
+    #[test]
+    fn should_choose_profile_from_terminal_size() {
+        // when
+        let small_pane = LayoutProfile::Auto.resolve(70, 40);
+        let low_pane = LayoutProfile::Auto.resolve(120, 20);
+        let regular = LayoutProfile::Auto.resolve(120, 40);
+        let very_wide = LayoutProfile::Auto.resolve(240, 50);
+
+        // then
+        assert_eq!(small_pane, LayoutProfile::Compact);
+        assert_eq!(low_pane, LayoutProfile::Compact);
+        assert_eq!(regular, LayoutProfile::Standard);
+        assert_eq!(very_wide, LayoutProfile::Wide);
+    }
+
+    #[test]
+    fn should_keep_selected_profile_regardless_of_size() {
+        // when
+        let result = LayoutProfile::Wide.resolve(60, 20);
+
+        // then
+        assert_eq!(result, LayoutProfile::Wide);
+    }
+
+    #[test]
+    fn should_cycle_profiles_and_panes() {
+        // when
+        let profiles: Vec<LayoutProfile> = (0..4)
+            .scan(LayoutProfile::Auto, |profile, _| {
+                *profile = profile.next();
+                Some(*profile)
+            })
+            .collect();
+        let pane = CompactPane::Player.next();
+
+        // then
+        assert_eq!(
+            profiles,
+            [
+                LayoutProfile::Compact,
+                LayoutProfile::Standard,
+                LayoutProfile::Wide,
+                LayoutProfile::Auto
+            ]
+        );
+        assert_eq!(pane, CompactPane::Library);
+    }
+}
@@ -1,9 +1,25 @@
 //! Module containing implementation of user interface
 pub mod actions;
 pub mod app;
+pub mod layout;
+pub mod mouse;
+pub mod popup;
+pub mod status;
+pub mod theme;
 pub mod ui;
 pub mod visualization_state;
 
 pub use app::App;
 pub use app::AppActionResult;
 pub use ui::ui;
+
+#[cfg(test)]
+mod layout_test;
+#[cfg(test)]
+mod mouse_test;
+#[cfg(test)]
+mod status_test;
+#[cfg(test)]
+mod theme_test;
+#[cfg(test)]
+mod visualization_state_test;
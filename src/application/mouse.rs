@@ -0,0 +1,61 @@
+use ratatui::layout::{Position, Rect};
+
+/// Number of rows of queue table taken by column headers
+const QUEUE_HEADER_ROWS: usize = 1;
+
+/// Areas of interface elements reacting to mouse, updated on every render
+#[derive(Debug, Default, Clone, Copy)]
+pub struct LayoutAreas {
+    pub library: Rect,
+    pub queue: Rect,
+    pub player: Rect,
+    pub progress_bar: Rect,
+}
+
+/// Element of interface under mouse cursor
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MouseTarget {
+    /// Library pane, with index of visible row if cursor is inside pane borders
+    Library(Option<usize>),
+    /// Queue pane, with index of visible row if cursor is inside pane borders and below header
+    Queue(Option<usize>),
+    /// Progress bar, with fraction of its width at cursor
+    ProgressBar(f64),
+    /// Title line of player panel
+    PlayerTitle,
+    Nothing,
+}
+
+/// Finds interface element at given terminal cell
+pub fn hit_test(areas: &LayoutAreas, column: u16, row: u16) -> MouseTarget {
+    let position = Position::new(column, row);
+    if areas.library.contains(position) {
+        return MouseTarget::Library(list_row(areas.library, position));
+    }
+    if areas.queue.contains(position) {
+        let row = list_row(areas.queue, position).and_then(|r| r.checked_sub(QUEUE_HEADER_ROWS));
+        return MouseTarget::Queue(row);
+    }
+    if areas.progress_bar.contains(position) {
+        let fraction = (column - areas.progress_bar.x) as f64 / areas.progress_bar.width as f64;
+        return MouseTarget::ProgressBar(fraction);
+    }
+    if areas.player.contains(position) && row == areas.player.y {
+        return MouseTarget::PlayerTitle;
+    }
+    MouseTarget::Nothing
+}
+
+/// Returns index of visible row of bordered list, `None` if position is on border
+fn list_row(area: Rect, position: Position) -> Option<usize> {
+    let inner = Rect::new(
+        area.x + 1,
+        area.y + 1,
+        area.width.saturating_sub(2),
+        area.height.saturating_sub(2),
+    );
+    match inner.contains(position) {
+        true => Some((position.y - inner.y) as usize),
+        false => None,
+    }
+}
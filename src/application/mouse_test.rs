@@ -0,0 +1,63 @@
+#[cfg(test)]
+mod tests {
+    use ratatui::layout::Rect;
+
+    use crate::application::mouse::{hit_test, LayoutAreas, MouseTarget};
+
+    fn areas() -> LayoutAreas {
+        LayoutAreas {
+            library: Rect::new(0, 0, 40, 20),
+            queue: Rect::new(40, 0, 40, 20),
+            player: Rect::new(0, 20, 80, 10),
+            progress_bar: Rect::new(1, 28, 78, 1),
+        }
+    }
+
+    #[test]
+    fn should_find_list_rows_under_cursor() {
+        // given
+        let positions = [(5, 1), (45, 4), (39, 4), (10, 0), (60, 19), (60, 1)];
+
+        // when
+        let result: Vec<MouseTarget> = positions
+            .iter()
+            .map(|(column, row)| hit_test(&areas(), *column, *row))
+            .collect();
+
+        // then
+        assert_eq!(
+            result,
+            vec![
+                MouseTarget::Library(Some(0)),
+                MouseTarget::Queue(Some(2)),
+                MouseTarget::Library(None),
+                MouseTarget::Library(None),
+                MouseTarget::Queue(None),
+                MouseTarget::Queue(None),
+            ]
+        );
+    }
+
+    #[test]
+    fn should_find_progress_bar_fraction_and_player_title() {
+        // given
+        let positions = [(1, 28), (40, 28), (10, 20), (10, 24)];
+
+        // when
+        let result: Vec<MouseTarget> = positions
+            .iter()
+            .map(|(column, row)| hit_test(&areas(), *column, *row))
+            .collect();
+
+        // then
+        assert_eq!(
+            result,
+            vec![
+                MouseTarget::ProgressBar(0.0),
+                MouseTarget::ProgressBar(0.5),
+                MouseTarget::PlayerTitle,
+                MouseTarget::Nothing,
+            ]
+        );
+    }
+}
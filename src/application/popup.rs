@@ -0,0 +1,62 @@
+use ratatui::widgets::ListState;
+
+use crate::files::path_completion::{complete_path, directory_candidates};
+
+/// Modal window drawn over interface, receiving all key presses while open
+pub enum Popup {
+    /// List of bookmarked directories
+    Bookmarks { state: ListState },
+    /// Prompt for directory to jump to, with directories matching typed path
+    GoToPath {
+        input: String,
+        candidates: Vec<String>,
+    },
+    /// History of status bar messages
+    Messages { state: ListState },
+}
+
+impl Popup {
+    pub fn bookmarks(count: usize) -> Self {
+        Popup::Bookmarks {
+            state: first_selected(count),
+        }
+    }
+
+    pub fn messages(count: usize) -> Self {
+        Popup::Messages {
+            state: first_selected(count),
+        }
+    }
+
+    pub fn go_to_path(input: &str) -> Self {
+        Popup::GoToPath {
+            input: String::from(input),
+            candidates: directory_candidates(input),
+        }
+    }
+
+    /// Replaces typed path, refreshing matching directories
+    pub fn set_input(&mut self, text: String) {
+        if let Popup::GoToPath { input, candidates } = self {
+            *candidates = directory_candidates(&text);
+            *input = text;
+        }
+    }
+
+    /// Completes typed path with tab key
+    pub fn complete(&mut self) {
+        if let Popup::GoToPath { input, .. } = self {
+            let completed = complete_path(input);
+            self.set_input(completed);
+        }
+    }
+}
+
+/// Returns state of list with first of given number of rows selected
+fn first_selected(count: usize) -> ListState {
+    let mut state = ListState::default();
+    if count > 0 {
+        state.select(Some(0));
+    }
+    state
+}
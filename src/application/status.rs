@@ -0,0 +1,58 @@
+use chrono::Local;
+use log::Level;
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+/// Time for which message is shown in status bar
+pub const MESSAGE_TIMEOUT: Duration = Duration::from_secs(5);
+/// Number of messages kept for history popup
+const MAX_HISTORY: usize = 100;
+
+/// Message about result of user action or background operation
+pub struct StatusMessage {
+    pub level: Level,
+    pub text: String,
+    /// Wall clock time of message, shown in history
+    pub time: String,
+    created: Instant,
+}
+
+/// Messages shown in status bar, newest first
+#[derive(Default)]
+pub struct StatusMessages {
+    history: VecDeque<StatusMessage>,
+}
+
+impl StatusMessages {
+    pub fn push(&mut self, level: Level, text: &str) {
+        self.history.push_front(StatusMessage {
+            level,
+            text: String::from(text),
+            time: Local::now().format("%H:%M:%S").to_string(),
+            created: Instant::now(),
+        });
+        self.history.truncate(MAX_HISTORY);
+    }
+
+    /// Returns newest message if it is younger than given timeout
+    pub fn current(&self, timeout: Duration) -> Option<&StatusMessage> {
+        self.history
+            .front()
+            .filter(|m| m.created.elapsed() < timeout)
+    }
+
+    /// Returns all kept messages, newest first
+    pub fn history(&self) -> impl Iterator<Item = &StatusMessage> {
+        self.history.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.history.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.history.is_empty()
+    }
+}
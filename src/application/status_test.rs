@@ -0,0 +1,41 @@
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use log::Level;
+
+    use crate::application::status::{StatusMessages, MESSAGE_TIMEOUT};
+
+    #[test]
+    fn should_show_newest_message_until_timeout() {
+        // given
+        let mut messages = StatusMessages::default();
+        messages.push(Level::Info, "Added 12 tracks");
+        messages.push(Level::Error, "Cannot open file");
+
+        // when
+        let current = messages.current(MESSAGE_TIMEOUT).map(|m| m.text.as_str());
+        let expired = messages.current(Duration::ZERO).map(|m| m.text.as_str());
+
+        // then
+        assert_eq!(current, Some("Cannot open file"));
+        assert_eq!(expired, None);
+    }
+
+    #[test]
+    fn should_keep_limited_history_newest_first() {
+        // given
+        let mut messages = StatusMessages::default();
+
+        // when
+        for i in 0..150 {
+            messages.push(Level::Info, &format!("message {i}"));
+        }
+
+        // then
+        assert_eq!(messages.len(), 100);
+        let texts: Vec<&str> = messages.history().map(|m| m.text.as_str()).collect();
+        assert_eq!(texts.first(), Some(&"message 149"));
+        assert_eq!(texts.last(), Some(&"message 50"));
+    }
+}
@@ -0,0 +1,203 @@
+use log::warn;
+use ratatui::style::Color;
+use std::{fs, path::Path, str::FromStr};
+
+/// Accent colors cycled with `c` key
+const ACCENT_COLORS: [Color; 5] = [
+    Color::Cyan,
+    Color::Red,
+    Color::Magenta,
+    Color::Blue,
+    Color::Green,
+];
+
+/// Colors used to draw interface
+#[derive(Debug, Clone, PartialEq)]
+pub struct Theme {
+    pub name: String,
+    pub foreground: Color,
+    pub background: Color,
+    /// Border of panes without focus
+    pub border: Color,
+    /// Border of focused pane
+    pub focused_border: Color,
+    /// Background of selected list row
+    pub highlight: Color,
+    /// Text of selected list row
+    pub highlight_text: Color,
+    /// Less important text, like ratings or playlist rules
+    pub secondary_text: Color,
+    pub gauge: Color,
+    pub gauge_label: Color,
+    /// Colors of spectrum from lowest to highest frequency, at least one
+    pub spectrum: Vec<Color>,
+    pub log_error: Color,
+    pub log_warning: Color,
+    pub log_info: Color,
+    pub log_debug: Color,
+    pub log_trace: Color,
+}
+
+impl Theme {
+    pub fn dark() -> Self {
+        Theme {
+            name: String::from("dark"),
+            foreground: Color::Reset,
+            background: Color::Reset,
+            border: Color::White,
+            focused_border: Color::Cyan,
+            highlight: Color::Cyan,
+            highlight_text: Color::Reset,
+            secondary_text: Color::DarkGray,
+            gauge: Color::Cyan,
+            gauge_label: Color::White,
+            spectrum: vec![Color::Cyan],
+            log_error: Color::Red,
+            log_warning: Color::Yellow,
+            log_info: Color::Blue,
+            log_debug: Color::Green,
+            log_trace: Color::Gray,
+        }
+    }
+
+    pub fn light() -> Self {
+        Theme {
+            name: String::from("light"),
+            foreground: Color::Black,
+            background: Color::White,
+            border: Color::DarkGray,
+            focused_border: Color::Blue,
+            highlight: Color::Blue,
+            highlight_text: Color::White,
+            secondary_text: Color::Gray,
+            gauge: Color::Blue,
+            gauge_label: Color::Black,
+            spectrum: vec![Color::Blue, Color::Magenta, Color::Red],
+            log_error: Color::Red,
+            log_warning: Color::Rgb(176, 112, 0),
+            log_info: Color::Blue,
+            log_debug: Color::Green,
+            log_trace: Color::DarkGray,
+        }
+    }
+
+    /// Parses theme file with `<key> = <color>` lines, missing keys are taken from dark theme.
+    /// Colors can be given as names, `#rrggbb` or 256-color palette indexes.
+    pub fn parse(name: &str, content: &str) -> Result<Self, String> {
+        let mut theme = Theme {
+            name: String::from(name),
+            ..Theme::dark()
+        };
+        for (number, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| format!("Missing '=' in line {}", number + 1))?;
+            let (key, value) = (key.trim(), value.trim());
+            if key == "spectrum" {
+                theme.spectrum = value
+                    .split(',')
+                    .map(|c| parse_color(c.trim()))
+                    .collect::<Result<_, _>>()?;
+                if theme.spectrum.is_empty() {
+                    return Err(String::from("Spectrum needs at least one color"));
+                }
+                continue;
+            }
+            let color = parse_color(value)?;
+            match key {
+                "foreground" => theme.foreground = color,
+                "background" => theme.background = color,
+                "border" => theme.border = color,
+                "focused_border" => theme.focused_border = color,
+                "highlight" => theme.highlight = color,
+                "highlight_text" => theme.highlight_text = color,
+                "secondary_text" => theme.secondary_text = color,
+                "gauge" => theme.gauge = color,
+                "gauge_label" => theme.gauge_label = color,
+                "log_error" => theme.log_error = color,
+                "log_warning" => theme.log_warning = color,
+                "log_info" => theme.log_info = color,
+                "log_debug" => theme.log_debug = color,
+                "log_trace" => theme.log_trace = color,
+                _ => return Err(format!("Unknown theme key: {key}")),
+            }
+        }
+        Ok(theme)
+    }
+
+    /// Returns theme with accent elements drawn in given color
+    pub fn with_accent(&self, color: Color) -> Self {
+        Theme {
+            focused_border: color,
+            highlight: color,
+            gauge: color,
+            spectrum: vec![color],
+            ..self.clone()
+        }
+    }
+
+    /// Returns theme with next of predefined accent colors
+    pub fn with_next_accent(&self) -> Self {
+        let next = ACCENT_COLORS
+            .iter()
+            .position(|c| *c == self.highlight)
+            .map(|i| ACCENT_COLORS[(i + 1) % ACCENT_COLORS.len()])
+            .unwrap_or(ACCENT_COLORS[0]);
+        self.with_accent(next)
+    }
+
+    /// Returns spectrum color at given fraction [0..1] of frequency range.
+    /// RGB colors are interpolated, other colors are taken from the nearest gradient stop.
+    pub fn spectrum_color(&self, fraction: f64) -> Color {
+        let last = self.spectrum.len() - 1;
+        let position = fraction.clamp(0.0, 1.0) * last as f64;
+        let (lower, upper) = (position.floor() as usize, position.ceil() as usize);
+        match (self.spectrum[lower], self.spectrum[upper]) {
+            (Color::Rgb(r1, g1, b1), Color::Rgb(r2, g2, b2)) => {
+                let t = position - lower as f64;
+                let mix = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * t).round() as u8;
+                Color::Rgb(mix(r1, r2), mix(g1, g2), mix(b1, b2))
+            }
+            _ => self.spectrum[position.round() as usize],
+        }
+    }
+}
+
+fn parse_color(value: &str) -> Result<Color, String> {
+    Color::from_str(value).map_err(|_| format!("Invalid color: {value}"))
+}
+
+/// Returns built-in themes followed by themes read from `*.theme` files in given directory
+pub fn load_themes(directory: &Path) -> Vec<Theme> {
+    let mut themes = vec![Theme::dark(), Theme::light()];
+    let Ok(entries) = fs::read_dir(directory) else {
+        return themes;
+    };
+    let mut paths: Vec<_> = entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|e| e == "theme"))
+        .collect();
+    paths.sort();
+    for path in paths {
+        let name = path
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let theme = fs::read_to_string(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|content| Theme::parse(&name, &content));
+        match theme {
+            Ok(theme) => {
+                themes.retain(|t| t.name != theme.name);
+                themes.push(theme);
+            }
+            Err(e) => warn!("Skipping invalid theme {}: {e}", path.display()),
+        }
+    }
+    themes
+}
@@ -0,0 +1,112 @@
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use ratatui::style::Color;
+    use tempdir::TempDir;
+
+    use crate::application::theme::{load_themes, Theme};
+
+    #[test]
+    fn should_parse_theme_with_named_rgb_and_indexed_colors() {
+        // given
+        let content = "# solarized\nbackground = #002b36\nforeground = 244\nborder = darkgray\nspectrum = #268bd2, #d33682\n";
+
+        // when
+        let result = Theme::parse("solarized", content);
+
+        // then
+        assert_eq!(
+            result,
+            Ok(Theme {
+                name: String::from("solarized"),
+                background: Color::Rgb(0, 43, 54),
+                foreground: Color::Indexed(244),
+                border: Color::DarkGray,
+                spectrum: vec![Color::Rgb(38, 139, 210), Color::Rgb(211, 54, 130)],
+                ..Theme::dark()
+            })
+        );
+    }
+
+    #[test]
+    fn should_reject_invalid_theme() {
+        // given
+        let contents = [
+            "border = notacolor",
+            "shadow = red",
+            "border red",
+            "spectrum = ",
+        ];
+
+        // when
+        let result: Vec<bool> = contents
+            .iter()
+            .map(|c| Theme::parse("broken", c).is_err())
+            .collect();
+
+        // then
+        assert_eq!(result, vec![true, true, true, true]);
+    }
+
+    #[test]
+    fn should_interpolate_rgb_spectrum_gradient() {
+        // given
+        let theme = Theme {
+            spectrum: vec![Color::Rgb(0, 0, 0), Color::Rgb(200, 100, 50)],
+            ..Theme::dark()
+        };
+
+        // when
+        let result = [
+            theme.spectrum_color(0.0),
+            theme.spectrum_color(0.5),
+            theme.spectrum_color(1.0),
+        ];
+
+        // then
+        assert_eq!(
+            result,
+            [
+                Color::Rgb(0, 0, 0),
+                Color::Rgb(100, 50, 25),
+                Color::Rgb(200, 100, 50)
+            ]
+        );
+    }
+
+    #[test]
+    fn should_cycle_accent_colors() {
+        // given
+        let theme = Theme::dark();
+
+        // when
+        let result = theme.with_next_accent();
+
+        // then
+        assert_eq!(result.highlight, Color::Red);
+        assert_eq!(result.focused_border, Color::Red);
+        assert_eq!(result.spectrum, vec![Color::Red]);
+        assert_eq!(result.border, theme.border);
+    }
+
+    #[test]
+    fn should_load_theme_files_after_built_in_themes() -> Result<(), Box<dyn std::error::Error>> {
+        // given
+        let tmp_dir = TempDir::new("themes")?;
+        fs::write(tmp_dir.path().join("ocean.theme"), "highlight = #0000ff")?;
+        fs::write(tmp_dir.path().join("light.theme"), "highlight = green")?;
+        fs::write(tmp_dir.path().join("broken.theme"), "highlight = ???")?;
+        fs::write(tmp_dir.path().join("notes.txt"), "not a theme")?;
+
+        // when
+        let result = load_themes(tmp_dir.path());
+
+        // then
+        let names: Vec<&str> = result.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, vec!["dark", "light", "ocean"]);
+        assert_eq!(result[1].highlight, Color::Green);
+
+        Ok(())
+    }
+}
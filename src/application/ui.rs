@@ -1,15 +1,35 @@
-use super::{app::VisualizationStyle, App};
-use crate::queue::SongFile;
-use crate::{files::FileEntry, player::Mp3Player};
+use super::{
+    app::{LibraryView, VisualizationStyle},
+    layout::{CompactPane, LayoutProfile},
+    mouse::LayoutAreas,
+    popup::Popup,
+    status::{StatusMessages, MESSAGE_TIMEOUT},
+    theme::Theme,
+    visualization_state::{BarChartData, SpectrogramData, VuMeterData},
+    App,
+};
+use crate::history::statistics::StatisticsRow;
+use crate::library::{
+    ratings::{RatingFilter, TrackRating, PENDING_RATING},
+    smart_playlist::SmartPlaylist,
+};
+use crate::player::duration_formatter::{DurationFormat, DurationFormatter};
+use crate::podcast::PodcastView;
+use crate::queue::queue_view::{QueueColumn, QueueView};
+use crate::{
+    files::{listing::SortOrder, preview::Preview, FileEntry},
+    player::Mp3Player,
+};
+use log::Level;
 use ratatui::style::Style;
 use ratatui::{
-    layout::{Constraint, Direction, Layout, Rect},
+    layout::{Alignment, Constraint, Direction, Flex, Layout, Rect},
     style::{Color, Modifier},
     symbols,
     text::{Line, Span},
     widgets::{
-        Axis, BarChart, Block, BorderType, Borders, Chart, Dataset, Gauge, GraphType, List,
-        ListItem, Paragraph,
+        Axis, Bar, BarChart, BarGroup, Block, BorderType, Borders, Cell, Chart, Clear, Dataset,
+        Gauge, GraphType, List, ListItem, Paragraph, Row, Table, TableState, Tabs,
     },
     Frame,
 };
@@ -18,21 +38,227 @@ use tui_logger::TuiLoggerWidget;
 
 /// Render UI based on application state
 pub fn ui(f: &mut Frame, app: &mut App) {
-    let main_view_constraint = Constraint::Max(60);
-    let now_playing_view_constraint = Constraint::Percentage(40);
+    let area = f.area();
+    let active_layout = app.state.layout_profile.resolve(area.width, area.height);
+    app.state.active_layout = active_layout;
+    app.state.layout = LayoutAreas::default();
 
-    let chunks = Layout::default()
-        .direction(Direction::Vertical)
-        .margin(1)
-        .constraints([main_view_constraint, now_playing_view_constraint].as_ref())
-        .split(f.area());
+    f.render_widget(
+        Block::default().style(
+            Style::default()
+                .fg(app.state.theme.foreground)
+                .bg(app.state.theme.background),
+        ),
+        area,
+    );
+    let [content_area, status_bar_area] =
+        Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).areas(area);
+    draw_status_bar(f, app, status_bar_area);
+    let area = content_area;
 
-    let (main_view_area, player_area) = (chunks[0], chunks[1]);
+    match active_layout {
+        LayoutProfile::Compact => render_compact_view(f, area, app),
+        LayoutProfile::Wide => {
+            let chunks = Layout::default()
+                .direction(Direction::Horizontal)
+                .margin(1)
+                .constraints([Constraint::Percentage(55), Constraint::Percentage(45)])
+                .split(area);
+            render_main_view(f, chunks[0], app);
+            draw_player_panel(f, app, chunks[1], true);
+        }
+        LayoutProfile::Auto | LayoutProfile::Standard => {
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .margin(1)
+                .constraints([Constraint::Max(60), Constraint::Percentage(40)])
+                .split(area);
+            render_main_view(f, chunks[0], app);
+            draw_player_panel(f, app, chunks[1], false);
+        }
+    }
 
-    render_main_view(f, main_view_area, app);
+    if let Some(popup) = &mut app.state.popup {
+        draw_popup(
+            f,
+            popup,
+            &app.bookmarks.directories,
+            &app.state.messages,
+            &app.state.theme,
+            area,
+        );
+    }
+}
 
-    // Player
-    draw_player_panel(f, app, player_area);
+/// Draws latest message, or key hints when there is none, with queue summary on the right
+fn draw_status_bar(f: &mut Frame, app: &App, area: Rect) {
+    let theme = &app.state.theme;
+    let message = match app.state.messages.current(MESSAGE_TIMEOUT) {
+        Some(message) => Span::styled(
+            format!(" {}", message.text),
+            Style::default().fg(log_level_color(message.level, theme)),
+        ),
+        None => Span::styled(
+            " h: help  M: messages  q: quit",
+            Style::default().fg(theme.secondary_text),
+        ),
+    };
+    let position = match app.queue_view.now_playing {
+        Some(index) => format!("{}/{}", index + 1, app.queue_view.items.len()),
+        None => format!("{}", app.queue_view.items.len()),
+    };
+    let summary = format!(
+        "filter: {} | queue: {position} ",
+        app.state.rating_filter.display()
+    );
+    let [message_area, summary_area] = Layout::horizontal([
+        Constraint::Min(0),
+        Constraint::Length(summary.chars().count() as u16),
+    ])
+    .areas(area);
+    f.render_widget(Paragraph::new(Line::from(message)), message_area);
+    f.render_widget(
+        Paragraph::new(summary).style(Style::default().fg(theme.secondary_text)),
+        summary_area,
+    );
+}
+
+fn log_level_color(level: Level, theme: &Theme) -> Color {
+    match level {
+        Level::Error => theme.log_error,
+        Level::Warn => theme.log_warning,
+        Level::Info => theme.foreground,
+        Level::Debug => theme.log_debug,
+        Level::Trace => theme.log_trace,
+    }
+}
+
+/// Draws popup in the middle of screen, over other panes
+fn draw_popup(
+    f: &mut Frame,
+    popup: &mut Popup,
+    bookmarks: &[String],
+    messages: &StatusMessages,
+    theme: &Theme,
+    area: Rect,
+) {
+    let height = match popup {
+        Popup::Bookmarks { .. } => bookmarks.len() as u16 + 2,
+        Popup::GoToPath { candidates, .. } => candidates.len() as u16 + 3,
+        Popup::Messages { .. } => messages.len() as u16 + 2,
+    };
+    let [popup_area] = Layout::vertical([Constraint::Length(height.clamp(3, 20))])
+        .flex(Flex::Center)
+        .areas(area);
+    let [popup_area] = Layout::horizontal([Constraint::Percentage(60)])
+        .flex(Flex::Center)
+        .areas(popup_area);
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Double)
+        .border_style(Style::default().fg(theme.focused_border))
+        .style(Style::default().fg(theme.foreground).bg(theme.background));
+    f.render_widget(Clear, popup_area);
+    match popup {
+        Popup::Bookmarks { state } => {
+            let items: Vec<ListItem> = match bookmarks.is_empty() {
+                true => vec![ListItem::new("No bookmarks, add current directory with B")
+                    .style(Style::default().fg(theme.secondary_text))],
+                false => bookmarks
+                    .iter()
+                    .map(|b| ListItem::new(b.as_str()))
+                    .collect(),
+            };
+            f.render_stateful_widget(
+                List::new(items)
+                    .block(block.title("Bookmarks (Enter: jump, d: remove, Esc: close)"))
+                    .highlight_style(
+                        Style::default()
+                            .bg(theme.highlight)
+                            .fg(theme.highlight_text)
+                            .add_modifier(Modifier::BOLD),
+                    )
+                    .highlight_symbol("> "),
+                popup_area,
+                state,
+            );
+        }
+        Popup::GoToPath { input, candidates } => {
+            let mut lines = vec![Line::from(format!("{input}\u{2588}")), Line::from("")];
+            lines.extend(candidates.iter().map(|c| {
+                Line::from(Span::styled(
+                    c.as_str(),
+                    Style::default().fg(theme.secondary_text),
+                ))
+            }));
+            f.render_widget(
+                Paragraph::new(lines).block(block.title("Go to (Tab: complete, Esc: close)")),
+                popup_area,
+            );
+        }
+        Popup::Messages { state } => {
+            let items: Vec<ListItem> = match messages.is_empty() {
+                true => vec![ListItem::new("No messages yet")
+                    .style(Style::default().fg(theme.secondary_text))],
+                false => messages
+                    .history()
+                    .map(|m| {
+                        ListItem::new(Line::from(vec![
+                            Span::styled(
+                                format!("{} ", m.time),
+                                Style::default().fg(theme.secondary_text),
+                            ),
+                            Span::styled(
+                                m.text.as_str(),
+                                Style::default().fg(log_level_color(m.level, theme)),
+                            ),
+                        ]))
+                    })
+                    .collect(),
+            };
+            f.render_stateful_widget(
+                List::new(items)
+                    .block(block.title("Messages (Esc: close)"))
+                    .highlight_style(
+                        Style::default()
+                            .bg(theme.highlight)
+                            .fg(theme.highlight_text),
+                    ),
+                popup_area,
+                state,
+            );
+        }
+    }
+}
+
+/// Renders single pane selected with tabs on the first line
+fn render_compact_view(f: &mut Frame, area: Rect, app: &mut App) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1), Constraint::Min(0)])
+        .split(area);
+    let (tabs_area, pane_area) = (chunks[0], chunks[1]);
+    let theme = &app.state.theme;
+    let selected = CompactPane::ALL
+        .iter()
+        .position(|p| *p == app.state.compact_pane)
+        .unwrap_or(0);
+    f.render_widget(
+        Tabs::new(CompactPane::ALL.iter().map(|p| p.title()))
+            .select(selected)
+            .style(Style::default().fg(theme.secondary_text))
+            .highlight_style(
+                Style::default()
+                    .fg(theme.focused_border)
+                    .add_modifier(Modifier::BOLD),
+            ),
+        tabs_area,
+    );
+    match app.state.compact_pane {
+        CompactPane::Library => draw_library_pane(f, app, pane_area),
+        CompactPane::Queue => draw_queue_pane(f, app, pane_area),
+        CompactPane::Player => draw_player_panel(f, app, pane_area, false),
+    }
 }
 
 fn render_main_view(f: &mut Frame, area: Rect, app: &mut App) {
@@ -61,54 +287,198 @@ fn render_main_view(f: &mut Frame, area: Rect, app: &mut App) {
 
     let (file_viewer_area, queue_view_area, help_area, logs_area) =
         (main_view[0], main_view[1], main_view[2], main_view[3]);
+    draw_library_pane(f, app, file_viewer_area);
+    draw_queue_pane(f, app, queue_view_area);
 
-    // File explorer
-    f.render_stateful_widget(
-        draw_file_list(
-            &app.file_list.current_directory,
-            &app.file_list.items,
-            app.state.color_style,
+    // Help
+    f.render_widget(
+        draw_help_panel(
             app.state.file_viewer_focused,
+            app.state.library_view,
+            &app.state.theme,
         ),
-        file_viewer_area,
-        &mut app.file_list.state,
+        help_area,
     );
 
-    // Playing queue
+    // Logs
+    f.render_widget(draw_log_view(&app.state.theme), logs_area);
+}
+
+/// Draws list of library view selected in app state
+fn draw_library_pane(f: &mut Frame, app: &mut App, area: Rect) {
+    let area = match app.state.preview_visible && app.state.library_view == LibraryView::Files {
+        true => {
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Min(0), Constraint::Length(10)])
+                .split(area);
+            let preview = app
+                .file_list
+                .get_selected_file_entry()
+                .map(|entry| app.previews.get(entry));
+            f.render_widget(draw_preview(preview, &app.state.theme), chunks[1]);
+            chunks[0]
+        }
+        false => area,
+    };
+    app.state.layout.library = area;
+    let file_ratings: Vec<String> = app
+        .file_list
+        .items
+        .iter()
+        .map(|e| match e.is_audio() {
+            true => display_rating(app.ratings.get_loaded(&e.path)),
+            false => String::new(),
+        })
+        .collect();
+    let mut file_list_title = app.file_list.current_directory.clone();
+    if app.state.rating_filter != RatingFilter::Any {
+        file_list_title.push_str(&format!(" [{}]", app.state.rating_filter.display()));
+    }
+    if app.file_list.options.sort_order != SortOrder::Name {
+        file_list_title.push_str(&format!(
+            " [by {}]",
+            app.file_list.options.sort_order.display()
+        ));
+    }
+
+    match app.state.library_view {
+        LibraryView::Files => f.render_stateful_widget(
+            draw_file_list(
+                &file_list_title,
+                &app.file_list.items,
+                &file_ratings,
+                &app.state.theme,
+                app.state.file_viewer_focused,
+            ),
+            area,
+            &mut app.file_list.state,
+        ),
+        LibraryView::Podcasts => f.render_stateful_widget(
+            draw_podcast_list(
+                &app.podcast_view,
+                &app.state.theme,
+                app.state.file_viewer_focused,
+            ),
+            area,
+            &mut app.podcast_view.state,
+        ),
+        LibraryView::Statistics => f.render_stateful_widget(
+            draw_statistics_list(
+                app.statistics_view.section.title(),
+                &app.statistics_view.rows,
+                &app.state.theme,
+                app.state.file_viewer_focused,
+            ),
+            area,
+            &mut app.statistics_view.state,
+        ),
+        LibraryView::SmartPlaylists => f.render_stateful_widget(
+            draw_smart_playlist_list(
+                &app.smart_playlist_view.playlists,
+                &app.state.theme,
+                app.state.file_viewer_focused,
+            ),
+            area,
+            &mut app.smart_playlist_view.state,
+        ),
+    }
+}
+
+/// Returns rating column text, with placeholder for rating still being read
+fn display_rating(rating: Option<TrackRating>) -> String {
+    rating.map_or_else(|| String::from(PENDING_RATING), |r| r.display())
+}
+
+fn draw_queue_pane(f: &mut Frame, app: &mut App, area: Rect) {
+    app.state.layout.queue = area;
+    let queue_ratings: Vec<String> = app
+        .queue_view
+        .items
+        .iter()
+        .map(|s| match s.is_stream() {
+            true => String::new(),
+            false => display_rating(app.ratings.get_loaded(&s.file_entry.path)),
+        })
+        .collect();
+    let footer = format!(
+        " {} items | total {} | remaining {} ",
+        app.queue_view.items.len(),
+        app.queue_view
+            .total_duration()
+            .format(DurationFormat::HhMmSs),
+        app.queue_view
+            .remaining_duration(app.player.get_elapsed())
+            .format(DurationFormat::HhMmSs),
+    );
+    // Queue keeps list state shared with other panes, table state is derived from it
+    let mut table_state = TableState::default()
+        .with_selected(app.queue_view.state.selected())
+        .with_offset(app.queue_view.state.offset());
     f.render_stateful_widget(
-        draw_queue_list(
+        draw_queue_table(
             "Queue",
-            &app.queue_view.items,
-            app.queue_view.now_playing,
-            app.state.color_style,
+            &footer,
+            &app.queue_view,
+            &queue_ratings,
+            &app.state.queue_columns,
+            &app.state.theme,
             !app.state.file_viewer_focused,
         ),
-        queue_view_area,
-        &mut app.queue_view.state,
+        area,
+        &mut table_state,
     );
+    *app.queue_view.state.offset_mut() = table_state.offset();
+}
 
-    // Help
-    f.render_widget(draw_help_panel(app.state.file_viewer_focused), help_area);
-
-    // Logs
-    f.render_widget(draw_log_view(), logs_area);
+/// Draws details of selected file viewer entry, `None` when nothing is selected
+/// and `Some(None)` while preview is loading
+fn draw_preview<'a>(preview: Option<Option<Preview>>, theme: &Theme) -> Paragraph<'a> {
+    let lines: Vec<Line> = match preview {
+        Some(Some(preview)) => preview.display().into_iter().map(Line::from).collect(),
+        Some(None) => vec![Line::from("Loading...")],
+        None => vec![],
+    };
+    Paragraph::new(lines)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(theme.border))
+                .title("Details"),
+        )
+        .style(Style::default().fg(theme.secondary_text))
 }
 
 fn draw_file_list<'a>(
     title_path: &'a str,
     files: &'a [FileEntry],
-    color: Color,
+    ratings: &[String],
+    theme: &Theme,
     focused: bool,
 ) -> List<'a> {
     let items: Vec<ListItem> = files
         .iter()
-        .map(|x| {
-            ListItem::new(Line::from(Span::styled(&x.name, Style::default())))
-                .style(Style::default().remove_modifier(Modifier::BOLD))
+        .zip(ratings)
+        .map(|(x, rating)| {
+            // Files which cannot be played are listed dimmed
+            let name_style = match x.is_file && !x.is_audio() {
+                true => Style::default()
+                    .fg(theme.secondary_text)
+                    .add_modifier(Modifier::DIM),
+                false => Style::default(),
+            };
+            ListItem::new(Line::from(vec![
+                Span::styled(&x.name, name_style),
+                Span::styled(
+                    format!(" {rating}"),
+                    Style::default().fg(theme.secondary_text),
+                ),
+            ]))
+            .style(Style::default().remove_modifier(Modifier::BOLD))
         })
         .collect();
 
-    let (border_type, border_color) = get_border_style(focused, color);
+    let (border_type, border_color) = get_border_style(focused, theme);
 
     List::new(items)
         .block(
@@ -119,38 +489,50 @@ fn draw_file_list<'a>(
                 .title(title_path)
                 .style(Style::default().add_modifier(Modifier::BOLD)),
         )
-        .highlight_style(Style::default().bg(color).add_modifier(Modifier::BOLD))
+        .highlight_style(
+            Style::default()
+                .bg(theme.highlight)
+                .fg(theme.highlight_text)
+                .add_modifier(Modifier::BOLD),
+        )
         .highlight_symbol("> ")
 }
 
-fn draw_queue_list<'a>(
-    title_path: &'a str,
-    items: &'a [SongFile],
-    now_playing: Option<usize>,
-    color: Color,
-    focused: bool,
-) -> List<'a> {
-    let items: Vec<ListItem> = items
+fn draw_podcast_list(view: &PodcastView, theme: &Theme, focused: bool) -> List<'static> {
+    let items: Vec<ListItem> = view
+        .episodes
         .iter()
-        .enumerate()
-        .map(|(index, x)| {
-            if let Some(i) = now_playing {
-                if i == index {
-                    return ListItem::new(Line::from(Span::styled(
-                        format!("\u{25B6} {}", x.display_short()),
-                        Style::default(),
-                    )));
+        .map(|episode| {
+            let state = view.episode_state(episode);
+            let marker = match (state, view.is_downloading(episode)) {
+                (_, true) => "\u{2193}",
+                (Some(s), false) if s.played => "\u{2713}",
+                (Some(s), false) if !s.position.is_zero() => "\u{25D1}",
+                _ if episode.is_downloaded() => "\u{25CF}",
+                _ => " ",
+            };
+            let resume = match state {
+                Some(s) if !s.played && !s.position.is_zero() => {
+                    format!(" [{}]", s.position.format(DurationFormat::MmSs))
                 }
-            }
+                _ => String::new(),
+            };
             ListItem::new(Line::from(Span::styled(
-                format!("  {}", x.display_short()),
+                format!(
+                    "{marker} {} - {}{resume}",
+                    episode.feed_title, episode.title
+                ),
                 Style::default(),
             )))
             .style(Style::default().remove_modifier(Modifier::BOLD))
         })
         .collect();
 
-    let (border_type, border_color) = get_border_style(focused, color);
+    let (border_type, border_color) = get_border_style(focused, theme);
+    let title = match view.is_refreshing() {
+        true => "Podcasts (refreshing...)",
+        false => "Podcasts",
+    };
 
     List::new(items)
         .block(
@@ -158,41 +540,224 @@ fn draw_queue_list<'a>(
                 .borders(Borders::ALL)
                 .border_type(border_type)
                 .border_style(Style::default().fg(border_color))
-                .title(title_path)
+                .title(title)
                 .style(Style::default().add_modifier(Modifier::BOLD)),
         )
-        .highlight_style(Style::default().bg(color).add_modifier(Modifier::BOLD))
+        .highlight_style(
+            Style::default()
+                .bg(theme.highlight)
+                .fg(theme.highlight_text)
+                .add_modifier(Modifier::BOLD),
+        )
         .highlight_symbol("> ")
 }
 
-fn get_border_style(focused: bool, accent_color: Color) -> (BorderType, Color) {
-    match focused {
-        true => (BorderType::Double, accent_color),
-        false => (BorderType::Plain, Color::White),
-    }
+fn draw_statistics_list<'a>(
+    section_title: &str,
+    rows: &'a [StatisticsRow],
+    theme: &Theme,
+    focused: bool,
+) -> List<'a> {
+    let items: Vec<ListItem> = rows
+        .iter()
+        .map(|row| {
+            ListItem::new(Line::from(Span::styled(&row.label, Style::default())))
+                .style(Style::default().remove_modifier(Modifier::BOLD))
+        })
+        .collect();
+
+    let (border_type, border_color) = get_border_style(focused, theme);
+
+    List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_type(border_type)
+                .border_style(Style::default().fg(border_color))
+                .title(format!("\u{2190} {section_title} \u{2192}"))
+                .style(Style::default().add_modifier(Modifier::BOLD)),
+        )
+        .highlight_style(
+            Style::default()
+                .bg(theme.highlight)
+                .fg(theme.highlight_text)
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol("> ")
 }
 
-fn draw_player_panel(f: &mut Frame, app: &mut App, area: Rect) {
-    let view = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints(
-            [
-                Constraint::Percentage(40),
-                Constraint::Percentage(58),
-                Constraint::Percentage(2),
-            ]
-            .as_ref(),
+fn draw_smart_playlist_list<'a>(
+    playlists: &'a [SmartPlaylist],
+    theme: &Theme,
+    focused: bool,
+) -> List<'a> {
+    let items: Vec<ListItem> = playlists
+        .iter()
+        .map(|playlist| {
+            ListItem::new(Line::from(vec![
+                Span::styled(&playlist.name, Style::default()),
+                Span::styled(
+                    format!(" {}", playlist.definition),
+                    Style::default().fg(theme.secondary_text),
+                ),
+            ]))
+            .style(Style::default().remove_modifier(Modifier::BOLD))
+        })
+        .collect();
+
+    let (border_type, border_color) = get_border_style(focused, theme);
+
+    List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_type(border_type)
+                .border_style(Style::default().fg(border_color))
+                .title("Smart playlists")
+                .style(Style::default().add_modifier(Modifier::BOLD)),
         )
-        .margin(1)
-        .split(area);
+        .highlight_style(
+            Style::default()
+                .bg(theme.highlight)
+                .fg(theme.highlight_text)
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol("> ")
+}
+
+/// Draws queue as table of selected columns, with now playing marker in first column
+/// and queue length in bottom border
+fn draw_queue_table<'a>(
+    title: &'a str,
+    footer: &str,
+    queue: &QueueView,
+    ratings: &[String],
+    columns: &[QueueColumn],
+    theme: &Theme,
+    focused: bool,
+) -> Table<'a> {
+    let now_playing = queue.now_playing;
+    let rows: Vec<Row> = queue
+        .items
+        .iter()
+        .zip(ratings)
+        .enumerate()
+        .map(|(index, (song, rating))| {
+            let marker = match (now_playing == Some(index), song.missing) {
+                (_, true) => "\u{2717}",
+                (true, false) => "\u{25B6}",
+                (false, false) => " ",
+            };
+            let mut cells = vec![Cell::from(marker)];
+            cells.extend(columns.iter().map(|column| {
+                let cell = Cell::from(column.value(song, rating));
+                match column {
+                    QueueColumn::Rating => cell.style(Style::default().fg(theme.secondary_text)),
+                    _ => cell,
+                }
+            }));
+            let row = Row::new(cells);
+            match (now_playing == Some(index), song.missing) {
+                (_, true) => row.style(
+                    Style::default()
+                        .fg(theme.secondary_text)
+                        .remove_modifier(Modifier::BOLD)
+                        .add_modifier(Modifier::CROSSED_OUT),
+                ),
+                (true, false) => row,
+                (false, false) => row.style(Style::default().remove_modifier(Modifier::BOLD)),
+            }
+        })
+        .collect();
+    let mut widths = vec![Constraint::Length(1)];
+    widths.extend(columns.iter().map(|column| match column {
+        QueueColumn::Track => Constraint::Length(3),
+        QueueColumn::Artist | QueueColumn::Album => Constraint::Fill(1),
+        QueueColumn::Title => Constraint::Fill(2),
+        QueueColumn::Duration => Constraint::Length(5),
+        QueueColumn::Rating => Constraint::Length(7),
+    }));
+    let header = Row::new(
+        [""].into_iter()
+            .chain(columns.iter().map(|column| column.header())),
+    )
+    .style(Style::default().fg(theme.secondary_text));
 
-    let (song_info_area, audio_spectrum_area, progress_bar_area) = (view[0], view[1], view[2]);
+    let (border_type, border_color) = get_border_style(focused, theme);
+
+    Table::new(rows, widths)
+        .header(header)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_type(border_type)
+                .border_style(Style::default().fg(border_color))
+                .title(title)
+                .title_bottom(Line::from(footer.to_string()).right_aligned())
+                .style(Style::default().add_modifier(Modifier::BOLD)),
+        )
+        .row_highlight_style(
+            Style::default()
+                .bg(theme.highlight)
+                .fg(theme.highlight_text)
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol("> ")
+}
+
+fn get_border_style(focused: bool, theme: &Theme) -> (BorderType, Color) {
+    match focused {
+        true => (BorderType::Double, theme.focused_border),
+        false => (BorderType::Plain, theme.border),
+    }
+}
+
+/// Draws player panel, with song information above visualization
+/// or beside it when `side_by_side` is set
+fn draw_player_panel(f: &mut Frame, app: &mut App, area: Rect, side_by_side: bool) {
+    let (song_info_area, audio_spectrum_area, progress_bar_area) = match side_by_side {
+        true => {
+            let progress_height = match app.state.waveform_seekbar {
+                true => 3,
+                false => 1,
+            };
+            let view = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Min(0), Constraint::Length(progress_height)])
+                .margin(1)
+                .split(area);
+            let content = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Percentage(35), Constraint::Percentage(65)])
+                .split(view[0]);
+            (content[0], content[1], view[1])
+        }
+        false => {
+            let progress_constraints = match app.state.waveform_seekbar {
+                true => [Constraint::Min(0), Constraint::Length(3)],
+                false => [Constraint::Percentage(58), Constraint::Percentage(2)],
+            };
+            let view = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints(
+                    [Constraint::Percentage(40)]
+                        .into_iter()
+                        .chain(progress_constraints),
+                )
+                .margin(1)
+                .split(area);
+            (view[0], view[1], view[2])
+        }
+    };
+    app.state.layout.player = area;
+    app.state.layout.progress_bar = progress_bar_area;
 
     let block_title = app.player.get_playback_status_string();
 
     f.render_widget(
         Block::default()
             .borders(Borders::ALL)
+            .border_style(Style::default().fg(app.state.theme.border))
             .title(block_title)
             .style(Style::default().add_modifier(Modifier::BOLD)),
         area,
@@ -205,10 +770,26 @@ fn draw_player_panel(f: &mut Frame, app: &mut App, area: Rect) {
     draw_audio_spectrum(f, app, audio_spectrum_area);
 
     // Song progress bar
-    f.render_widget(
-        draw_song_progress(&app.player, app.state.color_style),
-        progress_bar_area,
-    );
+    let waveform = match app.state.waveform_seekbar {
+        true => app.player.get_song_path().and_then(|path| {
+            app.waveforms
+                .columns(path, progress_bar_area.width as usize)
+        }),
+        false => None,
+    };
+    match waveform {
+        Some(columns) => draw_waveform_seekbar(
+            f,
+            &app.player,
+            &columns,
+            &app.state.theme,
+            progress_bar_area,
+        ),
+        None => f.render_widget(
+            draw_song_progress(&app.player, &app.state.theme),
+            progress_bar_area,
+        ),
+    }
 }
 
 fn draw_song_info(player: &mut Mp3Player) -> Paragraph<'_> {
@@ -222,71 +803,325 @@ fn draw_song_info(player: &mut Mp3Player) -> Paragraph<'_> {
         .style(Style::default().remove_modifier(Modifier::BOLD))
 }
 
-fn draw_song_progress(player: &Mp3Player, color: Color) -> Gauge<'_> {
+fn draw_song_progress<'a>(player: &'a Mp3Player, theme: &Theme) -> Gauge<'a> {
     let label = player
         .get_text_progress()
         .unwrap_or_else(|| String::from("-/-"));
     Gauge::default()
         .block(Block::default())
-        .gauge_style(Style::default().fg(color))
+        .gauge_style(Style::default().fg(theme.gauge))
         .ratio(player.get_current_song_percentage_progress())
         .label(Span::styled(
             label,
             Style::default()
-                .fg(Color::White)
+                .fg(theme.gauge_label)
                 .add_modifier(Modifier::BOLD),
         ))
 }
 
+/// Draws peak and RMS levels of whole track growing from the bottom of area,
+/// with played part in gauge color and progress label in top right corner
+fn draw_waveform_seekbar(
+    f: &mut Frame,
+    player: &Mp3Player,
+    columns: &[(f32, f32)],
+    theme: &Theme,
+    area: Rect,
+) {
+    const BLOCKS: [&str; 9] = [
+        " ", "\u{2581}", "\u{2582}", "\u{2583}", "\u{2584}", "\u{2585}", "\u{2586}", "\u{2587}",
+        "\u{2588}",
+    ];
+    let height = area.height as usize;
+    let played = (player.get_current_song_percentage_progress() * columns.len() as f64) as usize;
+    let lines: Vec<Line> = (0..height)
+        .map(|row| {
+            // Number of eighths of cell height below this row
+            let floor = ((height - 1 - row) * 8) as f32;
+            let spans: Vec<Span> = columns
+                .iter()
+                .enumerate()
+                .map(|(column, (peak, rms))| {
+                    let color = match column < played {
+                        true => theme.gauge,
+                        false => theme.secondary_text,
+                    };
+                    let fill = |level: f32| {
+                        ((level * (height * 8) as f32).round() - floor).clamp(0.0, 8.0) as usize
+                    };
+                    match (fill(*rms), fill(*peak)) {
+                        (0, 0) => Span::raw(" "),
+                        (0, peak) => Span::styled(
+                            BLOCKS[peak],
+                            Style::default().fg(color).add_modifier(Modifier::DIM),
+                        ),
+                        (rms, _) => Span::styled(BLOCKS[rms], Style::default().fg(color)),
+                    }
+                })
+                .collect();
+            Line::from(spans)
+        })
+        .collect();
+    f.render_widget(Paragraph::new(lines), area);
+    if let Some(label) = player.get_text_progress() {
+        f.render_widget(
+            Paragraph::new(Span::styled(
+                format!(" {label} "),
+                Style::default()
+                    .fg(theme.gauge_label)
+                    .add_modifier(Modifier::BOLD),
+            ))
+            .alignment(Alignment::Right),
+            Rect { height: 1, ..area },
+        );
+    }
+}
+
 fn draw_audio_spectrum(f: &mut Frame, app: &mut App, rect: Rect) {
+    let theme = &app.state.theme;
     match app.state.visualization_style {
         VisualizationStyle::Bar { ref mut data } => {
-            let unsigned_spectrum: Vec<u64> = app
-                .player
-                .get_audio_spectrum()
-                .into_iter()
-                .map(|v| v as u64)
-                .collect();
-            data.update_spectrum(unsigned_spectrum);
-            f.render_widget(
-                BarChart::default()
-                    .data(&data.audio_spectrum)
-                    .bar_width(rect.width / data.audio_spectrum_band_count as u16)
-                    .style(Style::default().fg(app.state.color_style))
-                    .value_style(Style::default().fg(app.state.color_style)),
-                rect,
-            );
+            data.update_spectrum(&app.player.get_audio_spectrum());
+            draw_spectrum_bars(f, data, theme, rect);
+        }
+        VisualizationStyle::StereoBar {
+            ref mut left,
+            ref mut right,
+        } => {
+            let spectrum = app.player.get_audio_spectrum();
+            left.update_spectrum(&spectrum.channel(0));
+            right.update_spectrum(&spectrum.channel(1));
+            let halves = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+                .split(rect);
+            for (data, area, name) in [(left, halves[0], "L"), (right, halves[1], "R")] {
+                draw_spectrum_bars(f, data, theme, area);
+                f.render_widget(
+                    Paragraph::new(name).style(Style::default().fg(theme.gauge_label)),
+                    Rect { height: 1, ..area },
+                );
+            }
+        }
+        VisualizationStyle::MirroredBar { ref mut data } => {
+            data.update_spectrum(&app.player.get_audio_spectrum());
+            f.render_widget(draw_mirrored_bars(data, theme, rect), rect);
         }
         VisualizationStyle::Chart { ref mut data } => {
-            let unsigned_spectrum: Vec<f64> = app
-                .player
-                .get_audio_spectrum()
-                .into_iter()
-                .map(f64::from)
-                .collect();
-            data.update_spectrum(unsigned_spectrum);
+            data.update_spectrum(&app.player.get_audio_spectrum());
             let dataset = Dataset::default()
                 .marker(symbols::Marker::Dot)
-                .style(Style::default().fg(app.state.color_style))
+                .style(Style::default().fg(theme.spectrum_color(0.0)))
                 .graph_type(GraphType::Line)
                 .data(&data.audio_spectrum);
             f.render_widget(
                 Chart::new(vec![dataset])
                     .block(Block::default())
-                    .x_axis(Axis::default().bounds([0.0, data.audio_spectrum_point_count as f64]))
-                    .y_axis(Axis::default().bounds([0.0, data.max_value])),
+                    .x_axis(Axis::default().bounds([0.0, data.settings.band_count as f64]))
+                    .y_axis(Axis::default().bounds([0.0, 1.0])),
                 rect,
             );
         }
+        VisualizationStyle::Oscilloscope { ref mut data } => {
+            data.update_samples(&app.player.get_audio_frame());
+            let dataset = Dataset::default()
+                .marker(symbols::Marker::Braille)
+                .style(Style::default().fg(theme.spectrum_color(0.0)))
+                .graph_type(GraphType::Line)
+                .data(&data.points);
+            f.render_widget(
+                Chart::new(vec![dataset])
+                    .block(Block::default())
+                    .x_axis(Axis::default().bounds([0.0, data.points.len() as f64]))
+                    .y_axis(Axis::default().bounds([-1.0, 1.0])),
+                rect,
+            );
+        }
+        VisualizationStyle::Spectrogram { ref mut data } => {
+            data.update_spectrum(
+                &app.player.get_audio_spectrum(),
+                app.player.get_analyzed_frames(),
+            );
+            f.render_widget(draw_spectrogram(data, theme, rect), rect);
+        }
+        VisualizationStyle::VuMeter { ref mut data } => {
+            data.update_samples(&app.player.get_audio_frame());
+            draw_vu_meters(f, data, theme, rect);
+        }
     }
 }
 
-fn draw_help_panel<'a>(show_file_viewer_help: bool) -> Paragraph<'a> {
+/// Draws spectrum bars with peak markers above them
+fn draw_spectrum_bars(f: &mut Frame, data: &BarChartData, theme: &Theme, area: Rect) {
+    let band_count = data.levels.len().max(1);
+    let bar_width = (area.width / band_count as u16).max(2) - 1;
+    let last_band = data.levels.len().max(2) - 1;
+    let bars: Vec<Bar> = data
+        .levels
+        .iter()
+        .enumerate()
+        .map(|(band, level)| {
+            let color = theme.spectrum_color(band as f64 / last_band as f64);
+            Bar::default()
+                .value((level * 100.0) as u64)
+                .text_value(String::new())
+                .style(Style::default().fg(color))
+        })
+        .collect();
+    f.render_widget(
+        BarChart::default()
+            .data(BarGroup::default().bars(&bars))
+            .max(100)
+            .bar_width(bar_width)
+            .bar_gap(1),
+        area,
+    );
+    if area.height == 0 {
+        return;
+    }
+    let buffer = f.buffer_mut();
+    for (band, peak) in data.peaks.iter().enumerate() {
+        let peak_height = (peak * area.height as f64).round() as u16;
+        if peak_height == 0 {
+            continue;
+        }
+        let y = area.bottom() - peak_height.min(area.height);
+        let color = theme.spectrum_color(band as f64 / last_band as f64);
+        let start = area.x + band as u16 * (bar_width + 1);
+        for x in start..(start + bar_width).min(area.right()) {
+            buffer[(x, y)]
+                .set_symbol("\u{2594}")
+                .set_style(Style::default().fg(color));
+        }
+    }
+}
+
+/// Draws spectrum bars growing from the middle of area, both upwards and downwards
+fn draw_mirrored_bars<'a>(data: &BarChartData, theme: &Theme, area: Rect) -> Paragraph<'a> {
+    let half_height = (area.height / 2).max(1) as f64;
+    let bar_width = (area.width as usize / data.levels.len().max(1)).max(2);
+    let last_band = data.levels.len().max(2) - 1;
+    let lines: Vec<Line> = (0..area.height as u64)
+        .map(|row| {
+            // Distance of row from middle line, in cells
+            let distance = match (row as f64) < half_height {
+                true => half_height - row as f64,
+                false => row as f64 - half_height + 1.0,
+            };
+            let spans: Vec<Span> = data
+                .levels
+                .iter()
+                .enumerate()
+                .map(|(band, level)| {
+                    let symbol = match (level * half_height).ceil() >= distance {
+                        true => "\u{2588}",
+                        false => " ",
+                    };
+                    let color = theme.spectrum_color(band as f64 / last_band as f64);
+                    Span::styled(
+                        format!("{} ", symbol.repeat(bar_width - 1)),
+                        Style::default().fg(color),
+                    )
+                })
+                .collect();
+            Line::from(spans)
+        })
+        .collect();
+    Paragraph::new(lines)
+}
+
+/// Draws spectrum history with time on horizontal axis and lowest frequencies at the bottom
+fn draw_spectrogram<'a>(data: &SpectrogramData, theme: &Theme, area: Rect) -> Paragraph<'a> {
+    const SHADES: [&str; 5] = [" ", "\u{2591}", "\u{2592}", "\u{2593}", "\u{2588}"];
+    let width = area.width as usize;
+    let height = area.height.max(1) as usize;
+    let visible: Vec<&Vec<f64>> = data
+        .columns
+        .iter()
+        .skip(data.columns.len().saturating_sub(width))
+        .collect();
+    let padding = width - visible.len();
+    let lines: Vec<Line> = (0..height)
+        .map(|row| {
+            let mut spans = vec![Span::raw(" ".repeat(padding))];
+            spans.extend(visible.iter().map(|column| {
+                let band = (height - 1 - row) * column.len() / height;
+                let intensity = column.get(band).copied().unwrap_or(0.0);
+                let shade = (intensity * (SHADES.len() - 1) as f64).round() as usize;
+                Span::styled(
+                    SHADES[shade],
+                    Style::default().fg(theme.spectrum_color(intensity)),
+                )
+            }));
+            Line::from(spans)
+        })
+        .collect();
+    Paragraph::new(lines)
+}
+
+/// Draws level gauge of each channel, labeled with level and held peak
+fn draw_vu_meters(f: &mut Frame, data: &VuMeterData, theme: &Theme, area: Rect) {
+    let mut constraints = vec![Constraint::Fill(1)];
+    constraints.extend(data.levels.iter().map(|_| Constraint::Length(2)));
+    constraints.push(Constraint::Fill(1));
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(constraints)
+        .split(area);
+    let channel_names = match data.levels.len() {
+        2 => vec!["L", "R"],
+        count => vec!["M"; count],
+    };
+    for (channel, (level, peak)) in data.levels.iter().zip(&data.peaks).enumerate() {
+        let label = format!(
+            "{} {:>6.1} dB  peak {:>6.1} dB",
+            channel_names[channel], level, peak
+        );
+        f.render_widget(
+            Gauge::default()
+                .gauge_style(Style::default().fg(theme.spectrum_color(VuMeterData::ratio(*peak))))
+                .ratio(VuMeterData::ratio(*level))
+                .label(Span::styled(
+                    label,
+                    Style::default()
+                        .fg(theme.gauge_label)
+                        .add_modifier(Modifier::BOLD),
+                )),
+            Rect {
+                height: 1,
+                ..rows[channel + 1]
+            },
+        );
+    }
+}
+
+fn draw_help_panel<'a>(
+    show_file_viewer_help: bool,
+    library_view: LibraryView,
+    theme: &Theme,
+) -> Paragraph<'a> {
     let mut help_text = vec![
         Line::from("h: Toogle help"),
         Line::from("f: Focus files/queue"),
         Line::from("v: Change visualization style"),
-        Line::from("c: Change player color"),
+        Line::from("c: Change accent color"),
+        Line::from("t: Change theme"),
+        Line::from("e: Toggle file details"),
+        Line::from("n: Change sort order"),
+        Line::from("b: Toggle directories first"),
+        Line::from(".: Toggle hidden files"),
+        Line::from("x: Toggle non-audio files"),
+        Line::from("B: Toggle bookmark"),
+        Line::from("': Show bookmarks"),
+        Line::from(":: Go to directory"),
+        Line::from("Backspace: Jump back"),
+        Line::from("y: Change layout"),
+        Line::from("Tab: Next pane"),
+        Line::from("w: Toggle waveform seekbar"),
+        Line::from("o: Toggle podcasts"),
+        Line::from("i: Toggle statistics"),
+        Line::from("g: Toggle smart playlists"),
+        Line::from("M: Show messages"),
         Line::from("q: Quit"),
     ];
 
@@ -300,6 +1135,7 @@ fn draw_help_panel<'a>(show_file_viewer_help: bool) -> Paragraph<'a> {
         Line::from("s: Stop"),
         Line::from("j: Play previous"),
         Line::from("k: Play next"),
+        Line::from("r: Add radio stations"),
     ];
 
     help_text.append(&mut player_help);
@@ -315,11 +1151,59 @@ fn draw_help_panel<'a>(show_file_viewer_help: bool) -> Paragraph<'a> {
             Line::from("\u{2191}: Select song up"),
             Line::from("\u{2193}: Select song down"),
             Line::from("d: Remove song"),
+            Line::from("0-5: Rate song"),
+            Line::from("*: Toggle favourite"),
         ];
         help_text.append(&mut queue_view_help_test);
     }
 
-    if show_file_viewer_help {
+    if show_file_viewer_help && library_view == LibraryView::Podcasts {
+        let mut podcast_help_text = vec![
+            Line::from(""),
+            Line::from(Span::styled(
+                "Podcasts",
+                Style::default().add_modifier(Modifier::BOLD),
+            )),
+            Line::from("\u{23CE}: Download / add to queue"),
+            Line::from("\u{2191}: Select episode up"),
+            Line::from("\u{2193}: Select episode down"),
+            Line::from("u: Refresh feeds"),
+            Line::from("m: Toggle played"),
+        ];
+        help_text.append(&mut podcast_help_text);
+    }
+
+    if show_file_viewer_help && library_view == LibraryView::Statistics {
+        let mut statistics_help_text = vec![
+            Line::from(""),
+            Line::from(Span::styled(
+                "Statistics",
+                Style::default().add_modifier(Modifier::BOLD),
+            )),
+            Line::from("\u{23CE}: Add to queue"),
+            Line::from("\u{2190}: Previous section"),
+            Line::from("\u{2192}: Next section"),
+            Line::from("\u{2191}: Select row up"),
+            Line::from("\u{2193}: Select row down"),
+        ];
+        help_text.append(&mut statistics_help_text);
+    }
+
+    if show_file_viewer_help && library_view == LibraryView::SmartPlaylists {
+        let mut smart_playlist_help_text = vec![
+            Line::from(""),
+            Line::from(Span::styled(
+                "Smart playlists",
+                Style::default().add_modifier(Modifier::BOLD),
+            )),
+            Line::from("\u{23CE}: Add to queue"),
+            Line::from("\u{2191}: Select playlist up"),
+            Line::from("\u{2193}: Select playlist down"),
+        ];
+        help_text.append(&mut smart_playlist_help_text);
+    }
+
+    if show_file_viewer_help && library_view == LibraryView::Files {
         let mut file_viewer_help_text = vec![
             Line::from(""),
             Line::from(Span::styled(
@@ -331,6 +1215,10 @@ fn draw_help_panel<'a>(show_file_viewer_help: bool) -> Paragraph<'a> {
             Line::from("\u{2192}: Enter directory"),
             Line::from("\u{2191}: Select file up"),
             Line::from("\u{2193}: Select file down"),
+            Line::from("0-5: Rate file"),
+            Line::from("*: Toggle favourite"),
+            Line::from("+: Change rating filter"),
+            Line::from("a: Add filtered files"),
         ];
         help_text.append(&mut file_viewer_help_text);
     }
@@ -338,46 +1226,69 @@ fn draw_help_panel<'a>(show_file_viewer_help: bool) -> Paragraph<'a> {
         .block(
             Block::default()
                 .borders(Borders::ALL)
+                .border_style(Style::default().fg(theme.border))
                 .style(Style::default().add_modifier(Modifier::BOLD))
                 .title("Help"),
         )
         .style(Style::default().remove_modifier(Modifier::BOLD))
 }
 
-fn draw_log_view<'a>() -> TuiLoggerWidget<'a> {
+fn draw_log_view<'a>(theme: &Theme) -> TuiLoggerWidget<'a> {
     TuiLoggerWidget::default()
         .style_error(
             Style::default()
-                .fg(Color::Red)
+                .fg(theme.log_error)
                 .remove_modifier(Modifier::BOLD),
         )
         .style_debug(
             Style::default()
-                .fg(Color::Green)
+                .fg(theme.log_debug)
                 .remove_modifier(Modifier::BOLD),
         )
         .style_warn(
             Style::default()
-                .fg(Color::Yellow)
+                .fg(theme.log_warning)
                 .remove_modifier(Modifier::BOLD),
         )
         .style_trace(
             Style::default()
-                .fg(Color::Gray)
+                .fg(theme.log_trace)
                 .remove_modifier(Modifier::BOLD),
         )
         .style_info(
             Style::default()
-                .fg(Color::Blue)
+                .fg(theme.log_info)
                 .remove_modifier(Modifier::BOLD),
         )
         .block(
             Block::default()
                 .title("Logs")
-                .border_style(Style::default())
+                .border_style(Style::default().fg(theme.border))
                 .borders(Borders::ALL)
                 .style(Style::default().add_modifier(Modifier::BOLD)),
         )
         .style(Style::default())
         .output_separator(' ')
 }
+
+/// Render interface attached to penny daemon
+pub fn remote_ui(f: &mut Frame, socket_path: &str, status: &str) {
+    let lines = vec![
+        Line::from(Span::styled(
+            format!("Attached to {socket_path}"),
+            Style::default().add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+        Line::from(status.to_string()),
+        Line::from(""),
+        Line::from("p: Toggle playback  s: Stop  j: Play previous  k: Play next  q: Detach"),
+    ];
+    f.render_widget(
+        Paragraph::new(lines).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(" penny daemon "),
+        ),
+        f.area(),
+    );
+}
@@ -1,82 +1,280 @@
+use clap::ValueEnum;
+use std::{collections::VecDeque, time::Instant};
+
+use crate::player::{mp3_player::AudioFrame, spectrum_analyzer::Spectrum};
+
+/// Level mapped to the bottom of spectrum visualizations, in dBFS
+const SPECTRUM_FLOOR_DB: f64 = -80.0;
+/// Decrease of displayed band level per second, as fraction of visualization height
+const LEVEL_DECAY_PER_SECOND: f64 = 1.2;
+/// Decrease of held band peak per second, as fraction of visualization height
+const PEAK_DECAY_PER_SECOND: f64 = 0.3;
+
+/// Spacing of frequency bands
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+pub enum FrequencyScale {
+    /// Band edges grow geometrically, every octave gets the same number of bands
+    Log,
+    /// Band edges are evenly spaced in mel scale, following perceived pitch
+    Mel,
+}
+
+/// Settings of frequency bands shown by spectrum visualizations
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SpectrumSettings {
+    pub band_count: usize,
+    pub scale: FrequencyScale,
+    pub min_frequency: f64,
+    pub max_frequency: f64,
+}
+
+impl SpectrumSettings {
+    /// Returns edges of bands in Hz, one more than number of bands
+    pub fn band_edges(&self) -> Vec<f64> {
+        let count = self.band_count.max(1);
+        let (low, high) = (
+            self.min_frequency.max(1.0),
+            self.max_frequency.max(self.min_frequency + 1.0),
+        );
+        (0..=count)
+            .map(|i| {
+                let position = i as f64 / count as f64;
+                match self.scale {
+                    FrequencyScale::Log => low * (high / low).powf(position),
+                    FrequencyScale::Mel => {
+                        let (low, high) = (hz_to_mel(low), hz_to_mel(high));
+                        mel_to_hz(low + (high - low) * position)
+                    }
+                }
+            })
+            .collect()
+    }
+
+    /// Returns level of each band mapped from dBFS to [0..1]
+    pub fn band_levels(&self, spectrum: &Spectrum) -> Vec<f64> {
+        if spectrum.magnitudes.is_empty() {
+            return vec![];
+        }
+        let bin_width = spectrum.bin_frequency(1);
+        let last_bin = spectrum.magnitudes.len() - 1;
+        self.band_edges()
+            .windows(2)
+            .map(|edges| {
+                let first = ((edges[0] / bin_width).ceil() as usize).min(last_bin);
+                let last = ((edges[1] / bin_width).floor() as usize).min(last_bin);
+                // Narrow low bands may not contain any bin, nearest bin to band center is used then
+                let magnitude = match first <= last {
+                    true => spectrum.magnitudes[first..=last]
+                        .iter()
+                        .copied()
+                        .fold(f32::MIN, f32::max),
+                    false => {
+                        let center = ((edges[0] + edges[1]) / 2.0 / bin_width).round() as usize;
+                        spectrum.magnitudes[center.min(last_bin)]
+                    }
+                };
+                ((magnitude as f64 - SPECTRUM_FLOOR_DB) / -SPECTRUM_FLOOR_DB).clamp(0.0, 1.0)
+            })
+            .collect()
+    }
+}
+
+fn hz_to_mel(frequency: f64) -> f64 {
+    2595.0 * (1.0 + frequency / 700.0).log10()
+}
+
+fn mel_to_hz(mel: f64) -> f64 {
+    700.0 * (10f64.powf(mel / 2595.0) - 1.0)
+}
+
+/// Returns seconds elapsed since previous update and remembers time of current one.
+/// Decay depends on elapsed time, so its speed does not change with frame rate.
+fn seconds_since(last_update: &mut Option<Instant>, now: Instant) -> f64 {
+    let elapsed = last_update.map_or(0.0, |last| now.duration_since(last).as_secs_f64());
+    *last_update = Some(now);
+    elapsed
+}
+
+/// Band levels with smoothed decay and held peaks, all in range [0..1]
 pub struct BarChartData {
-    pub audio_spectrum: Vec<(&'static str, u64)>,
-    pub audio_spectrum_band_count: usize,
+    pub settings: SpectrumSettings,
+    pub levels: Vec<f64>,
+    pub peaks: Vec<f64>,
+    last_update: Option<Instant>,
 }
 
 impl BarChartData {
-    pub fn new(bar_count: usize) -> Self {
+    pub fn new(settings: SpectrumSettings) -> Self {
         BarChartData {
-            audio_spectrum: vec![],
-            audio_spectrum_band_count: bar_count,
+            settings,
+            levels: vec![],
+            peaks: vec![],
+            last_update: None,
         }
     }
 
-    /// Process raw spectrum from player for display
-    /// Takes first half of frequencies, to display only audible range of frequencies (<20kHz)
-    /// For display purposes, we divide that range into bands and sum values
-    /// First two bins are skipped, as they always have high values
-    pub fn update_spectrum(&mut self, raw_spectrum: Vec<u64>) {
-        let usable_spectrum = &raw_spectrum[0..raw_spectrum.len() / 2];
-        let band_count = self.audio_spectrum_band_count;
-        if usable_spectrum.len() > band_count {
-            let band_width = usable_spectrum.len() / band_count;
-            self.audio_spectrum = usable_spectrum
-                .chunks(band_width)
-                .skip(2)
-                .map(|chunk| ("", chunk.iter().copied().reduce(|a, b| a + b).unwrap_or(0)))
-                .collect();
-        } else {
-            self.audio_spectrum = vec![];
+    /// Updates band levels, which rise immediately and fall gradually
+    pub fn update_spectrum(&mut self, spectrum: &Spectrum) {
+        self.update_spectrum_at(spectrum, Instant::now());
+    }
+
+    pub fn update_spectrum_at(&mut self, spectrum: &Spectrum, now: Instant) {
+        let elapsed = seconds_since(&mut self.last_update, now);
+        let mut current = self.settings.band_levels(spectrum);
+        current.resize(self.settings.band_count, 0.0);
+        self.levels.resize(current.len(), 0.0);
+        self.peaks.resize(current.len(), 0.0);
+        for ((level, peak), new_level) in self.levels.iter_mut().zip(&mut self.peaks).zip(current) {
+            *level = (*level - LEVEL_DECAY_PER_SECOND * elapsed)
+                .max(new_level)
+                .max(0.0);
+            *peak = (*peak - PEAK_DECAY_PER_SECOND * elapsed).max(*level);
         }
     }
 }
 
+/// Band levels shown as line chart, with band number on horizontal axis
 pub struct ChartData {
+    pub settings: SpectrumSettings,
     pub audio_spectrum: Vec<(f64, f64)>,
-    pub audio_spectrum_point_count: usize,
-    pub max_value: f64,
 }
 
 impl ChartData {
-    pub fn new(point_count: usize) -> Self {
+    pub fn new(settings: SpectrumSettings) -> Self {
         ChartData {
+            settings,
             audio_spectrum: vec![],
-            audio_spectrum_point_count: point_count,
-            max_value: 5.0,
         }
     }
 
-    pub fn update_spectrum(&mut self, raw_spectrum: Vec<f64>) {
-        let usable_spectrum = &raw_spectrum[0..raw_spectrum.len() / 2];
-        let band_count = self.audio_spectrum_point_count;
-        if usable_spectrum.len() > band_count {
-            let band_width = usable_spectrum.len() / band_count;
-            let banded_data: Vec<(f64, f64)> = usable_spectrum
-                .chunks(band_width)
-                .skip(2)
-                .enumerate()
-                .map(|(number, chunk)| {
-                    (
-                        number as f64,
-                        chunk.iter().copied().reduce(|a, b| a + b).unwrap_or(0.0),
-                    )
-                })
-                .collect();
-            let max_value = banded_data.iter().map(|(_, y)| *y).fold(0.0, f64::max);
-            self.audio_spectrum = banded_data
-                .iter()
-                .map(|(index, val)| (*index, self.scale_value(*val, max_value)))
-                .collect();
-        } else {
-            self.audio_spectrum = vec![];
+    pub fn update_spectrum(&mut self, spectrum: &Spectrum) {
+        self.audio_spectrum = self
+            .settings
+            .band_levels(spectrum)
+            .into_iter()
+            .enumerate()
+            .map(|(band, level)| (band as f64, level))
+            .collect();
+    }
+}
+
+/// Samples of played frame, mixed to mono and scaled to [-1..1]
+pub struct OscilloscopeData {
+    pub points: Vec<(f64, f64)>,
+}
+
+impl OscilloscopeData {
+    pub fn new() -> Self {
+        OscilloscopeData { points: vec![] }
+    }
+
+    pub fn update_samples(&mut self, frame: &AudioFrame) {
+        let channels = frame.channels.max(1);
+        self.points = frame
+            .samples
+            .chunks(channels)
+            .enumerate()
+            .map(|(index, chunk)| {
+                let mixed = chunk.iter().map(|s| *s as f64).sum::<f64>() / channels as f64;
+                (index as f64, mixed / i16::MAX as f64)
+            })
+            .collect();
+    }
+}
+
+/// Number of spectrum columns kept for scrolling spectrogram
+const SPECTROGRAM_HISTORY: usize = 512;
+
+/// History of spectra, each column holding band levels in range [0..1]
+pub struct SpectrogramData {
+    pub settings: SpectrumSettings,
+    pub columns: VecDeque<Vec<f64>>,
+    /// Number of analyzed audio frames when last column was added
+    last_frame: u64,
+}
+
+impl SpectrogramData {
+    pub fn new(settings: SpectrumSettings) -> Self {
+        SpectrogramData {
+            settings,
+            columns: VecDeque::new(),
+            last_frame: 0,
+        }
+    }
+
+    /// Adds column with band levels of spectrum for every audio frame analyzed since last update,
+    /// so spectrogram scrolls with playback regardless of how often it is drawn.
+    /// Empty spectrum of paused playback is skipped.
+    pub fn update_spectrum(&mut self, spectrum: &Spectrum, analyzed_frames: u64) {
+        let new_frames = analyzed_frames.saturating_sub(self.last_frame);
+        self.last_frame = analyzed_frames;
+        let levels = self.settings.band_levels(spectrum);
+        if levels.is_empty() {
+            return;
+        }
+        for _ in 0..new_frames.min(SPECTROGRAM_HISTORY as u64) {
+            self.columns.push_back(levels.clone());
+        }
+        while self.columns.len() > SPECTROGRAM_HISTORY {
+            self.columns.pop_front();
+        }
+    }
+}
+
+/// Lowest level displayed by VU meter, in dBFS
+pub const VU_METER_MIN_DB: f64 = -60.0;
+/// Decrease of held peak level per second, in dB
+const PEAK_DECAY_DB_PER_SECOND: f64 = 45.0;
+
+/// Levels of each channel in dBFS, with decaying peak hold
+pub struct VuMeterData {
+    pub levels: Vec<f64>,
+    pub peaks: Vec<f64>,
+    last_update: Option<Instant>,
+}
+
+impl VuMeterData {
+    pub fn new() -> Self {
+        VuMeterData {
+            levels: vec![],
+            peaks: vec![],
+            last_update: None,
         }
     }
 
-    fn scale_value(&self, value: f64, maximum: f64) -> f64 {
-        if value > 0.0 {
-            (value / maximum) * self.max_value
+    pub fn update_samples(&mut self, frame: &AudioFrame) {
+        self.update_samples_at(frame, Instant::now());
+    }
+
+    pub fn update_samples_at(&mut self, frame: &AudioFrame, now: Instant) {
+        let elapsed = seconds_since(&mut self.last_update, now);
+        if frame.channels == 0 || frame.samples.is_empty() {
+            self.levels.iter_mut().for_each(|l| *l = VU_METER_MIN_DB);
         } else {
-            0.0
+            self.levels = (0..frame.channels)
+                .map(|channel| {
+                    let samples: Vec<f64> = frame
+                        .samples
+                        .iter()
+                        .skip(channel)
+                        .step_by(frame.channels)
+                        .map(|s| *s as f64 / i16::MAX as f64)
+                        .collect();
+                    let rms = (samples.iter().map(|s| s * s).sum::<f64>()
+                        / samples.len().max(1) as f64)
+                        .sqrt();
+                    (20.0 * rms.log10()).clamp(VU_METER_MIN_DB, 0.0)
+                })
+                .collect();
         }
+        self.peaks.resize(self.levels.len(), VU_METER_MIN_DB);
+        for (peak, level) in self.peaks.iter_mut().zip(&self.levels) {
+            *peak = (*peak - PEAK_DECAY_DB_PER_SECOND * elapsed).max(*level);
+        }
+    }
+
+    /// Returns level as fraction of meter range [0..1]
+    pub fn ratio(level: f64) -> f64 {
+        (level - VU_METER_MIN_DB) / -VU_METER_MIN_DB
     }
 }
@@ -0,0 +1,150 @@
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use crate::application::visualization_state::{
+        BarChartData, FrequencyScale, OscilloscopeData, SpectrogramData, SpectrumSettings,
+        VuMeterData, VU_METER_MIN_DB,
+    };
+    use crate::player::{mp3_player::AudioFrame, spectrum_analyzer::Spectrum};
+
+    #[test]
+    fn should_mix_channels_for_oscilloscope() {
+        // given
+        let frame = AudioFrame {
+            samples: vec![i16::MAX, i16::MAX, 0, i16::MIN + 1, -16384, 0],
+            channels: 2,
+        };
+        let mut data = OscilloscopeData::new();
+
+        // when
+        data.update_samples(&frame);
+
+        // then
+        let values: Vec<f64> = data
+            .points
+            .iter()
+            .map(|(_, y)| (y * 100.0).round())
+            .collect();
+        assert_eq!(values, vec![100.0, -50.0, -25.0]);
+    }
+
+    #[test]
+    fn should_compute_channel_levels_with_decaying_peaks() {
+        // given
+        let loud = AudioFrame {
+            samples: vec![i16::MAX, 0, i16::MIN + 1, 0],
+            channels: 2,
+        };
+        let mut data = VuMeterData::new();
+        let start = Instant::now();
+
+        // when
+        data.update_samples_at(&loud, start);
+        data.update_samples_at(&AudioFrame::default(), start + Duration::from_millis(100));
+
+        // then
+        assert_eq!(data.levels, vec![VU_METER_MIN_DB, VU_METER_MIN_DB]);
+        assert_eq!(data.peaks, vec![-4.5, VU_METER_MIN_DB]);
+        assert_eq!(VuMeterData::ratio(-30.0), 0.5);
+    }
+
+    #[test]
+    fn should_keep_limited_spectrogram_history() {
+        // given
+        let mut data = SpectrogramData::new(settings(FrequencyScale::Log));
+        let spectrum = flat_spectrum(-40.0);
+
+        // when
+        for frame in 1..=600 {
+            data.update_spectrum(&spectrum, frame);
+        }
+        data.update_spectrum(&Spectrum::default(), 601);
+
+        // then
+        assert_eq!(data.columns.len(), 512);
+        assert_eq!(data.columns.back().unwrap(), &vec![0.5; 4]);
+    }
+
+    #[test]
+    fn should_advance_spectrogram_per_analyzed_frame() {
+        // given
+        let mut data = SpectrogramData::new(settings(FrequencyScale::Log));
+        let spectrum = flat_spectrum(-40.0);
+
+        // when
+        data.update_spectrum(&spectrum, 3);
+        data.update_spectrum(&spectrum, 3);
+        data.update_spectrum(&spectrum, 3);
+
+        // then
+        assert_eq!(data.columns.len(), 3);
+    }
+
+    #[test]
+    fn should_space_bands_logarithmically_or_in_mel_scale() {
+        // given
+        let log = settings(FrequencyScale::Log);
+        let mel = settings(FrequencyScale::Mel);
+
+        // when
+        let log_edges: Vec<u64> = log.band_edges().iter().map(|e| e.round() as u64).collect();
+        let mel_edges: Vec<u64> = mel.band_edges().iter().map(|e| e.round() as u64).collect();
+
+        // then
+        assert_eq!(log_edges, vec![20, 80, 320, 1280, 5120]);
+        assert_eq!(mel_edges, vec![20, 514, 1347, 2752, 5120]);
+    }
+
+    #[test]
+    fn should_map_band_magnitudes_to_levels() {
+        // given
+        let mut spectrum = flat_spectrum(-100.0);
+        spectrum.magnitudes[1] = -20.0;
+        spectrum.magnitudes[100] = 0.0;
+
+        // when
+        let result = settings(FrequencyScale::Log).band_levels(&spectrum);
+
+        // then
+        assert_eq!(result, vec![0.75, 0.0, 0.0, 1.0]);
+    }
+
+    #[test]
+    fn should_let_bar_levels_fall_faster_than_peaks() {
+        // given
+        let mut data = BarChartData::new(settings(FrequencyScale::Log));
+        let mut loud = flat_spectrum(-100.0);
+        loud.magnitudes[100] = 0.0;
+
+        let start = Instant::now();
+
+        // when
+        data.update_spectrum_at(&loud, start);
+        data.update_spectrum_at(&flat_spectrum(-100.0), start + Duration::from_millis(100));
+
+        // then
+        assert_eq!((data.levels[3] * 100.0).round(), 88.0);
+        assert_eq!((data.peaks[3] * 100.0).round(), 97.0);
+        assert_eq!(data.levels[0], 0.0);
+    }
+
+    /// 4 bands from 20 Hz to 5120 Hz
+    fn settings(scale: FrequencyScale) -> SpectrumSettings {
+        SpectrumSettings {
+            band_count: 4,
+            scale,
+            min_frequency: 20.0,
+            max_frequency: 5120.0,
+        }
+    }
+
+    /// Spectrum with bins 40 Hz wide
+    fn flat_spectrum(magnitude: f32) -> Spectrum {
+        Spectrum {
+            magnitudes: vec![magnitude; 512],
+            channel_magnitudes: vec![],
+            sample_rate: 40960,
+        }
+    }
+}
@@ -1,3 +1,7 @@
+use crate::{
+    application::{layout::LayoutProfile, visualization_state::FrequencyScale},
+    queue::queue_view::QueueColumn,
+};
 use clap::{Parser, ValueEnum};
 use ratatui::style::Color;
 use std::env::var;
@@ -35,6 +39,31 @@ pub struct Config {
         help = "Starting directory to open for penny")]
     pub starting_directory: String,
 
+    /// Root of music library searched by smart playlists, starting directory if not set
+    #[arg(long, help = "Music library directory used by smart playlists")]
+    pub library_directory: Option<String>,
+
+    /// Directory containing configuration files, like radio station list
+    #[arg(
+        long,
+        default_value_t = default_config_directory(),
+        help = "Directory with penny configuration files")]
+    pub config_directory: String,
+
+    /// Directory keeping persistent application data, like listening progress
+    #[arg(
+        long,
+        default_value_t = default_data_directory(),
+        help = "Directory with penny data files")]
+    pub data_directory: String,
+
+    /// Directory for downloaded files, like podcast episodes
+    #[arg(
+        long,
+        default_value_t = default_cache_directory(),
+        help = "Directory for files downloaded by penny")]
+    pub cache_directory: String,
+
     /// Open logs view and set log level to DEBUG
     #[arg(long, help = "Toggle logs with debug level")]
     pub debug: bool,
@@ -42,6 +71,128 @@ pub struct Config {
     #[arg(long, default_value_t = 64)]
     pub band_count: usize,
 
-    #[arg(value_enum, long, default_value_t = ParsedColor::Cyan) ]
-    pub color: ParsedColor,
+    /// Spacing of frequency bands in spectrum visualizations
+    #[arg(value_enum, long, default_value_t = FrequencyScale::Log)]
+    pub frequency_scale: FrequencyScale,
+
+    /// Lowest frequency shown in spectrum visualizations, in Hz
+    #[arg(long, default_value_t = 20.0)]
+    pub min_frequency: f64,
+
+    /// Highest frequency shown in spectrum visualizations, in Hz
+    #[arg(long, default_value_t = 16000.0)]
+    pub max_frequency: f64,
+
+    /// Columns of queue table, in order of display
+    #[arg(
+        value_enum,
+        long,
+        value_delimiter = ',',
+        default_values_t = [
+            QueueColumn::Track,
+            QueueColumn::Artist,
+            QueueColumn::Title,
+            QueueColumn::Album,
+            QueueColumn::Duration,
+            QueueColumn::Rating,
+        ]
+    )]
+    pub queue_columns: Vec<QueueColumn>,
+
+    /// Arrangement of panes, chosen from terminal size by default
+    #[arg(value_enum, long, default_value_t = LayoutProfile::Auto)]
+    pub layout: LayoutProfile,
+
+    /// Number of times per second interface is redrawn while song is played
+    #[arg(long, default_value_t = 30, value_parser = clap::value_parser!(u32).range(1..=120))]
+    pub frame_rate: u32,
+
+    /// Show loudness overview of whole track in place of plain progress bar
+    #[arg(long, help = "Draw waveform of track as seekbar")]
+    pub waveform_seekbar: bool,
+
+    /// Accent color replacing accent of selected theme
+    #[arg(value_enum, long)]
+    pub color: Option<ParsedColor>,
+
+    /// Name of built-in theme or theme file from themes configuration directory
+    #[arg(long, default_value_t = String::from("dark"), help = "Color theme")]
+    pub theme: String,
+
+    /// Run player without terminal interface, controlled through local socket
+    #[arg(long, help = "Run penny in background without terminal interface")]
+    pub daemon: bool,
+
+    /// Open terminal interface controlling already running daemon
+    #[arg(long, help = "Attach to running penny daemon")]
+    pub attach: bool,
+
+    /// Send single command to running daemon and print its response
+    #[arg(
+        long,
+        value_name = "COMMAND",
+        help = "Send command to running penny daemon (toggle, stop, next, previous, add <path>, status, quit)"
+    )]
+    pub remote: Option<String>,
+
+    /// Last.fm API key, scrobbling to Last.fm is enabled when key, secret and session key are set
+    #[arg(long, help = "Last.fm API key used for scrobbling")]
+    pub lastfm_api_key: Option<String>,
+
+    #[arg(long, help = "Last.fm API shared secret used for scrobbling")]
+    pub lastfm_api_secret: Option<String>,
+
+    #[arg(long, help = "Last.fm session key of authenticated user")]
+    pub lastfm_session_key: Option<String>,
+
+    #[arg(
+        long,
+        default_value = "https://ws.audioscrobbler.com/2.0/",
+        help = "Last.fm API endpoint"
+    )]
+    pub lastfm_url: String,
+
+    /// ListenBrainz user token, scrobbling to ListenBrainz is enabled when it is set
+    #[arg(long, help = "ListenBrainz user token used for scrobbling")]
+    pub listenbrainz_token: Option<String>,
+
+    #[arg(
+        long,
+        default_value = "https://api.listenbrainz.org",
+        help = "ListenBrainz API endpoint"
+    )]
+    pub listenbrainz_url: String,
+
+    /// Location of socket used for communication with daemon
+    #[arg(
+        long,
+        default_value_t = default_socket_path(),
+        help = "Path of socket used to control penny daemon")]
+    pub socket: String,
+}
+
+fn default_config_directory() -> String {
+    xdg_directory("XDG_CONFIG_HOME", ".config")
+}
+
+fn default_data_directory() -> String {
+    xdg_directory("XDG_DATA_HOME", ".local/share")
+}
+
+fn default_cache_directory() -> String {
+    xdg_directory("XDG_CACHE_HOME", ".cache")
+}
+
+/// Returns penny directory inside location from given variable,
+/// falling back to provided directory inside user home
+fn xdg_directory(variable: &str, home_fallback: &str) -> String {
+    match var(variable) {
+        Ok(directory) => format!("{directory}/penny"),
+        Err(_) => format!("{}/{home_fallback}/penny", var("HOME").unwrap_or_default()),
+    }
+}
+
+fn default_socket_path() -> String {
+    let runtime_directory = var("XDG_RUNTIME_DIR").unwrap_or(String::from("/tmp"));
+    format!("{runtime_directory}/penny.sock")
 }
@@ -0,0 +1,33 @@
+use std::{
+    io::{self, BufRead, BufReader, Write},
+    os::unix::net::UnixStream,
+};
+
+use super::RemoteCommand;
+
+/// Connection to running penny daemon
+pub struct RemoteClient {
+    reader: BufReader<UnixStream>,
+    writer: UnixStream,
+}
+
+impl RemoteClient {
+    pub fn connect(socket_path: &str) -> io::Result<Self> {
+        let writer = UnixStream::connect(socket_path)?;
+        let reader = BufReader::new(writer.try_clone()?);
+        Ok(RemoteClient { reader, writer })
+    }
+
+    /// Sends command to daemon and waits for single line response
+    pub fn send(&mut self, command: &RemoteCommand) -> io::Result<String> {
+        writeln!(self.writer, "{command}")?;
+        let mut response = String::new();
+        if self.reader.read_line(&mut response)? == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::ConnectionAborted,
+                "Daemon closed connection",
+            ));
+        }
+        Ok(response.trim_end().to_string())
+    }
+}
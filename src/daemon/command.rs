@@ -0,0 +1,69 @@
+use std::{fmt, str::FromStr};
+
+use crate::application::actions::Action;
+
+/// Commands accepted by penny daemon through control socket.
+/// Each command is sent as single line of text.
+#[derive(Debug, PartialEq)]
+pub enum RemoteCommand {
+    TogglePlayback,
+    Stop,
+    Next,
+    Previous,
+    /// Add file or all mp3 files from directory to queue
+    Add(String),
+    Status,
+    /// Terminate daemon
+    Quit,
+}
+
+impl RemoteCommand {
+    /// Returns command matching action performed in attached interface,
+    /// or `None` if action cannot be executed remotely
+    pub fn from_action(action: &Action) -> Option<RemoteCommand> {
+        match action {
+            Action::TogglePlayback => Some(RemoteCommand::TogglePlayback),
+            Action::StopPlayback => Some(RemoteCommand::Stop),
+            Action::PlayNextFromQueue => Some(RemoteCommand::Next),
+            Action::PlayPreviousFromQueue => Some(RemoteCommand::Previous),
+            _ => None,
+        }
+    }
+}
+
+impl FromStr for RemoteCommand {
+    type Err = String;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let line = line.trim();
+        let (name, argument) = match line.split_once(' ') {
+            Some((name, argument)) => (name, argument.trim()),
+            None => (line, ""),
+        };
+        match (name, argument) {
+            ("toggle", "") => Ok(RemoteCommand::TogglePlayback),
+            ("stop", "") => Ok(RemoteCommand::Stop),
+            ("next", "") => Ok(RemoteCommand::Next),
+            ("previous", "") => Ok(RemoteCommand::Previous),
+            ("status", "") => Ok(RemoteCommand::Status),
+            ("quit", "") => Ok(RemoteCommand::Quit),
+            ("add", "") => Err(String::from("Missing path for add command")),
+            ("add", path) => Ok(RemoteCommand::Add(String::from(path))),
+            _ => Err(format!("Unknown command: {line}")),
+        }
+    }
+}
+
+impl fmt::Display for RemoteCommand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RemoteCommand::TogglePlayback => write!(f, "toggle"),
+            RemoteCommand::Stop => write!(f, "stop"),
+            RemoteCommand::Next => write!(f, "next"),
+            RemoteCommand::Previous => write!(f, "previous"),
+            RemoteCommand::Add(path) => write!(f, "add {path}"),
+            RemoteCommand::Status => write!(f, "status"),
+            RemoteCommand::Quit => write!(f, "quit"),
+        }
+    }
+}
@@ -0,0 +1,71 @@
+#[cfg(test)]
+mod tests {
+    use crate::daemon::RemoteCommand;
+
+    #[test]
+    fn should_parse_simple_commands() {
+        // given
+        let lines = ["toggle", "stop", "next", "previous", "status", "quit"];
+
+        // when
+        let result: Vec<Result<RemoteCommand, String>> =
+            lines.iter().map(|line| line.parse()).collect();
+
+        // then
+        assert_eq!(
+            result,
+            vec![
+                Ok(RemoteCommand::TogglePlayback),
+                Ok(RemoteCommand::Stop),
+                Ok(RemoteCommand::Next),
+                Ok(RemoteCommand::Previous),
+                Ok(RemoteCommand::Status),
+                Ok(RemoteCommand::Quit),
+            ]
+        );
+    }
+
+    #[test]
+    fn should_parse_add_command_with_path() {
+        // given
+        let line = "add /home/user/Music/my song.mp3\n";
+
+        // when
+        let result = line.parse::<RemoteCommand>();
+
+        // then
+        assert_eq!(
+            result,
+            Ok(RemoteCommand::Add(String::from(
+                "/home/user/Music/my song.mp3"
+            )))
+        );
+    }
+
+    #[test]
+    fn should_reject_unknown_command() {
+        // given
+        let lines = ["rewind", "add", "stop now"];
+
+        // when
+        let result: Vec<bool> = lines
+            .iter()
+            .map(|line| line.parse::<RemoteCommand>().is_err())
+            .collect();
+
+        // then
+        assert_eq!(result, vec![true, true, true]);
+    }
+
+    #[test]
+    fn should_format_command_as_parsable_line() {
+        // given
+        let command = RemoteCommand::Add(String::from("/tmp/song.mp3"));
+
+        // when
+        let result = command.to_string().parse::<RemoteCommand>();
+
+        // then
+        assert_eq!(result, Ok(command));
+    }
+}
@@ -0,0 +1,12 @@
+//! Module allowing to run penny in background and control it
+//! through local socket
+pub mod client;
+pub mod command;
+pub mod server;
+
+pub use client::RemoteClient;
+pub use command::RemoteCommand;
+pub use server::ControlServer;
+
+#[cfg(test)]
+mod command_test;
@@ -0,0 +1,96 @@
+use log::{debug, error, info};
+use std::{
+    fs,
+    io::{self, BufRead, BufReader, Write},
+    os::unix::net::{UnixListener, UnixStream},
+    path::PathBuf,
+    sync::mpsc::{self, Sender},
+    thread,
+};
+
+use crate::input::AppEvent;
+
+use super::RemoteCommand;
+
+/// Listener accepting commands on local socket and forwarding them
+/// to application as [Remote](AppEvent::Remote) events.
+/// Socket file is removed when server is dropped.
+pub struct ControlServer {
+    socket_path: PathBuf,
+}
+
+impl ControlServer {
+    /// Binds socket under given path and starts accepting clients in separate thread
+    pub fn start(socket_path: &str, events: Sender<AppEvent>) -> io::Result<Self> {
+        let path = PathBuf::from(socket_path);
+        if path.exists() {
+            if UnixStream::connect(&path).is_ok() {
+                return Err(io::Error::new(
+                    io::ErrorKind::AddrInUse,
+                    format!("Another penny daemon is listening on {socket_path}"),
+                ));
+            }
+            fs::remove_file(&path)?;
+        }
+        let listener = UnixListener::bind(&path)?;
+        info!("Listening for commands on {socket_path}");
+
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                match stream {
+                    Ok(stream) => {
+                        let client_events = events.clone();
+                        thread::spawn(move || Self::handle_client(stream, client_events));
+                    }
+                    Err(e) => error!("Failed to accept control connection: {e}"),
+                }
+            }
+        });
+
+        Ok(ControlServer { socket_path: path })
+    }
+
+    fn handle_client(stream: UnixStream, events: Sender<AppEvent>) {
+        let reader = match stream.try_clone() {
+            Ok(s) => BufReader::new(s),
+            Err(e) => {
+                error!("Failed to read from control connection: {e}");
+                return;
+            }
+        };
+        let mut writer = stream;
+        for line in reader.lines().map_while(Result::ok) {
+            debug!("Received remote command: {line}");
+            let response = match line.parse::<RemoteCommand>() {
+                Ok(RemoteCommand::Quit) => {
+                    // Respond before forwarding, as daemon terminates right after handling it
+                    let _ = writeln!(writer, "OK");
+                    let (reply_tx, _) = mpsc::channel();
+                    let _ = events.send(AppEvent::Remote(RemoteCommand::Quit, reply_tx));
+                    break;
+                }
+                Ok(command) => {
+                    let (reply_tx, reply_rx) = mpsc::channel();
+                    if events.send(AppEvent::Remote(command, reply_tx)).is_err() {
+                        break;
+                    }
+                    reply_rx
+                        .recv()
+                        .unwrap_or_else(|_| String::from("ERR penny is shutting down"))
+                }
+                Err(e) => format!("ERR {e}"),
+            };
+            if writeln!(writer, "{response}").is_err() {
+                break;
+            }
+        }
+    }
+}
+
+impl Drop for ControlServer {
+    fn drop(&mut self) {
+        if fs::remove_file(&self.socket_path).is_err() {
+            debug!("Control socket was already removed.");
+        }
+    }
+}
@@ -0,0 +1,46 @@
+use std::{error, fmt, io};
+
+/// Errors of player, file viewer and queue, reported to user instead of terminating app
+#[derive(Debug)]
+pub enum AppError {
+    /// File or network stream could not be opened
+    Open { path: String, source: io::Error },
+    /// Directory could not be listed
+    ListDirectory { path: String, source: io::Error },
+    /// File is not valid mp3 or its data is corrupted
+    Decode { path: String, reason: String },
+    /// Audio output device is not available
+    AudioDevice(String),
+}
+
+impl AppError {
+    /// Returns true if error concerns single track, so playback can continue with next one
+    pub fn is_track_error(&self) -> bool {
+        match self {
+            AppError::Open { .. } | AppError::Decode { .. } => true,
+            AppError::ListDirectory { .. } | AppError::AudioDevice(_) => false,
+        }
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AppError::Open { path, source } => write!(f, "Cannot open {path}: {source}"),
+            AppError::ListDirectory { path, source } => {
+                write!(f, "Cannot list directory {path}: {source}")
+            }
+            AppError::Decode { path, reason } => write!(f, "Cannot play {path}: {reason}"),
+            AppError::AudioDevice(reason) => write!(f, "Cannot open audio device: {reason}"),
+        }
+    }
+}
+
+impl error::Error for AppError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            AppError::Open { source, .. } | AppError::ListDirectory { source, .. } => Some(source),
+            AppError::Decode { .. } | AppError::AudioDevice(_) => None,
+        }
+    }
+}
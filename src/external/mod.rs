@@ -1,2 +1,5 @@
 pub mod notifier;
+pub mod scrobbler;
 
+#[cfg(test)]
+mod scrobbler_test;
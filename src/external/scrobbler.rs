@@ -0,0 +1,380 @@
+use log::{debug, error, info, warn};
+use std::{
+    collections::BTreeMap,
+    fs, io,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    thread,
+    time::Duration,
+};
+
+use crate::{cli::config::Config, clock::unix_timestamp, queue::SongFile};
+
+/// Tracks shorter than this are never scrobbled
+const MIN_TRACK_DURATION: Duration = Duration::from_secs(30);
+/// Listening time after which track is scrobbled regardless of its length
+const MAX_REQUIRED_LISTENING: Duration = Duration::from_secs(240);
+
+/// Single listen submitted to scrobbling service
+#[derive(Debug, Clone, PartialEq)]
+pub struct Scrobble {
+    pub artist: String,
+    pub title: String,
+    pub album: Option<String>,
+    /// Unix timestamp of playback start
+    pub timestamp: u64,
+    pub duration: Duration,
+}
+
+impl Scrobble {
+    /// Creates scrobble for song, if song has enough metadata to be identified
+    pub fn from_song(song: &SongFile, timestamp: u64) -> Option<Self> {
+        Some(Scrobble {
+            artist: song.metadata.artist.clone()?,
+            title: song.metadata.title.clone()?,
+            album: song.metadata.album.clone(),
+            timestamp,
+            duration: song.duration,
+        })
+    }
+}
+
+/// Service accepting scrobbles
+#[derive(Debug, Clone)]
+pub enum ScrobbleService {
+    LastFm {
+        api_url: String,
+        api_key: String,
+        api_secret: String,
+        session_key: String,
+    },
+    ListenBrainz {
+        api_url: String,
+        token: String,
+    },
+}
+
+impl ScrobbleService {
+    /// Identifier of service used in offline queue
+    fn id(&self) -> &'static str {
+        match self {
+            ScrobbleService::LastFm { .. } => "lastfm",
+            ScrobbleService::ListenBrainz { .. } => "listenbrainz",
+        }
+    }
+
+    fn now_playing(&self, scrobble: &Scrobble) -> Result<(), String> {
+        match self {
+            ScrobbleService::LastFm { .. } => {
+                self.submit_lastfm("track.updateNowPlaying", scrobble, false)
+            }
+            ScrobbleService::ListenBrainz { .. } => {
+                self.submit_listenbrainz("playing_now", scrobble)
+            }
+        }
+    }
+
+    fn scrobble(&self, scrobble: &Scrobble) -> Result<(), String> {
+        match self {
+            ScrobbleService::LastFm { .. } => self.submit_lastfm("track.scrobble", scrobble, true),
+            ScrobbleService::ListenBrainz { .. } => self.submit_listenbrainz("single", scrobble),
+        }
+    }
+
+    fn submit_lastfm(
+        &self,
+        method: &str,
+        scrobble: &Scrobble,
+        with_timestamp: bool,
+    ) -> Result<(), String> {
+        let ScrobbleService::LastFm {
+            api_url,
+            api_key,
+            api_secret,
+            session_key,
+        } = self
+        else {
+            return Err(String::from("Not a Last.fm service"));
+        };
+        let mut parameters = BTreeMap::from([
+            ("method", String::from(method)),
+            ("artist", scrobble.artist.clone()),
+            ("track", scrobble.title.clone()),
+            ("duration", scrobble.duration.as_secs().to_string()),
+            ("api_key", api_key.clone()),
+            ("sk", session_key.clone()),
+        ]);
+        if let Some(album) = &scrobble.album {
+            parameters.insert("album", album.clone());
+        }
+        if with_timestamp {
+            parameters.insert("timestamp", scrobble.timestamp.to_string());
+        }
+        let signature = lastfm_signature(&parameters, api_secret);
+        parameters.insert("api_sig", signature);
+        parameters.insert("format", String::from("json"));
+
+        let form: Vec<(&str, &str)> = parameters.iter().map(|(k, v)| (*k, v.as_str())).collect();
+        let response = ureq::post(api_url)
+            .send_form(&form)
+            .map_err(|e| e.to_string())?
+            .into_string()
+            .map_err(|e| e.to_string())?;
+        lastfm_result(response)
+    }
+
+    fn submit_listenbrainz(&self, listen_type: &str, scrobble: &Scrobble) -> Result<(), String> {
+        let ScrobbleService::ListenBrainz { api_url, token } = self else {
+            return Err(String::from("Not a ListenBrainz service"));
+        };
+        let mut listen = ureq::json!({
+            "track_metadata": {
+                "artist_name": scrobble.artist,
+                "track_name": scrobble.title,
+                "additional_info": {
+                    "duration_ms": scrobble.duration.as_millis() as u64,
+                    "media_player": "penny",
+                },
+            },
+        });
+        if let Some(album) = &scrobble.album {
+            listen["track_metadata"]["release_name"] = ureq::json!(album);
+        }
+        if listen_type != "playing_now" {
+            listen["listened_at"] = ureq::json!(scrobble.timestamp);
+        }
+        ureq::post(&format!(
+            "{}/1/submit-listens",
+            api_url.trim_end_matches('/')
+        ))
+        .set("Authorization", &format!("Token {token}"))
+        .send_json(ureq::json!({
+            "listen_type": listen_type,
+            "payload": [listen],
+        }))
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+    }
+}
+
+/// Checks Last.fm API response, which reports failure in top-level `error` field
+pub fn lastfm_result(response: String) -> Result<(), String> {
+    match ureq::serde_json::from_str::<ureq::serde_json::Value>(&response) {
+        Ok(json) if json.get("error").is_none() => Ok(()),
+        Ok(_) => Err(response),
+        Err(e) => Err(format!("Invalid Last.fm response: {e}")),
+    }
+}
+
+/// Returns Last.fm API signature: md5 of sorted parameters concatenated with secret
+pub fn lastfm_signature(parameters: &BTreeMap<&str, String>, api_secret: &str) -> String {
+    let mut signed = String::new();
+    for (key, value) in parameters {
+        signed.push_str(key);
+        signed.push_str(value);
+    }
+    signed.push_str(api_secret);
+    format!("{:x}", md5::compute(signed))
+}
+
+/// Decides if track was listened long enough to be scrobbled:
+/// track must be longer than 30 seconds and played for half of its duration or 4 minutes
+pub fn should_scrobble(duration: Duration, listened: Duration) -> bool {
+    duration > MIN_TRACK_DURATION && listened >= (duration / 2).min(MAX_REQUIRED_LISTENING)
+}
+
+/// Submits listened tracks to configured services.
+/// Scrobbles which failed to be submitted are kept in queue file
+/// and retried with following submissions.
+pub struct Scrobbler {
+    services: Vec<ScrobbleService>,
+    queue_file: PathBuf,
+    /// Guards queue file against concurrent submissions
+    queue_lock: Arc<Mutex<()>>,
+}
+
+impl Scrobbler {
+    pub fn new(services: Vec<ScrobbleService>, queue_file: &Path) -> Self {
+        Scrobbler {
+            services,
+            queue_file: queue_file.to_path_buf(),
+            queue_lock: Arc::new(Mutex::new(())),
+        }
+    }
+
+    /// Creates scrobbler with services configured through command line arguments
+    pub fn from_config(config: &Config) -> Self {
+        let mut services = vec![];
+        if let (Some(api_key), Some(api_secret), Some(session_key)) = (
+            &config.lastfm_api_key,
+            &config.lastfm_api_secret,
+            &config.lastfm_session_key,
+        ) {
+            services.push(ScrobbleService::LastFm {
+                api_url: config.lastfm_url.clone(),
+                api_key: api_key.clone(),
+                api_secret: api_secret.clone(),
+                session_key: session_key.clone(),
+            });
+        }
+        if let Some(token) = &config.listenbrainz_token {
+            services.push(ScrobbleService::ListenBrainz {
+                api_url: config.listenbrainz_url.clone(),
+                token: token.clone(),
+            });
+        }
+        let scrobbler = Scrobbler::new(
+            services,
+            &Path::new(&config.data_directory).join("scrobble_queue"),
+        );
+        scrobbler.retry_pending();
+        scrobbler
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        !self.services.is_empty()
+    }
+
+    /// Announces start of playback to services in background
+    pub fn now_playing(&self, song: &SongFile) {
+        let Some(scrobble) = self.enabled_scrobble(song, unix_timestamp()) else {
+            return;
+        };
+        let services = self.services.clone();
+        thread::spawn(move || {
+            for service in services {
+                if let Err(e) = service.now_playing(&scrobble) {
+                    debug!("Failed to update now playing in {}: {e}", service.id());
+                }
+            }
+        });
+    }
+
+    /// Submits listen in background if song was played long enough
+    pub fn scrobble(&self, song: &SongFile, started_at: u64, listened: Duration) {
+        if !should_scrobble(song.duration, listened) {
+            return;
+        }
+        let Some(scrobble) = self.enabled_scrobble(song, started_at) else {
+            return;
+        };
+        let worker = self.worker();
+        thread::spawn(move || worker.submit(Some(scrobble)));
+    }
+
+    /// Retries submission of queued scrobbles in background
+    pub fn retry_pending(&self) {
+        if self.is_enabled() {
+            let worker = self.worker();
+            thread::spawn(move || worker.submit(None));
+        }
+    }
+
+    /// Submits new scrobble together with queued ones, keeping failed submissions in queue.
+    /// Returns number of scrobbles left in queue.
+    pub fn submit(&self, scrobble: Option<Scrobble>) -> usize {
+        let _guard = self.queue_lock.lock().unwrap();
+        let mut pending = self.read_queue();
+        if let Some(scrobble) = scrobble {
+            for service in &self.services {
+                pending.push((String::from(service.id()), scrobble.clone()));
+            }
+        }
+        let failed: Vec<(String, Scrobble)> = pending
+            .into_iter()
+            .filter(|(service_id, scrobble)| {
+                let Some(service) = self.services.iter().find(|s| s.id() == service_id) else {
+                    warn!("Dropping queued scrobble for unconfigured service {service_id}");
+                    return false;
+                };
+                match service.scrobble(scrobble) {
+                    Ok(()) => {
+                        info!(
+                            "Scrobbled {} - {} to {service_id}",
+                            scrobble.artist, scrobble.title
+                        );
+                        false
+                    }
+                    Err(e) => {
+                        warn!("Failed to scrobble to {service_id}, queued for retry: {e}");
+                        true
+                    }
+                }
+            })
+            .collect();
+        if let Err(e) = self.write_queue(&failed) {
+            error!("Failed to save scrobble queue: {e}");
+        }
+        failed.len()
+    }
+
+    fn enabled_scrobble(&self, song: &SongFile, timestamp: u64) -> Option<Scrobble> {
+        if !self.is_enabled() || song.is_stream() {
+            return None;
+        }
+        Scrobble::from_song(song, timestamp)
+    }
+
+    fn worker(&self) -> Scrobbler {
+        Scrobbler {
+            services: self.services.clone(),
+            queue_file: self.queue_file.clone(),
+            queue_lock: self.queue_lock.clone(),
+        }
+    }
+
+    fn read_queue(&self) -> Vec<(String, Scrobble)> {
+        fs::read_to_string(&self.queue_file)
+            .map(|content| content.lines().filter_map(parse_queue_line).collect())
+            .unwrap_or_default()
+    }
+
+    fn write_queue(&self, pending: &[(String, Scrobble)]) -> io::Result<()> {
+        if pending.is_empty() {
+            return match fs::remove_file(&self.queue_file) {
+                Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+                _ => Ok(()),
+            };
+        }
+        if let Some(parent) = self.queue_file.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let lines: Vec<String> = pending
+            .iter()
+            .map(|(service_id, s)| {
+                [
+                    service_id.as_str(),
+                    &s.timestamp.to_string(),
+                    &s.duration.as_secs().to_string(),
+                    &sanitize_column(&s.artist),
+                    &sanitize_column(&s.title),
+                    &sanitize_column(s.album.as_deref().unwrap_or_default()),
+                ]
+                .join("\t")
+            })
+            .collect();
+        fs::write(&self.queue_file, lines.join("\n"))
+    }
+}
+
+fn parse_queue_line(line: &str) -> Option<(String, Scrobble)> {
+    let columns: Vec<&str> = line.split('\t').collect();
+    if columns.len() != 6 {
+        return None;
+    }
+    Some((
+        String::from(columns[0]),
+        Scrobble {
+            timestamp: columns[1].parse().ok()?,
+            duration: Duration::from_secs(columns[2].parse().ok()?),
+            artist: String::from(columns[3]),
+            title: String::from(columns[4]),
+            album: Some(String::from(columns[5])).filter(|a| !a.is_empty()),
+        },
+    ))
+}
+
+/// Removes characters used as separators in queue file
+fn sanitize_column(value: &str) -> String {
+    value.replace(['\t', '\n'], " ")
+}
@@ -0,0 +1,185 @@
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::BTreeMap,
+        io::{BufRead, BufReader, Read, Write},
+        net::TcpListener,
+        sync::{Arc, Mutex},
+        thread,
+        time::Duration,
+    };
+
+    use tempdir::TempDir;
+
+    use crate::external::scrobbler::{
+        lastfm_result, lastfm_signature, should_scrobble, Scrobble, ScrobbleService, Scrobbler,
+    };
+
+    #[test]
+    fn should_follow_scrobbling_rules() {
+        // given
+        let cases = [
+            (Duration::from_secs(20), Duration::from_secs(20), false),
+            (Duration::from_secs(200), Duration::from_secs(99), false),
+            (Duration::from_secs(200), Duration::from_secs(100), true),
+            (Duration::from_secs(1200), Duration::from_secs(240), true),
+            (Duration::from_secs(1200), Duration::from_secs(239), false),
+        ];
+
+        // when
+        let result: Vec<bool> = cases
+            .iter()
+            .map(|(duration, listened, _)| should_scrobble(*duration, *listened))
+            .collect();
+
+        // then
+        let expected: Vec<bool> = cases.iter().map(|(_, _, expected)| *expected).collect();
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn should_detect_lastfm_error_only_in_top_level_field() {
+        // given
+        let accepted = r##"{"scrobbles":{"scrobble":{"track":{"#text":"\"error\""}}}}"##;
+        let rejected = r#"{"error":9,"message":"Invalid session key"}"#;
+
+        // when
+        let results = [accepted, rejected, "<html>"].map(|r| lastfm_result(String::from(r)));
+
+        // then
+        assert_eq!(results[0], Ok(()));
+        assert_eq!(results[1], Err(String::from(rejected)));
+        assert!(results[2].is_err());
+    }
+
+    #[test]
+    fn should_sign_lastfm_request() {
+        // given
+        let parameters = BTreeMap::from([
+            ("method", String::from("track.scrobble")),
+            ("api_key", String::from("xxx")),
+            ("sk", String::from("token123")),
+        ]);
+
+        // when
+        let result = lastfm_signature(&parameters, "secret");
+
+        // then
+        assert_eq!(result, "0611948c0be2b49bb571ceaa5dc55037");
+    }
+
+    #[test]
+    fn should_submit_scrobble_to_listenbrainz() -> Result<(), std::io::Error> {
+        // given
+        let tmp_dir = TempDir::new("scrobbles")?;
+        let (url, requests) = mock_server(1)?;
+        let scrobbler = Scrobbler::new(
+            vec![ScrobbleService::ListenBrainz {
+                api_url: url,
+                token: String::from("token"),
+            }],
+            &tmp_dir.path().join("queue"),
+        );
+
+        // when
+        let result = scrobbler.submit(Some(scrobble()));
+
+        // then
+        assert_eq!(result, 0);
+        let requests = requests.lock().unwrap();
+        assert!(requests[0].starts_with("POST /1/submit-listens"));
+        assert!(requests[0].contains("Authorization: Token token"));
+        assert!(requests[0].contains("\"listened_at\":1700000000"));
+        assert!(requests[0].contains("\"artist_name\":\"Rockers\""));
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_queue_failed_scrobble_and_retry_later() -> Result<(), std::io::Error> {
+        // given
+        let tmp_dir = TempDir::new("scrobbles")?;
+        let queue_file = tmp_dir.path().join("queue");
+        let unavailable_url = {
+            let listener = TcpListener::bind("127.0.0.1:0")?;
+            format!("http://{}", listener.local_addr()?)
+        };
+        let offline_scrobbler = Scrobbler::new(
+            vec![ScrobbleService::ListenBrainz {
+                api_url: unavailable_url,
+                token: String::from("token"),
+            }],
+            &queue_file,
+        );
+        let queued = offline_scrobbler.submit(Some(scrobble()));
+        let (url, requests) = mock_server(1)?;
+        let online_scrobbler = Scrobbler::new(
+            vec![ScrobbleService::ListenBrainz {
+                api_url: url,
+                token: String::from("token"),
+            }],
+            &queue_file,
+        );
+
+        // when
+        let result = online_scrobbler.submit(None);
+
+        // then
+        assert_eq!(queued, 1);
+        assert_eq!(result, 0);
+        assert!(!queue_file.exists());
+        assert!(requests.lock().unwrap()[0].contains("\"track_name\":\"Awesome medley\""));
+
+        Ok(())
+    }
+
+    type ReceivedRequests = Arc<Mutex<Vec<String>>>;
+
+    fn scrobble() -> Scrobble {
+        Scrobble {
+            artist: String::from("Rockers"),
+            title: String::from("Awesome medley"),
+            album: None,
+            timestamp: 1700000000,
+            duration: Duration::from_secs(180),
+        }
+    }
+
+    /// Starts HTTP server answering given number of requests with success,
+    /// returning its URL and received requests
+    fn mock_server(request_count: usize) -> Result<(String, ReceivedRequests), std::io::Error> {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let url = format!("http://{}", listener.local_addr()?);
+        let requests = Arc::new(Mutex::new(vec![]));
+        let received = requests.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming().take(request_count) {
+                let mut stream = stream.unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut request = String::new();
+                let mut content_length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if let Some(length) = line.to_lowercase().strip_prefix("content-length:") {
+                        content_length = length.trim().parse().unwrap();
+                    }
+                    request.push_str(&line);
+                    if line == "\r\n" {
+                        break;
+                    }
+                }
+                let mut body = vec![0u8; content_length];
+                reader.read_exact(&mut body).unwrap();
+                request.push_str(&String::from_utf8_lossy(&body));
+                received.lock().unwrap().push(request);
+                write!(
+                    stream,
+                    "HTTP/1.1 200 OK\r\nContent-Length: 15\r\nConnection: close\r\n\r\n{{\"status\":\"ok\"}}"
+                )
+                .unwrap();
+            }
+        });
+        Ok((url, requests))
+    }
+}
@@ -0,0 +1,70 @@
+use log::{error, warn};
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+/// Bookmarked directories, saved in file with one path per line
+pub struct Bookmarks {
+    file: PathBuf,
+    pub directories: Vec<String>,
+}
+
+impl Bookmarks {
+    /// Reads bookmarks from given file, missing file means no bookmarks
+    pub fn load(file: &Path) -> Self {
+        let directories = match fs::read_to_string(file) {
+            Ok(content) => content
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty())
+                .map(String::from)
+                .collect(),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => vec![],
+            Err(e) => {
+                warn!("Cannot read bookmarks from {}: {e}", file.display());
+                vec![]
+            }
+        };
+        Bookmarks {
+            file: file.to_path_buf(),
+            directories,
+        }
+    }
+
+    /// Bookmarks directory or removes its bookmark if it is already bookmarked.
+    /// Returns true if directory was bookmarked.
+    pub fn toggle(&mut self, directory: &str) -> bool {
+        let added = match self.directories.iter().position(|d| d == directory) {
+            Some(index) => {
+                self.directories.remove(index);
+                false
+            }
+            None => {
+                self.directories.push(String::from(directory));
+                true
+            }
+        };
+        self.save();
+        added
+    }
+
+    pub fn remove(&mut self, index: usize) {
+        if index < self.directories.len() {
+            self.directories.remove(index);
+            self.save();
+        }
+    }
+
+    fn save(&self) {
+        let content: String = self.directories.iter().map(|d| format!("{d}\n")).collect();
+        let result = match self.file.parent() {
+            Some(directory) => fs::create_dir_all(directory),
+            None => Ok(()),
+        }
+        .and_then(|_| fs::write(&self.file, content));
+        if let Err(e) = result {
+            error!("Failed to save bookmarks to {}: {e}", self.file.display());
+        }
+    }
+}
@@ -0,0 +1,31 @@
+#[cfg(test)]
+mod tests {
+    use std::io;
+
+    use tempdir::TempDir;
+
+    use crate::files::bookmarks::Bookmarks;
+
+    #[test]
+    fn should_save_toggled_bookmarks() -> Result<(), io::Error> {
+        // given
+        let tmp_dir = TempDir::new("bookmarks")?;
+        let file = tmp_dir.path().join("config").join("bookmarks");
+        let mut bookmarks = Bookmarks::load(&file);
+
+        // when
+        let jazz_added = bookmarks.toggle("/music/jazz");
+        bookmarks.toggle("/music/rock");
+        bookmarks.toggle("/music/blues");
+        let rock_added = bookmarks.toggle("/music/rock");
+        bookmarks.remove(1);
+        let result = Bookmarks::load(&file);
+
+        // then
+        assert!(jazz_added);
+        assert!(!rock_added);
+        assert_eq!(result.directories, vec!["/music/jazz"]);
+
+        Ok(())
+    }
+}
@@ -1,5 +1,7 @@
 use std::path::Path;
 
+use super::listing::is_audio_file;
+
 /// Structure representing file in file viewer.
 #[derive(Debug, Eq, Ord, PartialEq, PartialOrd, Clone)]
 pub struct FileEntry {
@@ -24,4 +26,9 @@ impl FileEntry {
             is_file: path.is_file(),
         }
     }
+
+    /// Returns true if entry is mp3 file which can be played
+    pub fn is_audio(&self) -> bool {
+        self.is_file && is_audio_file(Path::new(&self.path))
+    }
 }
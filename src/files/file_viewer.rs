@@ -1,10 +1,16 @@
-use log::{error, trace};
+use log::{error, info, trace, warn};
 use ratatui::widgets::ListState;
-use std::{fs, io, path::Path};
+use std::path::Path;
 
-use crate::application::actions::Action;
+use crate::{application::actions::Action, error::AppError};
 
-use super::FileEntry;
+use super::{
+    listing::{list_directory, ListingOptions},
+    FileEntry,
+};
+
+/// Number of directories remembered for jumping back
+const MAX_JUMP_HISTORY: usize = 50;
 
 /// File viewer for traversing filesystem and selecting mp3 files
 /// for playback
@@ -15,40 +21,64 @@ pub struct FileViewerList {
     /// Only contains directories and mp3 files
     pub items: Vec<FileEntry>,
     pub current_directory: String,
+    /// Sorting and filtering of listed entries
+    pub options: ListingOptions,
     /// Contains index of item selected before file viewer
     /// lost it's focus
     previously_selected_index: Option<usize>,
     /// Contains index of selected item in parent directory.
     /// Used to focus item when going to parent dir
     parent_selected_index: Option<usize>,
+    /// Directories left by jumping to other directory, most recent last
+    jump_history: Vec<String>,
 }
 
 impl FileViewerList {
     /// Creates new File Viewer for given directory
-    pub fn with_directory(dir_name: &str) -> Option<Self> {
-        FileViewerList::list_directory_content(dir_name)
+    pub fn with_directory(dir_name: &str) -> Result<Self, AppError> {
+        list(dir_name, &ListingOptions::default())
             .map(|entries| FileViewerList {
                 items: entries,
                 state: ListState::default(),
                 current_directory: dir_name.to_string(),
+                options: ListingOptions::default(),
                 previously_selected_index: None,
                 parent_selected_index: None,
+                jump_history: vec![],
             })
             .map(|mut viewer| {
                 viewer.focus_first_entry_if_available();
                 viewer
             })
-            .ok()
     }
 
-    pub fn do_action(&mut self, action: Action) {
+    /// Performs action, returning error if directory cannot be listed
+    pub fn do_action(&mut self, action: Action) -> Result<(), AppError> {
         match action {
             Action::ViewerUp => self.previous(),
             Action::ViewerDown => self.next(),
-            Action::FileViewerDirUp => self.go_directory_up(),
-            Action::FileViewerEnterDir => self.enter_directory(),
+            Action::FileViewerDirUp => return self.go_directory_up(),
+            Action::FileViewerEnterDir => return self.enter_directory(),
+            Action::ChangeSortOrder => {
+                self.options.sort_order = self.options.sort_order.next();
+                info!("Sorting files by {}", self.options.sort_order.display());
+                return self.reload();
+            }
+            Action::ToggleDirectoriesFirst => {
+                self.options.directories_first = !self.options.directories_first;
+                return self.reload();
+            }
+            Action::ToggleHiddenFiles => {
+                self.options.show_hidden = !self.options.show_hidden;
+                return self.reload();
+            }
+            Action::ToggleNonAudioFiles => {
+                self.options.show_non_audio = !self.options.show_non_audio;
+                return self.reload();
+            }
             _ => error!("Unsupported file viewer action: {action:?}"),
         }
+        Ok(())
     }
 
     /// Focuses file viewer allowing moving through filesystem and file picking
@@ -74,43 +104,100 @@ impl FileViewerList {
         self.state.selected().map(|i| &self.items[i])
     }
 
-    fn go_directory_up(&mut self) {
-        let path = Path::new(&self.current_directory)
+    /// Lists given directory, remembering current one so it can be returned to
+    /// with [jump_back](FileViewerList::jump_back)
+    pub fn jump_to(&mut self, directory: &str) -> Result<(), AppError> {
+        let items = list(directory, &self.options)?;
+        if directory != self.current_directory {
+            self.jump_history.push(self.current_directory.clone());
+            if self.jump_history.len() > MAX_JUMP_HISTORY {
+                self.jump_history.remove(0);
+            }
+        }
+        self.show_directory(directory, items);
+        Ok(())
+    }
+
+    /// Returns to directory listed before last jump, skipping directories which cannot be listed.
+    /// Returns false if there is no such directory.
+    pub fn jump_back(&mut self) -> bool {
+        while let Some(directory) = self.jump_history.pop() {
+            match list_directory(&directory, &self.options) {
+                Ok(items) => {
+                    self.show_directory(&directory, items);
+                    return true;
+                }
+                Err(e) => warn!("Cannot return to directory {directory}: {e}"),
+            }
+        }
+        false
+    }
+
+    fn show_directory(&mut self, directory: &str, items: Vec<FileEntry>) {
+        self.current_directory = String::from(directory);
+        self.items = items;
+        self.parent_selected_index = None;
+        self.previously_selected_index = None;
+        if self.state.selected().is_some() {
+            self.focus_first_entry_if_available();
+        }
+    }
+
+    /// Lists current directory again, keeping selected entry if it is still listed.
+    /// Previous entries are kept if directory cannot be listed.
+    pub fn reload(&mut self) -> Result<(), AppError> {
+        let path_at = |items: &[FileEntry], index: Option<usize>| {
+            index.and_then(|i| items.get(i)).map(|e| e.path.clone())
+        };
+        let selected_path = path_at(&self.items, self.state.selected());
+        let previously_selected_path = path_at(&self.items, self.previously_selected_index);
+        self.items = list(&self.current_directory, &self.options)?;
+        let position = |path: Option<String>| {
+            path.and_then(|path| self.items.iter().position(|e| e.path == path))
+        };
+        let selected = position(selected_path);
+        // Unfocused viewer returns to the same entry once focused again
+        self.previously_selected_index = position(previously_selected_path);
+        match (selected, self.state.selected()) {
+            (Some(index), _) => self.state.select(Some(index)),
+            (None, Some(_)) => self.focus_first_entry_if_available(),
+            (None, None) => {}
+        }
+        Ok(())
+    }
+
+    /// Lists parent directory, staying in current one if parent cannot be listed
+    fn go_directory_up(&mut self) -> Result<(), AppError> {
+        let Some(parent) = Path::new(&self.current_directory)
             .parent()
-            .map(|dir| dir.to_string_lossy().into_owned());
-        let new_path = match path {
-            Some(x) => x,
-            None => self.current_directory.to_string(),
+            .map(|dir| dir.to_string_lossy().into_owned())
+        else {
+            return Ok(());
         };
-        self.current_directory = new_path;
-        self.items = FileViewerList::list_directory_content(&self.current_directory).unwrap();
-        match self.parent_selected_index {
-            Some(_) => self.state.select(self.parent_selected_index),
+        self.items = list(&parent, &self.options)?;
+        self.current_directory = parent;
+        match self.parent_selected_index.filter(|i| *i < self.items.len()) {
+            Some(index) => self.state.select(Some(index)),
             None => self.focus_first_entry_if_available(),
         };
         self.parent_selected_index = None;
+        Ok(())
     }
 
-    fn enter_directory(&mut self) {
+    fn enter_directory(&mut self) -> Result<(), AppError> {
         let maybe_selected_entry = self.state.selected().map(|i| &self.items[i]);
 
         if let Some(entry) = maybe_selected_entry {
             if Path::new(&entry.path).is_file() {
-                return;
+                return Ok(());
             }
-            match FileViewerList::list_directory_content(&entry.path) {
-                Ok(items) => {
-                    self.parent_selected_index = self.state.selected();
-                    self.current_directory.clone_from(&entry.path);
-                    self.items = items;
-                    self.focus_first_entry_if_available();
-                }
-                Err(_) => error!(
-                    "Missing permission to list files in directory {}!",
-                    entry.path
-                ),
-            };
+            let items = list(&entry.path, &self.options)?;
+            self.parent_selected_index = self.state.selected();
+            self.current_directory.clone_from(&entry.path);
+            self.items = items;
+            self.focus_first_entry_if_available();
         };
+        Ok(())
     }
 
     fn next(&mut self) {
@@ -145,20 +232,6 @@ impl FileViewerList {
         }
     }
 
-    fn list_directory_content(dir_name: &str) -> io::Result<Vec<FileEntry>> {
-        let mut file_list = fs::read_dir(dir_name)?
-            .filter_map(|e| e.ok())
-            .map(|e| e.path())
-            .filter(|e| !e.file_name().unwrap().to_string_lossy().starts_with('.'))
-            .filter(|e| e.is_dir() || e.file_name().unwrap().to_string_lossy().ends_with(".mp3"))
-            .map(|e| FileEntry::new(&e))
-            .collect::<Vec<_>>();
-
-        file_list.sort_by(|a, b| a.name.cmp(&b.name));
-
-        Ok(file_list)
-    }
-
     fn focus_first_entry_if_available(&mut self) {
         if !self.items.is_empty() {
             self.state.select(Some(0));
@@ -167,3 +240,11 @@ impl FileViewerList {
         }
     }
 }
+
+/// Lists directory, reporting failure as [AppError]
+fn list(directory: &str, options: &ListingOptions) -> Result<Vec<FileEntry>, AppError> {
+    list_directory(directory, options).map_err(|source| AppError::ListDirectory {
+        path: String::from(directory),
+        source,
+    })
+}
@@ -1,10 +1,17 @@
 #[cfg(test)]
 mod tests {
-    use std::{fs::File, io};
+    use std::{
+        fs::{self, File},
+        io,
+    };
 
     use tempdir::TempDir;
 
-    use crate::files::{FileEntry, FileViewerList};
+    use crate::{
+        application::actions::Action,
+        error::AppError,
+        files::{FileEntry, FileViewerList},
+    };
 
     #[test]
     fn should_create_file_viewer_for_given_dir() -> Result<(), io::Error> {
@@ -15,7 +22,7 @@ mod tests {
         let result = FileViewerList::with_directory(directory.path().to_str().unwrap());
 
         // then
-        assert!(result.is_some());
+        assert!(result.is_ok());
 
         Ok(())
     }
@@ -37,4 +44,78 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn should_jump_back_to_directories_left_by_jumps() -> Result<(), Box<dyn std::error::Error>> {
+        // given
+        let directory = TempDir::new("music")?;
+        let jazz = directory.path().join("jazz");
+        let rock = directory.path().join("rock");
+        fs::create_dir(&jazz)?;
+        fs::create_dir(&rock)?;
+        let start = directory.path().to_str().unwrap();
+        let mut viewer = FileViewerList::with_directory(start).unwrap();
+
+        // when
+        viewer.jump_to(jazz.to_str().unwrap())?;
+        viewer.jump_to(rock.to_str().unwrap())?;
+        let first_jump_back = viewer.jump_back();
+        let directory_after_first = viewer.current_directory.clone();
+        viewer.jump_back();
+        let last_jump_back = viewer.jump_back();
+
+        // then
+        assert!(first_jump_back);
+        assert_eq!(directory_after_first, jazz.to_str().unwrap());
+        assert_eq!(viewer.current_directory, start);
+        assert!(!last_jump_back);
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_stay_in_directory_when_parent_cannot_be_listed() -> Result<(), io::Error> {
+        // given
+        let directory = TempDir::new("music")?;
+        let album = directory.path().join("artist").join("album");
+        fs::create_dir_all(&album)?;
+        let album = album.to_str().unwrap();
+        let mut viewer = FileViewerList::with_directory(album).unwrap();
+        fs::remove_dir_all(directory.path().join("artist"))?;
+
+        // when
+        let result = viewer.do_action(Action::FileViewerDirUp);
+
+        // then
+        assert!(matches!(result, Err(AppError::ListDirectory { .. })));
+        assert_eq!(viewer.current_directory, album);
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_keep_entry_selected_before_focus_was_lost_on_reload() -> Result<(), io::Error> {
+        // given
+        let directory = TempDir::new("music")?;
+        File::create(directory.path().join("b.mp3"))?;
+        let song = directory.path().join("c.mp3");
+        File::create(&song)?;
+        let mut viewer =
+            FileViewerList::with_directory(directory.path().to_str().unwrap()).unwrap();
+        viewer.do_action(Action::ViewerDown).unwrap();
+        viewer.toggle_focus();
+        File::create(directory.path().join("a.mp3"))?;
+
+        // when
+        viewer.reload().unwrap();
+        viewer.toggle_focus();
+
+        // then
+        assert_eq!(
+            viewer.get_selected_file_entry(),
+            Some(&FileEntry::new(&song))
+        );
+
+        Ok(())
+    }
 }
@@ -0,0 +1,144 @@
+use id3::{Tag, TagLike};
+use std::{cmp::Ordering, cmp::Reverse, fs, io, path::Path, time::SystemTime};
+
+use super::FileEntry;
+
+/// Order of entries listed in file viewer, cycled with `n` key
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum SortOrder {
+    /// Names compared character by character
+    #[default]
+    Name,
+    /// Names compared ignoring case, with numbers compared by value, so `2` goes before `10`
+    Natural,
+    /// Most recently modified first
+    Modified,
+    /// Largest first
+    Size,
+    /// Track number from ID3 tag, entries without it last
+    Track,
+}
+
+impl SortOrder {
+    pub fn next(self) -> SortOrder {
+        match self {
+            SortOrder::Name => SortOrder::Natural,
+            SortOrder::Natural => SortOrder::Modified,
+            SortOrder::Modified => SortOrder::Size,
+            SortOrder::Size => SortOrder::Track,
+            SortOrder::Track => SortOrder::Name,
+        }
+    }
+
+    pub fn display(self) -> &'static str {
+        match self {
+            SortOrder::Name => "name",
+            SortOrder::Natural => "natural",
+            SortOrder::Modified => "modified",
+            SortOrder::Size => "size",
+            SortOrder::Track => "track",
+        }
+    }
+}
+
+/// Options of listing directory content in file viewer
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ListingOptions {
+    pub sort_order: SortOrder,
+    /// Directories are listed before files, each group sorted separately
+    pub directories_first: bool,
+    /// Entries with names starting with dot are listed
+    pub show_hidden: bool,
+    /// Files other than mp3 are listed, they cannot be played
+    pub show_non_audio: bool,
+}
+
+/// Lists directories and files of given directory according to options
+pub fn list_directory(dir_name: &str, options: &ListingOptions) -> io::Result<Vec<FileEntry>> {
+    let mut entries = fs::read_dir(dir_name)?
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|e| {
+            options.show_hidden
+                || !e
+                    .file_name()
+                    .is_some_and(|n| n.to_string_lossy().starts_with('.'))
+        })
+        .filter(|e| e.is_dir() || is_audio_file(e) || (options.show_non_audio && e.is_file()))
+        .map(|e| FileEntry::new(&e))
+        .collect::<Vec<_>>();
+
+    match options.sort_order {
+        SortOrder::Name => entries.sort_by(|a, b| a.name.cmp(&b.name)),
+        SortOrder::Natural => entries.sort_by(|a, b| natural_cmp(&a.name, &b.name)),
+        SortOrder::Modified => entries.sort_by_cached_key(|e| {
+            let modified = fs::metadata(&e.path).and_then(|m| m.modified()).ok();
+            (
+                Reverse(modified.unwrap_or(SystemTime::UNIX_EPOCH)),
+                e.name.clone(),
+            )
+        }),
+        SortOrder::Size => entries.sort_by_cached_key(|e| {
+            let size = fs::metadata(&e.path).map_or(0, |m| m.len());
+            (Reverse(size), e.name.clone())
+        }),
+        SortOrder::Track => entries.sort_by_cached_key(|e| {
+            let track = match e.is_audio() {
+                true => Tag::read_from_path(&e.path).ok().and_then(|t| t.track()),
+                false => None,
+            };
+            (track.unwrap_or(u32::MAX), e.name.clone())
+        }),
+    }
+    if options.directories_first {
+        // Stable sort keeps selected order inside both groups
+        entries.sort_by_key(|e| e.is_file);
+    }
+
+    Ok(entries)
+}
+
+/// Returns true if path has mp3 extension
+pub fn is_audio_file(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|e| e.eq_ignore_ascii_case("mp3"))
+}
+
+/// Compares strings ignoring case, treating runs of digits as numbers
+pub fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let (mut a, mut b) = (a.chars().peekable(), b.chars().peekable());
+    loop {
+        match (a.peek().copied(), b.peek().copied()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) if x.is_ascii_digit() && y.is_ascii_digit() => {
+                let take_number = |chars: &mut std::iter::Peekable<std::str::Chars>| {
+                    let mut digits = String::new();
+                    while let Some(c) = chars.next_if(|c| c.is_ascii_digit()) {
+                        digits.push(c);
+                    }
+                    digits
+                };
+                let (x, y) = (take_number(&mut a), take_number(&mut b));
+                let (x_trimmed, y_trimmed) = (x.trim_start_matches('0'), y.trim_start_matches('0'));
+                let ordering = x_trimmed
+                    .len()
+                    .cmp(&y_trimmed.len())
+                    .then_with(|| x_trimmed.cmp(y_trimmed))
+                    .then_with(|| x.len().cmp(&y.len()));
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+            }
+            (Some(x), Some(y)) => {
+                let ordering = x.to_lowercase().cmp(y.to_lowercase());
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+                a.next();
+                b.next();
+            }
+        }
+    }
+}
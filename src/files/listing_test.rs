@@ -0,0 +1,108 @@
+#[cfg(test)]
+mod tests {
+    use std::{
+        cmp::Ordering,
+        fs::{self, File},
+        io,
+    };
+
+    use tempdir::TempDir;
+
+    use crate::files::listing::{list_directory, natural_cmp, ListingOptions, SortOrder};
+
+    fn names(directory: &TempDir, options: &ListingOptions) -> io::Result<Vec<String>> {
+        Ok(list_directory(directory.path().to_str().unwrap(), options)?
+            .into_iter()
+            .map(|e| e.name)
+            .collect())
+    }
+
+    #[test]
+    fn should_compare_numbers_by_value() {
+        // when
+        let result = [
+            natural_cmp("track 2.mp3", "track 10.mp3"),
+            natural_cmp("Disc 1", "disc 1"),
+            natural_cmp("a10b", "a9c"),
+            natural_cmp("b", "A"),
+        ];
+
+        // then
+        assert_eq!(
+            result,
+            [
+                Ordering::Less,
+                Ordering::Equal,
+                Ordering::Greater,
+                Ordering::Greater
+            ]
+        );
+    }
+
+    #[test]
+    fn should_sort_naturally_with_directories_first() -> Result<(), io::Error> {
+        // given
+        let directory = TempDir::new("music")?;
+        File::create(directory.path().join("10 outro.mp3"))?;
+        File::create(directory.path().join("2 intro.mp3"))?;
+        fs::create_dir(directory.path().join("z bonus"))?;
+        let options = ListingOptions {
+            sort_order: SortOrder::Natural,
+            directories_first: true,
+            ..ListingOptions::default()
+        };
+
+        // when
+        let result = names(&directory, &options)?;
+
+        // then
+        assert_eq!(result, vec!["z bonus", "2 intro.mp3", "10 outro.mp3"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_list_hidden_and_non_audio_files_when_enabled() -> Result<(), io::Error> {
+        // given
+        let directory = TempDir::new("music")?;
+        File::create(directory.path().join(".hidden.mp3"))?;
+        File::create(directory.path().join("cover.jpg"))?;
+        File::create(directory.path().join("song.MP3"))?;
+        let options = ListingOptions {
+            show_hidden: true,
+            show_non_audio: true,
+            ..ListingOptions::default()
+        };
+
+        // when
+        let default = names(&directory, &ListingOptions::default())?;
+        let all = names(&directory, &options)?;
+
+        // then
+        assert_eq!(default, vec!["song.MP3"]);
+        assert_eq!(all, vec![".hidden.mp3", "cover.jpg", "song.MP3"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_sort_by_size_largest_first() -> Result<(), io::Error> {
+        // given
+        let directory = TempDir::new("music")?;
+        fs::write(directory.path().join("a.mp3"), [0; 10])?;
+        fs::write(directory.path().join("b.mp3"), [0; 30])?;
+        fs::write(directory.path().join("c.mp3"), [0; 20])?;
+        let options = ListingOptions {
+            sort_order: SortOrder::Size,
+            ..ListingOptions::default()
+        };
+
+        // when
+        let result = names(&directory, &options)?;
+
+        // then
+        assert_eq!(result, vec!["b.mp3", "c.mp3", "a.mp3"]);
+
+        Ok(())
+    }
+}
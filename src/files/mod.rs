@@ -1,12 +1,27 @@
 //! Module containing implementation of file selecting
 //! and traversing file system for mp3 file selection
+pub mod bookmarks;
 pub mod file_entry;
 pub mod file_viewer;
+pub mod listing;
+pub mod path_completion;
+pub mod preview;
+pub mod watcher;
 
 pub use file_entry::FileEntry;
 pub use file_viewer::FileViewerList;
 
+#[cfg(test)]
+mod bookmarks_test;
 #[cfg(test)]
 mod file_entry_test;
 #[cfg(test)]
 mod file_viewer_test;
+#[cfg(test)]
+mod listing_test;
+#[cfg(test)]
+mod path_completion_test;
+#[cfg(test)]
+mod preview_test;
+#[cfg(test)]
+mod watcher_test;
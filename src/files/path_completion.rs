@@ -0,0 +1,61 @@
+use std::{env::var, fs, path::Path};
+
+/// Replaces leading `~` with home directory
+pub fn expand_home(input: &str) -> String {
+    match input.strip_prefix('~') {
+        Some(rest) if rest.is_empty() || rest.starts_with('/') => {
+            format!("{}{rest}", var("HOME").unwrap_or_default())
+        }
+        _ => String::from(input),
+    }
+}
+
+/// Returns directories whose paths start with given input, sorted by name.
+/// Hidden directories are only returned when input names them with leading dot.
+pub fn directory_candidates(input: &str) -> Vec<String> {
+    let expanded = expand_home(input);
+    let (parent, prefix) = match expanded.rfind('/') {
+        Some(index) => (&expanded[..=index], &expanded[index + 1..]),
+        None => ("./", expanded.as_str()),
+    };
+    let Ok(entries) = fs::read_dir(Path::new(parent)) else {
+        return vec![];
+    };
+    let mut candidates: Vec<String> = entries
+        .flatten()
+        .filter(|e| e.path().is_dir())
+        .map(|e| e.file_name().to_string_lossy().to_string())
+        .filter(|name| {
+            name.starts_with(prefix) && (prefix.starts_with('.') || !name.starts_with('.'))
+        })
+        .map(|name| format!("{parent}{name}"))
+        .collect();
+    candidates.sort();
+    candidates
+}
+
+/// Completes input to the longest path shared by all matching directories,
+/// adding trailing slash when single directory matches
+pub fn complete_path(input: &str) -> String {
+    let candidates = directory_candidates(input);
+    match candidates.as_slice() {
+        [] => String::from(input),
+        [single] => format!("{single}/"),
+        [first, rest @ ..] => {
+            let common = rest.iter().fold(first.len(), |length, candidate| {
+                first
+                    .chars()
+                    .zip(candidate.chars())
+                    .take_while(|(a, b)| a == b)
+                    .map(|(a, _)| a.len_utf8())
+                    .sum::<usize>()
+                    .min(length)
+            });
+            let completed = &first[..common];
+            match completed.len() > expand_home(input).len() {
+                true => String::from(completed),
+                false => String::from(input),
+            }
+        }
+    }
+}
@@ -0,0 +1,49 @@
+#[cfg(test)]
+mod tests {
+    use std::{fs, io};
+
+    use tempdir::TempDir;
+
+    use crate::files::path_completion::{complete_path, directory_candidates};
+
+    #[test]
+    fn should_complete_single_matching_directory() -> Result<(), io::Error> {
+        // given
+        let tmp_dir = TempDir::new("completion")?;
+        fs::create_dir(tmp_dir.path().join("jazz"))?;
+        fs::create_dir(tmp_dir.path().join("rock"))?;
+        fs::write(tmp_dir.path().join("jingle.mp3"), [])?;
+        let root = tmp_dir.path().to_string_lossy();
+
+        // when
+        let result = complete_path(&format!("{root}/j"));
+
+        // then
+        assert_eq!(result, format!("{root}/jazz/"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_complete_common_prefix_of_many_directories() -> Result<(), io::Error> {
+        // given
+        let tmp_dir = TempDir::new("completion")?;
+        fs::create_dir(tmp_dir.path().join("album_1"))?;
+        fs::create_dir(tmp_dir.path().join("album_2"))?;
+        fs::create_dir(tmp_dir.path().join(".albums"))?;
+        let root = tmp_dir.path().to_string_lossy();
+
+        // when
+        let result = complete_path(&format!("{root}/a"));
+        let candidates = directory_candidates(&format!("{root}/"));
+
+        // then
+        assert_eq!(result, format!("{root}/album_"));
+        assert_eq!(
+            candidates,
+            vec![format!("{root}/album_1"), format!("{root}/album_2")]
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,298 @@
+use chrono::{DateTime, Local};
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc, Condvar, Mutex,
+    },
+    thread,
+    time::{Duration, SystemTime},
+};
+
+use crate::player::{
+    duration_formatter::{DurationFormat, DurationFormatter},
+    metadata::Mp3Metadata,
+    MetadataReader,
+};
+
+use super::FileEntry;
+
+/// Depth of subdirectories included in directory summary, 0 covers only direct children
+const SUMMARY_DEPTH: usize = 2;
+/// Number of tracks read at most for directory summary
+const SUMMARY_TRACK_LIMIT: usize = 200;
+
+/// Details of single mp3 file
+#[derive(Clone)]
+pub struct FileDetails {
+    pub metadata: Option<Mp3Metadata>,
+    pub duration: Duration,
+    /// Size of file in bytes
+    pub size: u64,
+    pub modified: Option<SystemTime>,
+}
+
+impl FileDetails {
+    /// Returns average bitrate in kbps computed from file size and duration
+    pub fn bitrate(&self) -> Option<u64> {
+        match self.duration.as_millis() {
+            0 => None,
+            millis => Some(self.size * 8 / millis as u64),
+        }
+    }
+}
+
+/// Summary of mp3 files below directory, up to limited depth and number of tracks
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DirectorySummary {
+    pub tracks: usize,
+    pub duration: Duration,
+    /// Summed size of tracks in bytes
+    pub size: u64,
+    /// Directory contains more tracks than were summarized
+    pub truncated: bool,
+}
+
+/// Information shown in preview of file viewer entry
+#[derive(Clone)]
+pub enum Preview {
+    File(FileDetails),
+    Directory(DirectorySummary),
+}
+
+impl Preview {
+    /// Reads details of given entry, directories are scanned up to [SUMMARY_DEPTH].
+    /// Returns `None` if loading was cancelled before it finished.
+    pub fn load(entry: &FileEntry, cancelled: &dyn Fn() -> bool) -> Option<Self> {
+        if entry.is_file {
+            return Some(Preview::File(read_file_details(entry)));
+        }
+        let mut files = vec![];
+        let complete = collect_tracks(Path::new(&entry.path), SUMMARY_DEPTH, &mut files);
+        let mut summary = DirectorySummary {
+            truncated: !complete,
+            ..DirectorySummary::default()
+        };
+        for file in files {
+            if cancelled() {
+                return None;
+            }
+            let details = read_file_details(&FileEntry::new(&file));
+            summary.tracks += 1;
+            summary.duration += details.duration;
+            summary.size += details.size;
+        }
+        Some(Preview::Directory(summary))
+    }
+
+    /// Returns lines of `<label>: <value>` displayed in preview pane
+    pub fn display(&self) -> Vec<String> {
+        match self {
+            Preview::File(details) => {
+                let mut lines = vec![];
+                if let Some(metadata) = &details.metadata {
+                    let tags = [
+                        ("Artist  ", metadata.artist.clone()),
+                        ("Title   ", metadata.title.clone()),
+                        ("Album   ", metadata.album.clone()),
+                        ("Track   ", metadata.track.map(|t| t.to_string())),
+                        ("Genre   ", metadata.genre.clone()),
+                        ("Year    ", metadata.year.map(|y| y.to_string())),
+                    ];
+                    lines.extend(
+                        tags.into_iter()
+                            .filter_map(|(label, value)| value.map(|v| format!("{label}: {v}"))),
+                    );
+                }
+                lines.push(format!(
+                    "Duration: {}",
+                    details.duration.format(DurationFormat::MmSs)
+                ));
+                if let Some(bitrate) = details.bitrate() {
+                    lines.push(format!("Bitrate : {bitrate} kbps"));
+                }
+                lines.push(format!("Size    : {}", format_size(details.size)));
+                if let Some(modified) = details.modified {
+                    let modified: DateTime<Local> = modified.into();
+                    lines.push(format!("Modified: {}", modified.format("%Y-%m-%d %H:%M")));
+                }
+                lines
+            }
+            Preview::Directory(summary) => {
+                // Totals are lower bounds if not all tracks were read
+                let more = match summary.truncated {
+                    true => "+",
+                    false => "",
+                };
+                vec![
+                    format!("Tracks  : {}{more}", summary.tracks),
+                    format!(
+                        "Duration: {}{more}",
+                        summary.duration.format(DurationFormat::HhMmSs)
+                    ),
+                    format!("Size    : {}{more}", format_size(summary.size)),
+                ]
+            }
+        }
+    }
+}
+
+/// Collects mp3 files below directory, descending at most `depth` levels
+/// and skipping symlinked directories. Returns false if [SUMMARY_TRACK_LIMIT] was reached.
+fn collect_tracks(directory: &Path, depth: usize, files: &mut Vec<PathBuf>) -> bool {
+    let Ok(entries) = fs::read_dir(directory) else {
+        return true;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if entry.file_type().is_ok_and(|t| t.is_dir()) {
+            if depth > 0 && !collect_tracks(&path, depth - 1, files) {
+                return false;
+            }
+        } else if path.extension().is_some_and(|e| e == "mp3") {
+            if files.len() == SUMMARY_TRACK_LIMIT {
+                return false;
+            }
+            files.push(path);
+        }
+    }
+    true
+}
+
+fn read_file_details(entry: &FileEntry) -> FileDetails {
+    let path = Path::new(&entry.path);
+    let file_metadata = fs::metadata(path).ok();
+    FileDetails {
+        metadata: MetadataReader::read_metadata(entry),
+        duration: mp3_duration::from_path(path).unwrap_or(Duration::ZERO),
+        size: file_metadata.as_ref().map_or(0, |m| m.len()),
+        modified: file_metadata.and_then(|m| m.modified().ok()),
+    }
+}
+
+/// Returns size in bytes formatted with binary unit
+pub fn format_size(size: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+    let mut value = size as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    match unit {
+        0 => format!("{size} B"),
+        _ => format!("{value:.1} {}", UNITS[unit]),
+    }
+}
+
+/// Entry waiting for its preview, newer request replaces older one
+#[derive(Default)]
+struct PreviewRequests {
+    requested: Mutex<Option<FileEntry>>,
+    changed: Condvar,
+    /// Increased with every request, so worker can abandon entry which is no longer shown
+    generation: AtomicUsize,
+    closed: AtomicBool,
+}
+
+/// Loads previews of file viewer entries in single background thread, so browsing is not slowed
+/// down by reading tags or scanning large directories. Only preview of most recently requested
+/// entry is loaded, older requests are cancelled. Loaded previews are kept until files change.
+pub struct PreviewLoader {
+    previews: Arc<Mutex<HashMap<String, Preview>>>,
+    requests: Arc<PreviewRequests>,
+    /// Path of entry being loaded by worker
+    loading: Arc<Mutex<Option<String>>>,
+}
+
+impl PreviewLoader {
+    pub fn new() -> Self {
+        let loader = PreviewLoader {
+            previews: Arc::new(Mutex::new(HashMap::new())),
+            requests: Arc::new(PreviewRequests::default()),
+            loading: Arc::new(Mutex::new(None)),
+        };
+        let (previews, requests, loading) = (
+            loader.previews.clone(),
+            loader.requests.clone(),
+            loader.loading.clone(),
+        );
+        thread::spawn(move || Self::load_previews(previews, requests, loading));
+        loader
+    }
+
+    /// Returns preview of entry if already loaded, otherwise requests loading it
+    pub fn get(&self, entry: &FileEntry) -> Option<Preview> {
+        if let Some(preview) = self.previews.lock().unwrap().get(&entry.path) {
+            return Some(preview.clone());
+        }
+        let mut requested = self.requests.requested.lock().unwrap();
+        let is_loading = self.loading.lock().unwrap().as_ref() == Some(&entry.path);
+        let is_requested = requested.as_ref().is_some_and(|r| r.path == entry.path);
+        // Entry being loaded is not requested again, unless other entry would cancel it
+        let pending = is_requested || (is_loading && requested.is_none());
+        if !pending {
+            *requested = Some(entry.clone());
+            self.requests.generation.fetch_add(1, Ordering::Relaxed);
+            self.requests.changed.notify_all();
+        }
+        None
+    }
+
+    /// Returns true while preview is being loaded
+    pub fn is_loading(&self) -> bool {
+        self.loading.lock().unwrap().is_some() || self.requests.requested.lock().unwrap().is_some()
+    }
+
+    /// Forgets loaded previews of changed paths and directories containing them,
+    /// so they are read again when shown
+    pub fn invalidate(&self, changed_paths: &[String]) {
+        self.previews.lock().unwrap().retain(|path, _| {
+            !changed_paths
+                .iter()
+                .any(|changed| Path::new(changed).starts_with(path))
+        });
+    }
+
+    fn load_previews(
+        previews: Arc<Mutex<HashMap<String, Preview>>>,
+        requests: Arc<PreviewRequests>,
+        loading: Arc<Mutex<Option<String>>>,
+    ) {
+        loop {
+            let entry = {
+                let mut requested = requests
+                    .changed
+                    .wait_while(requests.requested.lock().unwrap(), |r| {
+                        r.is_none() && !requests.closed.load(Ordering::Relaxed)
+                    })
+                    .unwrap();
+                match requested.take() {
+                    Some(entry) if !requests.closed.load(Ordering::Relaxed) => entry,
+                    _ => break,
+                }
+            };
+            let generation = requests.generation.load(Ordering::Relaxed);
+            *loading.lock().unwrap() = Some(entry.path.clone());
+            let cancelled = || {
+                requests.generation.load(Ordering::Relaxed) != generation
+                    || requests.closed.load(Ordering::Relaxed)
+            };
+            if let Some(preview) = Preview::load(&entry, &cancelled) {
+                previews.lock().unwrap().insert(entry.path.clone(), preview);
+            }
+            *loading.lock().unwrap() = None;
+        }
+    }
+}
+
+impl Drop for PreviewLoader {
+    fn drop(&mut self) {
+        // Lock is held, so worker cannot miss notification between check and wait
+        let _requested = self.requests.requested.lock().unwrap();
+        self.requests.closed.store(true, Ordering::Relaxed);
+        self.requests.changed.notify_all();
+    }
+}
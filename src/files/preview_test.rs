@@ -0,0 +1,118 @@
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs::{self, File},
+        os::unix::fs::symlink,
+    };
+
+    use id3::{Tag, TagLike};
+    use tempdir::TempDir;
+
+    use crate::files::{
+        preview::{format_size, DirectorySummary, Preview},
+        FileEntry,
+    };
+
+    #[test]
+    fn should_format_sizes_with_binary_units() {
+        // when
+        let result: Vec<String> = [512, 2048, 5 * 1024 * 1024 + 512 * 1024]
+            .iter()
+            .map(|size| format_size(*size))
+            .collect();
+
+        // then
+        assert_eq!(result, vec!["512 B", "2.0 KiB", "5.5 MiB"]);
+    }
+
+    #[test]
+    fn should_summarize_tracks_below_directory() -> Result<(), Box<dyn std::error::Error>> {
+        // given
+        let tmp_dir = TempDir::new("preview_directory")?;
+        let album = tmp_dir.path().join("album");
+        fs::create_dir(&album)?;
+        fs::write(tmp_dir.path().join("single.mp3"), [0; 100])?;
+        fs::write(album.join("track_1.mp3"), [0; 200])?;
+        fs::write(album.join("cover.jpg"), [0; 300])?;
+
+        // when
+        let result = Preview::load(&FileEntry::new(tmp_dir.path()), &|| false);
+
+        // then
+        match result {
+            Some(Preview::Directory(summary)) => assert_eq!(
+                summary,
+                DirectorySummary {
+                    tracks: 2,
+                    duration: std::time::Duration::ZERO,
+                    size: 300,
+                    truncated: false,
+                }
+            ),
+            _ => panic!("Expected directory summary"),
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_limit_summary_depth_and_skip_symlinked_directories(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        // given
+        let tmp_dir = TempDir::new("preview_depth")?;
+        let deep = tmp_dir.path().join("a").join("b").join("c");
+        fs::create_dir_all(&deep)?;
+        fs::write(
+            tmp_dir.path().join("a").join("b").join("shallow.mp3"),
+            [0; 10],
+        )?;
+        fs::write(deep.join("deep.mp3"), [0; 10])?;
+        symlink(tmp_dir.path(), tmp_dir.path().join("a").join("loop"))?;
+
+        // when
+        let result = Preview::load(&FileEntry::new(tmp_dir.path()), &|| false);
+        let cancelled = Preview::load(&FileEntry::new(tmp_dir.path()), &|| true);
+
+        // then
+        match result {
+            Some(Preview::Directory(summary)) => assert_eq!(summary.tracks, 1),
+            _ => panic!("Expected directory summary"),
+        }
+        assert!(cancelled.is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_show_tags_and_file_details() -> Result<(), Box<dyn std::error::Error>> {
+        // given
+        let tmp_dir = TempDir::new("preview_file")?;
+        let path = tmp_dir.path().join("song.mp3");
+        File::create(&path)?;
+        let mut tag = Tag::new();
+        tag.set_artist("Rockers");
+        tag.set_title("My song");
+        tag.set_track(7);
+        tag.write_to_path(&path, id3::Version::Id3v24)?;
+
+        // when
+        let result = Preview::load(&FileEntry::new(&path), &|| false)
+            .unwrap()
+            .display();
+
+        // then
+        assert_eq!(
+            result[..4],
+            [
+                "Artist  : Rockers",
+                "Title   : My song",
+                "Track   : 7",
+                "Duration: 00:00"
+            ]
+        );
+        assert!(result[4].starts_with("Size    : "));
+        assert!(result[5].starts_with("Modified: "));
+
+        Ok(())
+    }
+}
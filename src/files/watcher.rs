@@ -0,0 +1,109 @@
+use log::{debug, error, warn};
+use nix::sys::inotify::{AddWatchFlags, InitFlags, Inotify, WatchDescriptor};
+use std::{
+    ffi::OsString,
+    path::Path,
+    sync::{Arc, Mutex},
+    thread,
+};
+
+/// Change reported by inotify, with name of changed entry or `None` if directory itself changed
+type Change = (WatchDescriptor, Option<OsString>);
+
+/// Watches single directory with inotify, recording which of its entries changed
+pub struct DirectoryWatcher {
+    inotify: Arc<Inotify>,
+    /// Watched directory and its watch descriptor, missing if directory cannot be watched
+    watch: Option<(String, Option<WatchDescriptor>)>,
+    /// Filled by watching thread when entry was added, removed, renamed or written
+    changes: Arc<Mutex<Vec<Change>>>,
+}
+
+impl DirectoryWatcher {
+    /// Starts thread reading inotify events, returns `None` if inotify is not available
+    pub fn new() -> Option<Self> {
+        let inotify = match Inotify::init(InitFlags::IN_CLOEXEC) {
+            Ok(inotify) => Arc::new(inotify),
+            Err(e) => {
+                warn!("Cannot watch directories for changes: {e}");
+                return None;
+            }
+        };
+        let changes = Arc::new(Mutex::new(vec![]));
+        let (events, thread_changes) = (inotify.clone(), changes.clone());
+        thread::spawn(move || loop {
+            match events.read_events() {
+                Ok(events) => {
+                    thread_changes.lock().unwrap().extend(
+                        events
+                            .into_iter()
+                            .filter(|e| !e.mask.contains(AddWatchFlags::IN_IGNORED))
+                            .map(|e| (e.wd, e.name)),
+                    );
+                }
+                Err(e) => {
+                    error!("Failed to read filesystem events: {e}");
+                    break;
+                }
+            }
+        });
+        Some(DirectoryWatcher {
+            inotify,
+            watch: None,
+            changes,
+        })
+    }
+
+    /// Watches given directory instead of previously watched one
+    pub fn watch(&mut self, directory: &str) {
+        if self.watch.as_ref().is_some_and(|(d, _)| d == directory) {
+            return;
+        }
+        if let Some((_, Some(descriptor))) = self.watch.take() {
+            let _ = self.inotify.rm_watch(descriptor);
+        }
+        let flags = AddWatchFlags::IN_CREATE
+            | AddWatchFlags::IN_DELETE
+            | AddWatchFlags::IN_MOVED_FROM
+            | AddWatchFlags::IN_MOVED_TO
+            | AddWatchFlags::IN_CLOSE_WRITE
+            | AddWatchFlags::IN_DELETE_SELF
+            | AddWatchFlags::IN_MOVE_SELF;
+        let descriptor = match self.inotify.add_watch(directory, flags) {
+            Ok(descriptor) => {
+                debug!("Watching directory {directory}");
+                Some(descriptor)
+            }
+            Err(e) => {
+                warn!("Cannot watch directory {directory}: {e}");
+                None
+            }
+        };
+        self.watch = Some((String::from(directory), descriptor));
+        // Changes of previous directory are no longer relevant
+        self.changes.lock().unwrap().clear();
+    }
+
+    /// Returns paths of entries of watched directory changed since last call,
+    /// path of directory itself if it was removed or moved
+    pub fn take_changes(&self) -> Vec<String> {
+        let changes: Vec<Change> = self.changes.lock().unwrap().drain(..).collect();
+        let Some((directory, Some(descriptor))) = &self.watch else {
+            return vec![];
+        };
+        let mut paths: Vec<String> = changes
+            .into_iter()
+            .filter(|(wd, _)| wd == descriptor)
+            .map(|(_, name)| match name {
+                Some(name) => Path::new(directory)
+                    .join(name)
+                    .to_string_lossy()
+                    .to_string(),
+                None => directory.clone(),
+            })
+            .collect();
+        paths.sort();
+        paths.dedup();
+        paths
+    }
+}
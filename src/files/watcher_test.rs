@@ -0,0 +1,48 @@
+#[cfg(test)]
+mod tests {
+    use std::{fs, io, thread, time::Duration};
+
+    use tempdir::TempDir;
+
+    use crate::files::watcher::DirectoryWatcher;
+
+    fn wait_for_changes(watcher: &DirectoryWatcher) -> Vec<String> {
+        for _ in 0..100 {
+            thread::sleep(Duration::from_millis(20));
+            let changes = watcher.take_changes();
+            if !changes.is_empty() {
+                return changes;
+            }
+        }
+        vec![]
+    }
+
+    #[test]
+    fn should_report_changes_of_watched_directory_only() -> Result<(), io::Error> {
+        // given
+        let directory = TempDir::new("watched")?;
+        let other = TempDir::new("not_watched")?;
+        let mut watcher = DirectoryWatcher::new().unwrap();
+        watcher.watch(directory.path().to_str().unwrap());
+
+        // when
+        fs::write(other.path().join("ignored.mp3"), [])?;
+        thread::sleep(Duration::from_millis(100));
+        let other_changed = watcher.take_changes();
+        fs::write(directory.path().join("download.mp3"), [])?;
+        let created = wait_for_changes(&watcher);
+        fs::rename(
+            directory.path().join("download.mp3"),
+            directory.path().join("renamed.mp3"),
+        )?;
+        let renamed = wait_for_changes(&watcher);
+
+        // then
+        let path = |name: &str| directory.path().join(name).to_string_lossy().to_string();
+        assert!(other_changed.is_empty());
+        assert!(created.contains(&path("download.mp3")));
+        assert!(renamed.contains(&path("renamed.mp3")));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,141 @@
+use log::warn;
+use std::{
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use crate::queue::SongFile;
+
+/// Single playback of a track
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlayRecord {
+    pub path: String,
+    pub artist: Option<String>,
+    pub title: Option<String>,
+    pub album: Option<String>,
+    /// Unix timestamp of playback start
+    pub timestamp: u64,
+    pub listened: Duration,
+    /// True if track was played until its end, false if it was skipped or stopped
+    pub completed: bool,
+}
+
+impl PlayRecord {
+    pub fn new(song: &SongFile, timestamp: u64, listened: Duration, completed: bool) -> Self {
+        PlayRecord {
+            path: song.file_entry.path.clone(),
+            artist: song.metadata.artist.clone(),
+            title: song.metadata.title.clone(),
+            album: song.metadata.album.clone(),
+            timestamp,
+            listened,
+            completed,
+        }
+    }
+
+    /// Returns "Artist - Title", falling back to file path if tags are missing
+    pub fn display_short(&self) -> String {
+        let title = self.title.as_ref().unwrap_or(&self.path);
+        match &self.artist {
+            Some(artist) => format!("{artist} - {title}"),
+            None => title.clone(),
+        }
+    }
+
+    fn to_line(&self) -> String {
+        [
+            self.timestamp.to_string(),
+            self.listened.as_secs().to_string(),
+            String::from(if self.completed {
+                "completed"
+            } else {
+                "skipped"
+            }),
+            sanitize_column(&self.path),
+            sanitize_column(self.artist.as_deref().unwrap_or_default()),
+            sanitize_column(self.title.as_deref().unwrap_or_default()),
+            sanitize_column(self.album.as_deref().unwrap_or_default()),
+        ]
+        .join("\t")
+    }
+
+    fn from_line(line: &str) -> Option<Self> {
+        let columns: Vec<&str> = line.split('\t').collect();
+        if columns.len() != 7 {
+            return None;
+        }
+        let optional = |value: &str| Some(String::from(value)).filter(|v| !v.is_empty());
+        Some(PlayRecord {
+            timestamp: columns[0].parse().ok()?,
+            listened: Duration::from_secs(columns[1].parse().ok()?),
+            completed: match columns[2] {
+                "completed" => true,
+                "skipped" => false,
+                _ => return None,
+            },
+            path: String::from(columns[3]),
+            artist: optional(columns[4]),
+            title: optional(columns[5]),
+            album: optional(columns[6]),
+        })
+    }
+}
+
+/// Store of all playbacks, kept in file with one tab separated record per line
+pub struct ListeningHistory {
+    file: PathBuf,
+    records: Vec<PlayRecord>,
+}
+
+impl ListeningHistory {
+    /// Loads history from file, starting with empty history if file does not exist
+    pub fn load(file: &Path) -> Self {
+        let records = fs::read_to_string(file)
+            .map(|content| {
+                content
+                    .lines()
+                    .filter_map(|line| {
+                        let record = PlayRecord::from_line(line);
+                        if record.is_none() {
+                            warn!("Skipping invalid history entry: {line}");
+                        }
+                        record
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        ListeningHistory {
+            file: file.to_path_buf(),
+            records,
+        }
+    }
+
+    /// Adds record to history and appends it to history file.
+    /// Playback which produced no audio, e.g. because file failed to open, is not a play and is skipped.
+    pub fn record(&mut self, record: PlayRecord) -> io::Result<()> {
+        if record.listened.is_zero() {
+            return Ok(());
+        }
+        if let Some(parent) = self.file.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.file)?;
+        writeln!(file, "{}", record.to_line())?;
+        self.records.push(record);
+        Ok(())
+    }
+
+    /// Returns all records, oldest first
+    pub fn records(&self) -> &[PlayRecord] {
+        &self.records
+    }
+}
+
+fn sanitize_column(value: &str) -> String {
+    value.replace(['\t', '\n'], " ")
+}
@@ -0,0 +1,75 @@
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use tempdir::TempDir;
+
+    use crate::history::{statistics::top_tracks, ListeningHistory, PlayRecord};
+
+    #[test]
+    fn should_persist_play_records() -> Result<(), std::io::Error> {
+        // given
+        let tmp_dir = TempDir::new("history")?;
+        let history_file = tmp_dir.path().join("penny").join("history");
+        let record = PlayRecord {
+            path: String::from("/music/song.mp3"),
+            artist: Some(String::from("Rockers")),
+            title: Some(String::from("Awesome\tmedley")),
+            album: None,
+            timestamp: 1700000000,
+            listened: Duration::from_secs(95),
+            completed: false,
+        };
+        let mut history = ListeningHistory::load(&history_file);
+        history.record(record.clone())?;
+
+        // when
+        let result = ListeningHistory::load(&history_file);
+
+        // then
+        assert_eq!(
+            result.records(),
+            &[PlayRecord {
+                title: Some(String::from("Awesome medley")),
+                ..record
+            }]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_not_count_playback_which_failed_to_open() -> Result<(), std::io::Error> {
+        // given
+        let tmp_dir = TempDir::new("history")?;
+        let history_file = tmp_dir.path().join("history");
+        let played = PlayRecord {
+            path: String::from("/music/song.mp3"),
+            artist: Some(String::from("Rockers")),
+            title: Some(String::from("Medley")),
+            album: None,
+            timestamp: 1700000000,
+            listened: Duration::from_secs(180),
+            completed: true,
+        };
+        let failed = PlayRecord {
+            timestamp: 1700000200,
+            listened: Duration::ZERO,
+            completed: false,
+            ..played.clone()
+        };
+        let mut history = ListeningHistory::load(&history_file);
+        history.record(played)?;
+
+        // when
+        history.record(failed)?;
+
+        // then
+        let tracks = top_tracks(history.records(), 10);
+        assert_eq!(tracks.len(), 1);
+        assert!(tracks[0].label.starts_with("   1\u{00D7}"));
+        assert_eq!(ListeningHistory::load(&history_file).records().len(), 1);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,12 @@
+//! Module recording played tracks and computing listening statistics
+pub mod listening_history;
+pub mod statistics;
+pub mod statistics_view;
+
+pub use listening_history::{ListeningHistory, PlayRecord};
+pub use statistics_view::StatisticsView;
+
+#[cfg(test)]
+mod listening_history_test;
+#[cfg(test)]
+mod statistics_test;
@@ -0,0 +1,160 @@
+use chrono::{DateTime, Datelike, Local, NaiveDate};
+use std::{collections::HashMap, time::Duration};
+
+use super::listening_history::PlayRecord;
+
+/// Row of statistics, with paths of tracks it represents
+#[derive(Debug, PartialEq)]
+pub struct StatisticsRow {
+    pub label: String,
+    pub paths: Vec<String>,
+}
+
+/// Returns most played tracks, with play count
+pub fn top_tracks(records: &[PlayRecord], limit: usize) -> Vec<StatisticsRow> {
+    top_by(
+        records,
+        limit,
+        |r| Some(r.path.clone()),
+        |r| r.display_short(),
+    )
+}
+
+/// Returns artists with most plays
+pub fn top_artists(records: &[PlayRecord], limit: usize) -> Vec<StatisticsRow> {
+    top_by(
+        records,
+        limit,
+        |r| r.artist.clone(),
+        |r| r.artist.clone().unwrap_or_default(),
+    )
+}
+
+/// Returns albums with most plays
+pub fn top_albums(records: &[PlayRecord], limit: usize) -> Vec<StatisticsRow> {
+    top_by(
+        records,
+        limit,
+        |r| {
+            r.album
+                .as_ref()
+                .map(|album| format!("{}\t{album}", r.artist.as_deref().unwrap_or_default()))
+        },
+        |r| match &r.artist {
+            Some(artist) => format!("{artist} - {}", r.album.as_deref().unwrap_or_default()),
+            None => r.album.clone().unwrap_or_default(),
+        },
+    )
+}
+
+/// Returns most recent plays, newest first
+pub fn recently_played(records: &[PlayRecord], limit: usize) -> Vec<StatisticsRow> {
+    records
+        .iter()
+        .rev()
+        .take(limit)
+        .map(|r| StatisticsRow {
+            label: format!(
+                "{} {}{}",
+                local_time(r.timestamp)
+                    .map(|t| t.format("%Y-%m-%d %H:%M").to_string())
+                    .unwrap_or_default(),
+                r.display_short(),
+                if r.completed { "" } else { " (skipped)" }
+            ),
+            paths: vec![r.path.clone()],
+        })
+        .collect()
+}
+
+/// Returns total listening time for each of recent days, newest first
+pub fn listening_time_per_day(
+    records: &[PlayRecord],
+    today: NaiveDate,
+    days: u64,
+) -> Vec<StatisticsRow> {
+    (0..days)
+        .filter_map(|offset| today.checked_sub_days(chrono::Days::new(offset)))
+        .map(|day| {
+            let total = total_listening(records, |date| date == day);
+            StatisticsRow {
+                label: format!("{}  {}", day.format("%Y-%m-%d"), format_total(total)),
+                paths: vec![],
+            }
+        })
+        .collect()
+}
+
+/// Returns total listening time for each of recent ISO weeks, newest first
+pub fn listening_time_per_week(
+    records: &[PlayRecord],
+    today: NaiveDate,
+    weeks: u64,
+) -> Vec<StatisticsRow> {
+    (0..weeks)
+        .filter_map(|offset| today.checked_sub_days(chrono::Days::new(offset * 7)))
+        .map(|day| {
+            let week = day.iso_week();
+            let total = total_listening(records, |date| date.iso_week() == week);
+            StatisticsRow {
+                label: format!(
+                    "{}-W{:0>2}  {}",
+                    week.year(),
+                    week.week(),
+                    format_total(total)
+                ),
+                paths: vec![],
+            }
+        })
+        .collect()
+}
+
+/// Formats duration as hours and minutes, e.g. "2h 05m"
+pub fn format_total(duration: Duration) -> String {
+    let minutes = duration.as_secs() / 60;
+    format!("{}h {:0>2}m", minutes / 60, minutes % 60)
+}
+
+fn total_listening(records: &[PlayRecord], matches: impl Fn(NaiveDate) -> bool) -> Duration {
+    records
+        .iter()
+        .filter(|r| local_time(r.timestamp).is_some_and(|t| matches(t.date_naive())))
+        .map(|r| r.listened)
+        .sum()
+}
+
+fn local_time(timestamp: u64) -> Option<DateTime<Local>> {
+    DateTime::from_timestamp(timestamp as i64, 0).map(|t| t.with_timezone(&Local))
+}
+
+/// Groups records by key, returning groups with most records first.
+/// Each group contains distinct paths of its tracks.
+fn top_by(
+    records: &[PlayRecord],
+    limit: usize,
+    key: impl Fn(&PlayRecord) -> Option<String>,
+    label: impl Fn(&PlayRecord) -> String,
+) -> Vec<StatisticsRow> {
+    let mut groups: HashMap<String, (usize, String, Vec<String>)> = HashMap::new();
+    for record in records {
+        if let Some(key) = key(record) {
+            let group = groups
+                .entry(key)
+                .or_insert_with(|| (0, label(record), vec![]));
+            group.0 += 1;
+            if !group.2.contains(&record.path) {
+                group.2.push(record.path.clone());
+            }
+        }
+    }
+    let mut groups: Vec<(usize, String, Vec<String>)> = groups.into_values().collect();
+    groups.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.cmp(&b.1)));
+    groups
+        .into_iter()
+        .take(limit)
+        .map(|(count, label, paths)| StatisticsRow {
+            label: format!("{count:>4}\u{00D7} {label}"),
+            paths,
+        })
+        .collect()
+}
@@ -0,0 +1,116 @@
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use chrono::{Local, NaiveDate, TimeZone};
+
+    use crate::history::{
+        statistics::{
+            format_total, listening_time_per_day, recently_played, top_albums, top_artists,
+            top_tracks, StatisticsRow,
+        },
+        PlayRecord,
+    };
+
+    #[test]
+    fn should_rank_most_played_tracks() {
+        // given
+        let records = vec![
+            record("/a.mp3", "Rockers", "Song A", "First", 1),
+            record("/b.mp3", "Rockers", "Song B", "First", 2),
+            record("/b.mp3", "Rockers", "Song B", "First", 3),
+        ];
+
+        // when
+        let result = top_tracks(&records, 10);
+
+        // then
+        assert_eq!(
+            result,
+            vec![
+                StatisticsRow {
+                    label: String::from("   2\u{00D7} Rockers - Song B"),
+                    paths: vec![String::from("/b.mp3")],
+                },
+                StatisticsRow {
+                    label: String::from("   1\u{00D7} Rockers - Song A"),
+                    paths: vec![String::from("/a.mp3")],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn should_group_plays_by_artist_and_album() {
+        // given
+        let records = vec![
+            record("/a.mp3", "Rockers", "Song A", "First", 1),
+            record("/b.mp3", "Rockers", "Song B", "Second", 2),
+            record("/c.mp3", "Jazzmen", "Song C", "First", 3),
+        ];
+
+        // when
+        let artists = top_artists(&records, 1);
+        let albums = top_albums(&records, 10);
+
+        // then
+        assert_eq!(artists[0].label, "   2\u{00D7} Rockers");
+        assert_eq!(
+            artists[0].paths,
+            vec![String::from("/a.mp3"), String::from("/b.mp3")]
+        );
+        assert_eq!(albums.len(), 3);
+    }
+
+    #[test]
+    fn should_list_recent_plays_first() {
+        // given
+        let records = vec![
+            record("/a.mp3", "Rockers", "Song A", "First", 1),
+            record("/b.mp3", "Rockers", "Song B", "First", 2),
+        ];
+
+        // when
+        let result = recently_played(&records, 1);
+
+        // then
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].paths, vec![String::from("/b.mp3")]);
+    }
+
+    #[test]
+    fn should_sum_listening_time_per_day() {
+        // given
+        let day = NaiveDate::from_ymd_opt(2024, 3, 10).unwrap();
+        let timestamp_at = |hour| {
+            Local
+                .from_local_datetime(&day.and_hms_opt(hour, 0, 0).unwrap())
+                .unwrap()
+                .timestamp() as u64
+        };
+        let mut first = record("/a.mp3", "Rockers", "Song A", "First", timestamp_at(10));
+        first.listened = Duration::from_secs(3600);
+        let mut second = record("/b.mp3", "Rockers", "Song B", "First", timestamp_at(12));
+        second.listened = Duration::from_secs(300);
+
+        // when
+        let result = listening_time_per_day(&[first, second], day, 2);
+
+        // then
+        assert_eq!(result[0].label, "2024-03-10  1h 05m");
+        assert_eq!(result[1].label, "2024-03-09  0h 00m");
+        assert_eq!(format_total(Duration::from_secs(7500)), "2h 05m");
+    }
+
+    fn record(path: &str, artist: &str, title: &str, album: &str, timestamp: u64) -> PlayRecord {
+        PlayRecord {
+            path: String::from(path),
+            artist: Some(String::from(artist)),
+            title: Some(String::from(title)),
+            album: Some(String::from(album)),
+            timestamp,
+            listened: Duration::from_secs(180),
+            completed: true,
+        }
+    }
+}
@@ -0,0 +1,158 @@
+use chrono::Local;
+use log::error;
+use ratatui::widgets::ListState;
+
+use crate::application::actions::Action;
+
+use super::{
+    listening_history::ListeningHistory,
+    statistics::{
+        listening_time_per_day, listening_time_per_week, recently_played, top_albums, top_artists,
+        top_tracks, StatisticsRow,
+    },
+};
+
+const ROW_LIMIT: usize = 50;
+
+/// Sections of statistics view
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StatisticsSection {
+    TopTracks,
+    TopArtists,
+    TopAlbums,
+    ListeningTime,
+    RecentlyPlayed,
+}
+
+impl StatisticsSection {
+    pub fn title(&self) -> &'static str {
+        match self {
+            StatisticsSection::TopTracks => "Most played tracks",
+            StatisticsSection::TopArtists => "Most played artists",
+            StatisticsSection::TopAlbums => "Most played albums",
+            StatisticsSection::ListeningTime => "Listening time",
+            StatisticsSection::RecentlyPlayed => "Recently played",
+        }
+    }
+
+    fn next(&self) -> Self {
+        match self {
+            StatisticsSection::TopTracks => StatisticsSection::TopArtists,
+            StatisticsSection::TopArtists => StatisticsSection::TopAlbums,
+            StatisticsSection::TopAlbums => StatisticsSection::ListeningTime,
+            StatisticsSection::ListeningTime => StatisticsSection::RecentlyPlayed,
+            StatisticsSection::RecentlyPlayed => StatisticsSection::TopTracks,
+        }
+    }
+
+    fn previous(&self) -> Self {
+        match self {
+            StatisticsSection::TopTracks => StatisticsSection::RecentlyPlayed,
+            StatisticsSection::TopArtists => StatisticsSection::TopTracks,
+            StatisticsSection::TopAlbums => StatisticsSection::TopArtists,
+            StatisticsSection::ListeningTime => StatisticsSection::TopAlbums,
+            StatisticsSection::RecentlyPlayed => StatisticsSection::ListeningTime,
+        }
+    }
+}
+
+/// View presenting statistics computed from listening history
+pub struct StatisticsView {
+    pub state: ListState,
+    pub section: StatisticsSection,
+    pub rows: Vec<StatisticsRow>,
+    /// Number of history records rows were computed from
+    computed_from: Option<usize>,
+}
+
+impl StatisticsView {
+    pub fn new() -> Self {
+        StatisticsView {
+            state: ListState::default(),
+            section: StatisticsSection::TopTracks,
+            rows: vec![],
+            computed_from: None,
+        }
+    }
+
+    pub fn do_action(&mut self, action: Action) {
+        match action {
+            Action::ViewerUp => self.previous(),
+            Action::ViewerDown => self.next(),
+            Action::FileViewerDirUp => self.change_section(self.section.previous()),
+            Action::FileViewerEnterDir => self.change_section(self.section.next()),
+            _ => error!("Unsupported statistics view action: {action:?}"),
+        }
+    }
+
+    /// Recomputes statistics if history changed since last computation.
+    /// Returns true if statistics were recomputed.
+    pub fn update(&mut self, history: &ListeningHistory) -> bool {
+        let records = history.records();
+        if self.computed_from == Some(records.len()) {
+            return false;
+        }
+        self.computed_from = Some(records.len());
+        self.rows = match self.section {
+            StatisticsSection::TopTracks => top_tracks(records, ROW_LIMIT),
+            StatisticsSection::TopArtists => top_artists(records, ROW_LIMIT),
+            StatisticsSection::TopAlbums => top_albums(records, ROW_LIMIT),
+            StatisticsSection::ListeningTime => {
+                let today = Local::now().date_naive();
+                let mut rows = vec![StatisticsRow {
+                    label: String::from("Per day"),
+                    paths: vec![],
+                }];
+                rows.extend(listening_time_per_day(records, today, 7));
+                rows.push(StatisticsRow {
+                    label: String::from("Per week"),
+                    paths: vec![],
+                });
+                rows.extend(listening_time_per_week(records, today, 4));
+                rows
+            }
+            StatisticsSection::RecentlyPlayed => recently_played(records, ROW_LIMIT),
+        };
+        match self.state.selected() {
+            Some(i) if i < self.rows.len() => {}
+            _ if self.rows.is_empty() => self.state = ListState::default(),
+            _ => self.state.select(Some(0)),
+        }
+        true
+    }
+
+    /// Returns paths of tracks represented by selected row
+    pub fn get_selected_paths(&self) -> Vec<String> {
+        self.state
+            .selected()
+            .and_then(|i| self.rows.get(i))
+            .map(|row| row.paths.clone())
+            .unwrap_or_default()
+    }
+
+    fn change_section(&mut self, section: StatisticsSection) {
+        self.section = section;
+        self.computed_from = None;
+        self.state = ListState::default();
+    }
+
+    fn next(&mut self) {
+        let i = match self.state.selected() {
+            Some(i) if i + 1 < self.rows.len() => i + 1,
+            _ => 0,
+        };
+        if !self.rows.is_empty() {
+            self.state.select(Some(i));
+        }
+    }
+
+    fn previous(&mut self) {
+        let i = match self.state.selected() {
+            Some(0) | None => self.rows.len().saturating_sub(1),
+            Some(i) => i - 1,
+        };
+        if !self.rows.is_empty() {
+            self.state.select(Some(i));
+        }
+    }
+}
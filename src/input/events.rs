@@ -1,9 +1,13 @@
-use crossterm::event::{self, KeyCode, KeyEvent};
+use crate::{
+    daemon::RemoteCommand, error::AppError, player::mp3_player::PlaybackReport, queue::SongFile,
+};
+use crossterm::event::{self, KeyCode, KeyEvent, MouseEvent};
+use log::debug;
 use std::{
     sync::{
         atomic::{AtomicBool, Ordering},
         mpsc::{self, Receiver, Sender},
-        Arc,
+        Arc, Condvar, Mutex,
     },
     thread,
     time::Duration,
@@ -26,70 +30,209 @@ impl KeyPress {
 pub enum AppEvent {
     /// Key pressed by user
     Input(KeyPress),
+    /// Mouse clicked, scrolled or moved by user
+    Mouse(MouseEvent),
+    /// Terminal resized to given number of columns and rows
+    Resize(u16, u16),
+    /// Terminal window gained (`true`) or lost (`false`) focus
+    FocusChanged(bool),
+    /// Text pasted into terminal
+    Paste(String),
     /// Defined span of time elapsed in app
     Tick,
+    /// Next frame of interface should be drawn, sent only while frames are enabled
+    Frame,
     /// Event occurred during playback
     Playback(PlaybackEvent),
+    /// Command received through control socket, with channel for the response
+    Remote(RemoteCommand, Sender<String>),
 }
 
+/// Event published by player, allowing other components to react to playback
 pub enum PlaybackEvent {
+    /// Playback of song started
+    Started(Box<SongFile>),
+    Paused,
+    Resumed,
+    /// Playback of song ended, because it finished, was stopped or failed
+    Stopped(Box<PlaybackReport>),
+    /// Song played until its end, next song from queue should be played
     SongFinished,
+    /// Elapsed time of played song reached next whole second
+    PositionChanged(Duration),
+    /// Song could not be played
+    Error(AppError),
+    /// Network stream ran out of data, playback waits until more is downloaded
+    BufferUnderrun,
+}
+
+/// Publishes playback event in application
+pub fn publish(events: &Sender<AppEvent>, event: PlaybackEvent) {
+    if events.send(AppEvent::Playback(event)).is_err() {
+        debug!("Playback event not delivered, application is closing");
+    }
+}
+
+/// Switch of [Frame](AppEvent::Frame) events, waking frame thread when frames are enabled
+#[derive(Default)]
+struct FrameSwitch {
+    enabled: Mutex<bool>,
+    changed: Condvar,
+    /// Frame was sent, but not yet received, so next one is skipped instead of queued
+    pending: AtomicBool,
 }
 
 /// Event handling in application
-/// Captures key presses and allows to poll for them
-/// In case of no key press, sends [Tick](InputEvent::Tick) event
+/// Captures key presses and allows to poll for them.
+/// Sends [Tick](AppEvent::Tick) events at tick rate, and [Frame](AppEvent::Frame) events
+/// at frame rate while they are enabled.
 pub struct EventBus {
     tx: Sender<AppEvent>,
     rx: Receiver<AppEvent>,
     stop_capture: Arc<AtomicBool>,
+    /// Missing if frames are never sent
+    frames: Option<Arc<FrameSwitch>>,
 }
 
 impl EventBus {
-    /// Creates event instance starting key press capture loop in separate thread
-    pub fn new(tick_rate: Duration) -> EventBus {
+    /// Creates event instance starting key press capture, tick and frame loops in separate threads
+    pub fn new(tick_rate: Duration, frame_interval: Duration) -> EventBus {
         let (tx, rx) = mpsc::channel();
         let stop_capture = Arc::new(AtomicBool::new(false));
+        let frames = Arc::new(FrameSwitch::default());
 
-        let event_stop_capture = stop_capture.clone();
-        let loop_tx = tx.clone();
+        let (key_tx, key_stop_capture) = (tx.clone(), stop_capture.clone());
+        thread::spawn(move || Self::poll_key_events(tick_rate, key_tx, key_stop_capture));
+        let (tick_tx, tick_stop_capture) = (tx.clone(), stop_capture.clone());
+        thread::spawn(move || Self::send_ticks(tick_rate, tick_tx, tick_stop_capture));
+        let (frame_tx, frame_stop_capture, frame_switch) =
+            (tx.clone(), stop_capture.clone(), frames.clone());
+        thread::spawn(move || {
+            Self::send_frames(frame_interval, frame_tx, frame_stop_capture, frame_switch)
+        });
 
-        thread::spawn(move || Self::poll_key_events(tick_rate, loop_tx, event_stop_capture));
+        EventBus {
+            tx,
+            rx,
+            stop_capture,
+            frames: Some(frames),
+        }
+    }
+
+    /// Creates event instance without key press capture and frames, for running without terminal.
+    /// Ticks are still sent, so background work of app is carried out.
+    pub fn headless(tick_rate: Duration) -> EventBus {
+        let (tx, rx) = mpsc::channel();
+        let stop_capture = Arc::new(AtomicBool::new(false));
+
+        let (tick_tx, tick_stop_capture) = (tx.clone(), stop_capture.clone());
+        thread::spawn(move || Self::send_ticks(tick_rate, tick_tx, tick_stop_capture));
 
         EventBus {
             tx,
             rx,
             stop_capture,
+            frames: None,
         }
     }
 
-    /// Alows to send event in application
-    pub fn send(&mut self, event: PlaybackEvent) {
-        self.tx.send(AppEvent::Playback(event)).unwrap();
+    /// Returns sender allowing other threads to publish events in application
+    pub fn sender(&self) -> Sender<AppEvent> {
+        self.tx.clone()
+    }
+
+    /// Starts or stops sending [Frame](AppEvent::Frame) events
+    pub fn set_frames_enabled(&self, enabled: bool) {
+        if let Some(frames) = &self.frames {
+            let mut frames_enabled = frames.enabled.lock().unwrap();
+            if *frames_enabled != enabled {
+                *frames_enabled = enabled;
+                frames.changed.notify_all();
+            }
+        }
     }
 
     /// Fetches next key press event or returns [Tick](InputEvent::Tick)
     pub fn next(&mut self) -> AppEvent {
-        self.rx.recv().unwrap_or(AppEvent::Tick)
+        let event = self.rx.recv().unwrap_or(AppEvent::Tick);
+        if let (AppEvent::Frame, Some(frames)) = (&event, &self.frames) {
+            frames.pending.store(false, Ordering::Relaxed);
+        }
+        event
     }
 
-    /// Stops keypress capture thread
+    /// Stops keypress capture, tick and frame threads
     pub fn close(&mut self) {
-        self.stop_capture.store(true, Ordering::Relaxed)
+        self.stop_capture.store(true, Ordering::Relaxed);
+        if let Some(frames) = &self.frames {
+            // Lock is held, so frame thread cannot miss notification between check and wait
+            let _enabled = frames.enabled.lock().unwrap();
+            frames.changed.notify_all();
+        }
     }
 
     fn poll_key_events(tick_rate: Duration, tx: Sender<AppEvent>, stop_capture: Arc<AtomicBool>) {
         loop {
-            // poll for tick rate duration, if no event, sent tick event.
+            // poll for tick rate duration, so stop request is noticed without key press
             if crossterm::event::poll(tick_rate).unwrap() {
-                if let event::Event::Key(key_event) = event::read().unwrap() {
-                    tx.send(AppEvent::Input(KeyPress::new(key_event))).unwrap();
+                match event::read().unwrap() {
+                    event::Event::Key(key_event) => {
+                        tx.send(AppEvent::Input(KeyPress::new(key_event))).unwrap()
+                    }
+                    event::Event::Mouse(mouse_event) => {
+                        tx.send(AppEvent::Mouse(mouse_event)).unwrap()
+                    }
+                    event::Event::Resize(columns, rows) => {
+                        tx.send(AppEvent::Resize(columns, rows)).unwrap()
+                    }
+                    event::Event::FocusGained => tx.send(AppEvent::FocusChanged(true)).unwrap(),
+                    event::Event::FocusLost => tx.send(AppEvent::FocusChanged(false)).unwrap(),
+                    event::Event::Paste(text) => tx.send(AppEvent::Paste(text)).unwrap(),
                 }
             }
-            tx.send(AppEvent::Tick).unwrap();
             if stop_capture.load(Ordering::Relaxed) {
                 break;
             }
         }
     }
+
+    fn send_ticks(tick_rate: Duration, tx: Sender<AppEvent>, stop_capture: Arc<AtomicBool>) {
+        while !stop_capture.load(Ordering::Relaxed) {
+            thread::sleep(tick_rate);
+            if tx.send(AppEvent::Tick).is_err() {
+                break;
+            }
+        }
+    }
+
+    fn send_frames(
+        frame_interval: Duration,
+        tx: Sender<AppEvent>,
+        stop_capture: Arc<AtomicBool>,
+        frames: Arc<FrameSwitch>,
+    ) {
+        loop {
+            // Sleeps without waking up until frames are enabled
+            drop(
+                frames
+                    .changed
+                    .wait_while(frames.enabled.lock().unwrap(), |enabled| {
+                        !*enabled && !stop_capture.load(Ordering::Relaxed)
+                    })
+                    .unwrap(),
+            );
+            if stop_capture.load(Ordering::Relaxed) {
+                break;
+            }
+            thread::sleep(frame_interval);
+            // Frames are coalesced when drawing is slower than frame rate,
+            // so they do not pile up ahead of key presses
+            if !*frames.enabled.lock().unwrap() || frames.pending.swap(true, Ordering::Relaxed) {
+                continue;
+            }
+            if tx.send(AppEvent::Frame).is_err() {
+                break;
+            }
+        }
+    }
 }
@@ -0,0 +1,12 @@
+//! Module keeping information about music library, like track ratings and smart playlists
+pub mod ratings;
+pub mod smart_playlist;
+pub mod smart_playlist_view;
+
+pub use ratings::Ratings;
+pub use smart_playlist_view::SmartPlaylistView;
+
+#[cfg(test)]
+mod ratings_test;
+#[cfg(test)]
+mod smart_playlist_test;
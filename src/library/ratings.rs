@@ -0,0 +1,377 @@
+use id3::{
+    frame::{Content, ExtendedText, Frame, Popularimeter},
+    Tag, TagLike, Version,
+};
+use log::{debug, warn};
+use std::{
+    collections::{HashMap, HashSet},
+    fs, io,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc, Condvar, Mutex,
+    },
+    thread,
+};
+
+/// Description of ID3 user text frame marking favourite tracks
+const FAVOURITE_FRAME_DESCRIPTION: &str = "FAVOURITE";
+/// User stored in POPM frames written by penny
+const POPULARIMETER_USER: &str = "penny";
+/// Column text shown until rating of track is read from its tags
+pub const PENDING_RATING: &str = "\u{00B7}\u{00B7}\u{00B7}\u{00B7}\u{00B7}  ";
+
+/// Rating and favourite mark of single track
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct TrackRating {
+    /// Number of stars in range 1-5, `None` if track is not rated
+    pub stars: Option<u8>,
+    pub favourite: bool,
+}
+
+impl TrackRating {
+    /// Returns rating as column text, e.g. "★★★☆☆ ♥"
+    pub fn display(&self) -> String {
+        let stars = match self.stars {
+            Some(stars) => format!(
+                "{}{}",
+                "\u{2605}".repeat(stars as usize),
+                "\u{2606}".repeat(5 - stars as usize)
+            ),
+            None => String::from("     "),
+        };
+        match self.favourite {
+            true => format!("{stars} \u{2665}"),
+            false => format!("{stars}  "),
+        }
+    }
+}
+
+/// Minimal rating of tracks added to queue in bulk
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RatingFilter {
+    Any,
+    AtLeast(u8),
+    Favourites,
+}
+
+impl RatingFilter {
+    pub fn matches(&self, rating: &TrackRating) -> bool {
+        match self {
+            RatingFilter::Any => true,
+            RatingFilter::AtLeast(minimum) => rating.stars.is_some_and(|s| s >= *minimum),
+            RatingFilter::Favourites => rating.favourite,
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            RatingFilter::Any => RatingFilter::AtLeast(1),
+            RatingFilter::AtLeast(minimum) if *minimum < 5 => RatingFilter::AtLeast(minimum + 1),
+            RatingFilter::AtLeast(_) => RatingFilter::Favourites,
+            RatingFilter::Favourites => RatingFilter::Any,
+        }
+    }
+
+    pub fn display(&self) -> String {
+        match self {
+            RatingFilter::Any => String::from("all"),
+            RatingFilter::AtLeast(minimum) => format!("\u{2265}{minimum}\u{2605}"),
+            RatingFilter::Favourites => String::from("\u{2665}"),
+        }
+    }
+}
+
+/// Converts stars to POPM rating, following convention used by common players
+pub fn stars_to_popularimeter(stars: u8) -> u8 {
+    match stars {
+        0 => 0,
+        1 => 1,
+        2 => 64,
+        3 => 128,
+        4 => 196,
+        _ => 255,
+    }
+}
+
+/// Converts POPM rating to stars, `None` for unknown rating
+pub fn popularimeter_to_stars(rating: u8) -> Option<u8> {
+    match rating {
+        0 => None,
+        1..=31 => Some(1),
+        32..=95 => Some(2),
+        96..=159 => Some(3),
+        160..=223 => Some(4),
+        224..=255 => Some(5),
+    }
+}
+
+/// Paths which tags should be read by background worker
+#[derive(Default)]
+struct TagRequests {
+    requested: Mutex<Vec<String>>,
+    changed: Condvar,
+    /// Number of requested paths not read yet
+    pending: AtomicUsize,
+    closed: AtomicBool,
+}
+
+/// Ratings of tracks, stored in ID3 tags of files.
+/// Library index file keeps ratings of tracks which tags could not be written,
+/// in format `<path>\t<stars>\t<favourite>` per line.
+pub struct Ratings {
+    index_file: PathBuf,
+    /// Ratings stored in library index
+    index: HashMap<String, TrackRating>,
+    /// Ratings read from tags
+    cache: HashMap<String, TrackRating>,
+    /// Paths requested from background worker
+    requested: HashSet<String>,
+    /// Ratings read by background worker, not moved to cache yet
+    loaded: Arc<Mutex<HashMap<String, TrackRating>>>,
+    requests: Arc<TagRequests>,
+}
+
+impl Ratings {
+    pub fn load(index_file: &Path) -> Self {
+        let index = fs::read_to_string(index_file)
+            .map(|content| content.lines().filter_map(parse_index_line).collect())
+            .unwrap_or_default();
+        let ratings = Ratings {
+            index_file: index_file.to_path_buf(),
+            index,
+            cache: HashMap::new(),
+            requested: HashSet::new(),
+            loaded: Arc::new(Mutex::new(HashMap::new())),
+            requests: Arc::new(TagRequests::default()),
+        };
+        let (loaded, requests) = (ratings.loaded.clone(), ratings.requests.clone());
+        thread::spawn(move || Self::read_tags(loaded, requests));
+        ratings
+    }
+
+    /// Returns rating of track, reading it from file tags on first access
+    pub fn get(&mut self, path: &str) -> TrackRating {
+        if let Some(rating) = self.lookup(path) {
+            return rating;
+        }
+        *self
+            .cache
+            .entry(String::from(path))
+            .or_insert_with(|| read_tag_rating(path))
+    }
+
+    /// Returns rating of track if already known, otherwise requests reading it in background,
+    /// so listing many tracks does not block on reading their tags
+    pub fn get_loaded(&mut self, path: &str) -> Option<TrackRating> {
+        let rating = self.lookup(path);
+        if rating.is_none() && self.requested.insert(String::from(path)) {
+            self.requests.pending.fetch_add(1, Ordering::Relaxed);
+            self.requests
+                .requested
+                .lock()
+                .unwrap()
+                .push(String::from(path));
+            self.requests.changed.notify_all();
+        }
+        rating
+    }
+
+    /// Returns true while ratings requested by `get_loaded` are being read
+    pub fn is_loading(&self) -> bool {
+        self.requests.pending.load(Ordering::Relaxed) > 0
+    }
+
+    /// Returns rating of track, taking provided rating read from tags if track was not accessed yet
+    pub fn get_or_insert(&mut self, path: &str, tag_rating: TrackRating) -> TrackRating {
+        if let Some(rating) = self.index.get(path) {
+            return *rating;
+        }
+        *self.cache.entry(String::from(path)).or_insert(tag_rating)
+    }
+
+    /// Sets number of stars for track, 0 removes rating
+    pub fn set_stars(&mut self, path: &str, stars: u8) {
+        let mut rating = self.get(path);
+        rating.stars = Some(stars.min(5)).filter(|s| *s > 0);
+        self.store(path, rating);
+    }
+
+    pub fn toggle_favourite(&mut self, path: &str) {
+        let mut rating = self.get(path);
+        rating.favourite = !rating.favourite;
+        self.store(path, rating);
+    }
+
+    /// Returns rating from index or cache, taking over ratings read by background worker
+    fn lookup(&mut self, path: &str) -> Option<TrackRating> {
+        if let Some(rating) = self.index.get(path) {
+            return Some(*rating);
+        }
+        for (loaded_path, rating) in self.loaded.lock().unwrap().drain() {
+            self.requested.remove(&loaded_path);
+            // Rating stored in the meantime is newer than one read by worker
+            self.cache.entry(loaded_path).or_insert(rating);
+        }
+        self.cache.get(path).copied()
+    }
+
+    fn read_tags(loaded: Arc<Mutex<HashMap<String, TrackRating>>>, requests: Arc<TagRequests>) {
+        loop {
+            let paths: Vec<String> = {
+                let mut requested = requests
+                    .changed
+                    .wait_while(requests.requested.lock().unwrap(), |r| {
+                        r.is_empty() && !requests.closed.load(Ordering::Relaxed)
+                    })
+                    .unwrap();
+                if requests.closed.load(Ordering::Relaxed) {
+                    break;
+                }
+                requested.drain(..).collect()
+            };
+            for path in paths {
+                let rating = read_tag_rating(&path);
+                loaded.lock().unwrap().insert(path, rating);
+                requests.pending.fetch_sub(1, Ordering::Relaxed);
+            }
+        }
+    }
+
+    fn store(&mut self, path: &str, rating: TrackRating) {
+        match write_tag_rating(path, &rating) {
+            Ok(()) => {
+                debug!("Saved rating of {path} in tags");
+                self.cache.insert(String::from(path), rating);
+                if self.index.remove(path).is_some() {
+                    self.save_index();
+                }
+            }
+            Err(e) => {
+                debug!("Could not write rating to tags of {path}, using library index: {e}");
+                self.index.insert(String::from(path), rating);
+                self.save_index();
+            }
+        }
+    }
+
+    fn save_index(&self) {
+        let mut lines: Vec<String> = self
+            .index
+            .iter()
+            .map(|(path, rating)| {
+                format!(
+                    "{path}\t{}\t{}",
+                    rating.stars.unwrap_or(0),
+                    rating.favourite as u8
+                )
+            })
+            .collect();
+        lines.sort();
+        let result = self
+            .index_file
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|_| fs::write(&self.index_file, lines.join("\n")));
+        if let Err(e) = result {
+            warn!("Failed to save library index: {e}");
+        }
+    }
+}
+
+impl Drop for Ratings {
+    fn drop(&mut self) {
+        // Lock is held, so worker cannot miss notification between check and wait
+        let _requested = self.requests.requested.lock().unwrap();
+        self.requests.closed.store(true, Ordering::Relaxed);
+        self.requests.changed.notify_all();
+    }
+}
+
+/// Reads rating from ID3 tags of file, unrated if file has no tags
+pub fn read_tag_rating(path: &str) -> TrackRating {
+    match Tag::read_from_path(path) {
+        Ok(tag) => {
+            let popularimeters: Vec<&Popularimeter> = tag
+                .frames()
+                .filter_map(|f| f.content().popularimeter())
+                .collect();
+            // Own rating takes precedence over ratings of other players
+            let own = popularimeters
+                .iter()
+                .find(|p| p.user == POPULARIMETER_USER)
+                .and_then(|p| popularimeter_to_stars(p.rating));
+            TrackRating {
+                stars: own.or_else(|| {
+                    popularimeters
+                        .iter()
+                        .find_map(|p| popularimeter_to_stars(p.rating))
+                }),
+                favourite: tag
+                    .extended_texts()
+                    .any(|t| t.description == FAVOURITE_FRAME_DESCRIPTION && t.value == "1"),
+            }
+        }
+        Err(_) => TrackRating::default(),
+    }
+}
+
+fn write_tag_rating(path: &str, rating: &TrackRating) -> io::Result<()> {
+    if !path.to_lowercase().ends_with(".mp3") {
+        return Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "Only mp3 files support ID3 tags",
+        ));
+    }
+    // Existing tag keeps its version, as many players handle ID3v2.4 poorly
+    let (mut tag, version) = match Tag::read_from_path(path) {
+        Ok(tag) => {
+            let version = tag.version();
+            (tag, version)
+        }
+        Err(e) if matches!(e.kind, id3::ErrorKind::NoTag) => (Tag::new(), Version::Id3v24),
+        Err(e) => return Err(io::Error::other(e.to_string())),
+    };
+    // Only own frame is replaced, ratings and play counters of other players stay intact
+    let mut counter = 0;
+    for frame in tag.remove("POPM") {
+        match frame.content().popularimeter() {
+            Some(p) if p.user == POPULARIMETER_USER => counter = p.counter,
+            _ => {
+                tag.add_frame(frame);
+            }
+        }
+    }
+    // Frame of unrated track is kept only to preserve play counter
+    if rating.stars.is_some() || counter > 0 {
+        tag.add_frame(Frame::with_content(
+            "POPM",
+            Content::Popularimeter(Popularimeter {
+                user: String::from(POPULARIMETER_USER),
+                rating: rating.stars.map_or(0, stars_to_popularimeter),
+                counter,
+            }),
+        ));
+    }
+    tag.remove_extended_text(Some(FAVOURITE_FRAME_DESCRIPTION), None);
+    if rating.favourite {
+        tag.add_frame(ExtendedText {
+            description: String::from(FAVOURITE_FRAME_DESCRIPTION),
+            value: String::from("1"),
+        });
+    }
+    tag.write_to_path(path, version)
+        .map_err(|e| io::Error::other(e.to_string()))
+}
+
+fn parse_index_line(line: &str) -> Option<(String, TrackRating)> {
+    let mut columns = line.split('\t');
+    let (path, stars, favourite) = (columns.next()?, columns.next()?, columns.next()?);
+    Some((
+        String::from(path),
+        TrackRating {
+            stars: stars.parse::<u8>().ok().filter(|s| (1..=5).contains(s)),
+            favourite: favourite == "1",
+        },
+    ))
+}
@@ -0,0 +1,243 @@
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs::{self, File},
+        thread,
+        time::{Duration, Instant},
+    };
+
+    use id3::{Tag, TagLike};
+    use tempdir::TempDir;
+
+    use crate::library::{
+        ratings::{popularimeter_to_stars, stars_to_popularimeter, RatingFilter, TrackRating},
+        Ratings,
+    };
+
+    #[test]
+    fn should_store_rating_in_id3_tags() -> Result<(), Box<dyn std::error::Error>> {
+        // given
+        let tmp_dir = TempDir::new("ratings")?;
+        let song_path = tmp_dir.path().join("song.mp3");
+        File::create(&song_path)?;
+        let song = song_path.to_string_lossy().to_string();
+        let index_file = tmp_dir.path().join("library_index");
+        let mut ratings = Ratings::load(&index_file);
+
+        // when
+        ratings.set_stars(&song, 4);
+        ratings.toggle_favourite(&song);
+
+        // then
+        let tag = Tag::read_from_path(&song_path)?;
+        let popularimeter = tag
+            .frames()
+            .find_map(|f| f.content().popularimeter())
+            .unwrap();
+        assert_eq!(popularimeter.rating, 196);
+        assert_eq!(
+            Ratings::load(&index_file).get(&song),
+            TrackRating {
+                stars: Some(4),
+                favourite: true
+            }
+        );
+        assert!(!index_file.exists());
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_keep_version_of_existing_tag() -> Result<(), Box<dyn std::error::Error>> {
+        // given
+        let tmp_dir = TempDir::new("ratings")?;
+        let song_path = tmp_dir.path().join("song.mp3");
+        File::create(&song_path)?;
+        let mut tag = Tag::new();
+        tag.set_title("Old tagger");
+        tag.write_to_path(&song_path, id3::Version::Id3v23)?;
+        let song = song_path.to_string_lossy().to_string();
+        let mut ratings = Ratings::load(&tmp_dir.path().join("library_index"));
+
+        // when
+        ratings.set_stars(&song, 5);
+
+        // then
+        let tag = Tag::read_from_path(&song_path)?;
+        assert_eq!(tag.version(), id3::Version::Id3v23);
+        assert_eq!(tag.title(), Some("Old tagger"));
+        assert_eq!(ratings.get(&song).stars, Some(5));
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_store_rating_in_library_index_for_files_without_id3(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        // given
+        let tmp_dir = TempDir::new("ratings")?;
+        let song_path = tmp_dir.path().join("song.flac");
+        fs::write(&song_path, "flac data")?;
+        let song = song_path.to_string_lossy().to_string();
+        let index_file = tmp_dir.path().join("library_index");
+        let mut ratings = Ratings::load(&index_file);
+
+        // when
+        ratings.set_stars(&song, 2);
+
+        // then
+        assert_eq!(fs::read_to_string(&song_path)?, "flac data");
+        assert_eq!(
+            Ratings::load(&index_file).get(&song),
+            TrackRating {
+                stars: Some(2),
+                favourite: false
+            }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_convert_between_stars_and_popularimeter() {
+        // given
+        let stars = [1, 2, 3, 4, 5];
+
+        // when
+        let result: Vec<Option<u8>> = stars
+            .iter()
+            .map(|s| popularimeter_to_stars(stars_to_popularimeter(*s)))
+            .collect();
+
+        // then
+        assert_eq!(result, vec![Some(1), Some(2), Some(3), Some(4), Some(5)]);
+        assert_eq!(popularimeter_to_stars(0), None);
+    }
+
+    #[test]
+    fn should_filter_tracks_by_rating() {
+        // given
+        let rated = TrackRating {
+            stars: Some(3),
+            favourite: false,
+        };
+        let favourite = TrackRating {
+            stars: None,
+            favourite: true,
+        };
+
+        // when
+        let result = [
+            RatingFilter::Any.matches(&favourite),
+            RatingFilter::AtLeast(3).matches(&rated),
+            RatingFilter::AtLeast(4).matches(&rated),
+            RatingFilter::AtLeast(1).matches(&favourite),
+            RatingFilter::Favourites.matches(&favourite),
+            RatingFilter::Favourites.matches(&rated),
+        ];
+
+        // then
+        assert_eq!(result, [true, true, false, false, true, false]);
+    }
+
+    #[test]
+    fn should_read_rating_written_by_other_players() -> Result<(), Box<dyn std::error::Error>> {
+        // given
+        let tmp_dir = TempDir::new("ratings")?;
+        let song_path = tmp_dir.path().join("song.mp3");
+        File::create(&song_path)?;
+        let mut tag = Tag::new();
+        tag.add_frame(id3::Frame::with_content(
+            "POPM",
+            id3::Content::Popularimeter(id3::frame::Popularimeter {
+                user: String::from("Windows Media Player 9 Series"),
+                rating: 128,
+                counter: 3,
+            }),
+        ));
+        tag.write_to_path(&song_path, id3::Version::Id3v24)?;
+        let mut ratings = Ratings::load(&tmp_dir.path().join("library_index"));
+
+        // when
+        let result = ratings.get(&song_path.to_string_lossy());
+
+        // then
+        assert_eq!(result.stars, Some(3));
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_keep_popularimeters_of_other_players() -> Result<(), Box<dyn std::error::Error>> {
+        // given
+        let tmp_dir = TempDir::new("ratings")?;
+        let song_path = tmp_dir.path().join("song.mp3");
+        File::create(&song_path)?;
+        let mut tag = Tag::new();
+        for (user, rating, counter) in [("other@player.org", 64, 12), ("penny", 128, 7)] {
+            tag.add_frame(id3::Frame::with_content(
+                "POPM",
+                id3::Content::Popularimeter(id3::frame::Popularimeter {
+                    user: String::from(user),
+                    rating,
+                    counter,
+                }),
+            ));
+        }
+        tag.write_to_path(&song_path, id3::Version::Id3v24)?;
+        let song = song_path.to_string_lossy().to_string();
+        let mut ratings = Ratings::load(&tmp_dir.path().join("library_index"));
+
+        // when
+        ratings.set_stars(&song, 5);
+
+        // then
+        let tag = Tag::read_from_path(&song_path)?;
+        let mut popularimeters: Vec<(String, u8, u64)> = tag
+            .frames()
+            .filter_map(|f| f.content().popularimeter())
+            .map(|p| (p.user.clone(), p.rating, p.counter))
+            .collect();
+        popularimeters.sort();
+        assert_eq!(
+            popularimeters,
+            [
+                (String::from("other@player.org"), 64, 12),
+                (String::from("penny"), 255, 7)
+            ]
+        );
+        assert_eq!(
+            Ratings::load(&tmp_dir.path().join("library_index"))
+                .get(&song)
+                .stars,
+            Some(5)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_read_listed_ratings_in_background() -> Result<(), Box<dyn std::error::Error>> {
+        // given
+        let tmp_dir = TempDir::new("ratings")?;
+        let song_path = tmp_dir.path().join("song.mp3");
+        File::create(&song_path)?;
+        let song = song_path.to_string_lossy().to_string();
+        Ratings::load(&tmp_dir.path().join("library_index")).set_stars(&song, 2);
+        let mut ratings = Ratings::load(&tmp_dir.path().join("library_index"));
+
+        // when
+        let first = ratings.get_loaded(&song);
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while ratings.is_loading() && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(10));
+        }
+
+        // then
+        assert_eq!(first, None);
+        assert_eq!(ratings.get_loaded(&song).and_then(|r| r.stars), Some(2));
+        assert!(!ratings.is_loading());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,608 @@
+use log::warn;
+use std::{
+    cmp::Ordering,
+    collections::HashMap,
+    fs, io,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use crate::{
+    files::FileEntry, history::listening_history::PlayRecord, player::metadata::Mp3Metadata,
+    queue::SongFile,
+};
+
+use super::{
+    ratings::{read_tag_rating, TrackRating},
+    Ratings,
+};
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// Track properties which can be used in smart playlist rules
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Field {
+    Artist,
+    Title,
+    Album,
+    Genre,
+    Year,
+    /// Number of stars, unrated tracks have no rating
+    Rating,
+    Favourite,
+    /// Number of times track was played
+    Plays,
+    /// Number of days since track was last played
+    LastPlayed,
+}
+
+impl FromStr for Field {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "artist" => Ok(Field::Artist),
+            "title" => Ok(Field::Title),
+            "album" => Ok(Field::Album),
+            "genre" => Ok(Field::Genre),
+            "year" => Ok(Field::Year),
+            "rating" => Ok(Field::Rating),
+            "favourite" => Ok(Field::Favourite),
+            "plays" => Ok(Field::Plays),
+            "last_played" => Ok(Field::LastPlayed),
+            _ => Err(format!("Unknown field: {name}")),
+        }
+    }
+}
+
+/// Value compared with track property
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Text(String),
+    Number(i64),
+    Bool(bool),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Operator {
+    Equal,
+    NotEqual,
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+    /// Text contains value, ignoring case
+    Contains,
+}
+
+/// Condition tree of smart playlist rule
+#[derive(Debug, Clone, PartialEq)]
+pub enum Condition {
+    Compare(Field, Operator, Value),
+    And(Box<Condition>, Box<Condition>),
+    Or(Box<Condition>, Box<Condition>),
+    Not(Box<Condition>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Order {
+    pub field: Field,
+    pub descending: bool,
+}
+
+/// Parsed smart playlist rule, e.g. `genre = "Jazz" and year < 1970 order by plays desc limit 20`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rule {
+    pub condition: Option<Condition>,
+    pub order: Option<Order>,
+    pub limit: Option<usize>,
+}
+
+/// Track with properties available to smart playlist rules
+#[derive(Clone)]
+pub struct Track {
+    pub path: String,
+    pub metadata: Mp3Metadata,
+    pub rating: TrackRating,
+    pub plays: usize,
+    /// Unix timestamp of last playback
+    pub last_played: Option<u64>,
+}
+
+impl Track {
+    /// Returns value of field, `None` if track does not have it
+    fn value(&self, field: Field, now: u64) -> Option<Value> {
+        match field {
+            Field::Artist => self.metadata.artist.clone().map(Value::Text),
+            Field::Title => self.metadata.title.clone().map(Value::Text),
+            Field::Album => self.metadata.album.clone().map(Value::Text),
+            Field::Genre => self.metadata.genre.clone().map(Value::Text),
+            Field::Year => self.metadata.year.map(|y| Value::Number(y as i64)),
+            Field::Rating => self.rating.stars.map(|s| Value::Number(s as i64)),
+            Field::Favourite => Some(Value::Bool(self.rating.favourite)),
+            Field::Plays => Some(Value::Number(self.plays as i64)),
+            // Tracks never played are treated as played infinitely long ago
+            Field::LastPlayed => Some(Value::Number(
+                self.last_played
+                    .map(|t| (now.saturating_sub(t) / SECONDS_PER_DAY) as i64)
+                    .unwrap_or(i64::MAX),
+            )),
+        }
+    }
+
+    /// Returns value used to sort tracks by field
+    fn sort_key(&self, field: Field, now: u64) -> Option<Value> {
+        match field {
+            Field::LastPlayed => Some(Value::Number(
+                self.last_played
+                    .map(|t| now.saturating_sub(t) as i64)
+                    .unwrap_or(i64::MAX),
+            )),
+            _ => self.value(field, now),
+        }
+    }
+}
+
+impl Condition {
+    pub fn matches(&self, track: &Track, now: u64) -> bool {
+        match self {
+            Condition::Compare(field, operator, value) => match track.value(*field, now) {
+                Some(actual) => compare(&actual, *operator, value),
+                None => *operator == Operator::NotEqual,
+            },
+            Condition::And(left, right) => left.matches(track, now) && right.matches(track, now),
+            Condition::Or(left, right) => left.matches(track, now) || right.matches(track, now),
+            Condition::Not(condition) => !condition.matches(track, now),
+        }
+    }
+}
+
+fn compare(actual: &Value, operator: Operator, expected: &Value) -> bool {
+    if operator == Operator::Contains {
+        return match (actual, expected) {
+            (Value::Text(actual), Value::Text(expected)) => {
+                actual.to_lowercase().contains(&expected.to_lowercase())
+            }
+            _ => false,
+        };
+    }
+    match compare_values(actual, expected) {
+        Some(ordering) => match operator {
+            Operator::Equal => ordering == Ordering::Equal,
+            Operator::NotEqual => ordering != Ordering::Equal,
+            Operator::Less => ordering == Ordering::Less,
+            Operator::LessOrEqual => ordering != Ordering::Greater,
+            Operator::Greater => ordering == Ordering::Greater,
+            Operator::GreaterOrEqual => ordering != Ordering::Less,
+            Operator::Contains => false,
+        },
+        None => false,
+    }
+}
+
+/// Compares values of the same type, texts are compared ignoring case
+fn compare_values(a: &Value, b: &Value) -> Option<Ordering> {
+    match (a, b) {
+        (Value::Text(a), Value::Text(b)) => Some(a.to_lowercase().cmp(&b.to_lowercase())),
+        (Value::Number(a), Value::Number(b)) => Some(a.cmp(b)),
+        (Value::Bool(a), Value::Bool(b)) => Some(a.cmp(b)),
+        _ => None,
+    }
+}
+
+impl Rule {
+    /// Returns paths of tracks matching rule, ordered and limited as requested
+    pub fn evaluate(&self, tracks: &[Track], now: u64) -> Vec<String> {
+        let mut matching: Vec<&Track> = tracks
+            .iter()
+            .filter(|t| match &self.condition {
+                Some(condition) => condition.matches(t, now),
+                None => true,
+            })
+            .collect();
+        if let Some(order) = self.order {
+            matching.sort_by(|a, b| {
+                // Tracks missing sorted field always go last
+                let ordering = match (a.sort_key(order.field, now), b.sort_key(order.field, now)) {
+                    (Some(a), Some(b)) => {
+                        let ordering = compare_values(&a, &b).unwrap_or(Ordering::Equal);
+                        match order.descending {
+                            true => ordering.reverse(),
+                            false => ordering,
+                        }
+                    }
+                    (Some(_), None) => Ordering::Less,
+                    (None, Some(_)) => Ordering::Greater,
+                    (None, None) => Ordering::Equal,
+                };
+                ordering.then_with(|| a.path.cmp(&b.path))
+            });
+        }
+        matching
+            .into_iter()
+            .take(self.limit.unwrap_or(usize::MAX))
+            .map(|t| t.path.clone())
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Word(String),
+    Number(i64),
+    Text(String),
+    Operator(Operator),
+    OpenParen,
+    CloseParen,
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = vec![];
+    let mut chars = input.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '(' => {
+                chars.next();
+                tokens.push(Token::OpenParen);
+            }
+            ')' => {
+                chars.next();
+                tokens.push(Token::CloseParen);
+            }
+            '"' => {
+                chars.next();
+                let mut text = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some(c) => text.push(c),
+                        None => return Err(String::from("Unterminated text value")),
+                    }
+                }
+                tokens.push(Token::Text(text));
+            }
+            '=' | '!' | '<' | '>' | '~' => {
+                chars.next();
+                let followed_by_equal = chars.next_if_eq(&'=').is_some();
+                let operator = match (c, followed_by_equal) {
+                    ('=', false) => Operator::Equal,
+                    ('!', true) => Operator::NotEqual,
+                    ('<', false) => Operator::Less,
+                    ('<', true) => Operator::LessOrEqual,
+                    ('>', false) => Operator::Greater,
+                    ('>', true) => Operator::GreaterOrEqual,
+                    ('~', false) => Operator::Contains,
+                    _ => return Err(format!("Unknown operator: {c}=")),
+                };
+                tokens.push(Token::Operator(operator));
+            }
+            c if c.is_ascii_digit() => {
+                let mut number = String::new();
+                while let Some(digit) = chars.next_if(|c| c.is_ascii_digit()) {
+                    number.push(digit);
+                }
+                let number = number
+                    .parse()
+                    .map_err(|_| format!("Invalid number: {number}"))?;
+                tokens.push(Token::Number(number));
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let mut word = String::new();
+                while let Some(c) = chars.next_if(|c| c.is_alphanumeric() || *c == '_') {
+                    word.push(c);
+                }
+                tokens.push(Token::Word(word.to_lowercase()));
+            }
+            _ => return Err(format!("Unexpected character: {c}")),
+        }
+    }
+    Ok(tokens)
+}
+
+/// Recursive descent parser of rule tokens
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn next_is_word(&self, word: &str) -> bool {
+        matches!(self.peek(), Some(Token::Word(w)) if w == word)
+    }
+
+    fn expect_word(&mut self, word: &str) -> Result<(), String> {
+        match self.next() {
+            Some(Token::Word(w)) if w == word => Ok(()),
+            _ => Err(format!("Expected '{word}'")),
+        }
+    }
+
+    fn parse_rule(&mut self) -> Result<Rule, String> {
+        let mut rule = match self.peek() {
+            Some(Token::Number(_)) => self.parse_shorthand()?,
+            _ => Rule {
+                condition: None,
+                order: None,
+                limit: None,
+            },
+        };
+        if rule.order.is_some() && self.next_is_word("where") {
+            self.next();
+        }
+        if self.peek().is_some() && !self.next_is_word("order") && !self.next_is_word("limit") {
+            rule.condition = Some(self.parse_or()?);
+        }
+        if self.next_is_word("order") {
+            self.next();
+            self.expect_word("by")?;
+            let field = match self.next() {
+                Some(Token::Word(name)) => name.parse()?,
+                _ => return Err(String::from("Expected field after 'order by'")),
+            };
+            let descending = self.next_is_word("desc");
+            if descending || self.next_is_word("asc") {
+                self.next();
+            }
+            rule.order = Some(Order { field, descending });
+        }
+        if self.next_is_word("limit") {
+            self.next();
+            rule.limit = match self.next() {
+                Some(Token::Number(limit)) => Some(limit as usize),
+                _ => return Err(String::from("Expected number after 'limit'")),
+            };
+        }
+        match self.peek() {
+            Some(token) => Err(format!("Unexpected {token:?}")),
+            None => Ok(rule),
+        }
+    }
+
+    /// Parses shortcuts like `50 least recently played` or `20 most played`
+    fn parse_shorthand(&mut self) -> Result<Rule, String> {
+        let limit = match self.next() {
+            Some(Token::Number(limit)) => limit as usize,
+            _ => return Err(String::from("Expected number")),
+        };
+        let most = match self.next() {
+            Some(Token::Word(w)) if w == "most" => true,
+            Some(Token::Word(w)) if w == "least" => false,
+            _ => return Err(String::from("Expected 'most' or 'least'")),
+        };
+        let order = match self.next() {
+            Some(Token::Word(w)) if w == "recently" => {
+                self.expect_word("played")?;
+                Order {
+                    field: Field::LastPlayed,
+                    descending: !most,
+                }
+            }
+            Some(Token::Word(w)) if w == "played" => Order {
+                field: Field::Plays,
+                descending: most,
+            },
+            Some(Token::Word(w)) if w == "rated" => Order {
+                field: Field::Rating,
+                descending: most,
+            },
+            _ => {
+                return Err(String::from(
+                    "Expected 'recently played', 'played' or 'rated'",
+                ))
+            }
+        };
+        Ok(Rule {
+            condition: None,
+            order: Some(order),
+            limit: Some(limit),
+        })
+    }
+
+    fn parse_or(&mut self) -> Result<Condition, String> {
+        let mut condition = self.parse_and()?;
+        while self.next_is_word("or") {
+            self.next();
+            condition = Condition::Or(Box::new(condition), Box::new(self.parse_and()?));
+        }
+        Ok(condition)
+    }
+
+    fn parse_and(&mut self) -> Result<Condition, String> {
+        let mut condition = self.parse_unary()?;
+        while self.next_is_word("and") {
+            self.next();
+            condition = Condition::And(Box::new(condition), Box::new(self.parse_unary()?));
+        }
+        Ok(condition)
+    }
+
+    fn parse_unary(&mut self) -> Result<Condition, String> {
+        match self.next() {
+            Some(Token::Word(w)) if w == "not" => Ok(Condition::Not(Box::new(self.parse_unary()?))),
+            Some(Token::OpenParen) => {
+                let condition = self.parse_or()?;
+                match self.next() {
+                    Some(Token::CloseParen) => Ok(condition),
+                    _ => Err(String::from("Expected ')'")),
+                }
+            }
+            Some(Token::Word(name)) => {
+                let field: Field = name.parse()?;
+                let operator = match self.next() {
+                    Some(Token::Operator(operator)) => operator,
+                    _ => return Err(format!("Expected operator after '{name}'")),
+                };
+                let value = match self.next() {
+                    Some(Token::Text(text)) => Value::Text(text),
+                    Some(Token::Number(number)) => Value::Number(number),
+                    Some(Token::Word(w)) if w == "true" => Value::Bool(true),
+                    Some(Token::Word(w)) if w == "false" => Value::Bool(false),
+                    _ => return Err(format!("Expected value after '{name}'")),
+                };
+                validate_comparison(field, operator, &value)?;
+                Ok(Condition::Compare(field, operator, value))
+            }
+            _ => Err(String::from("Expected condition")),
+        }
+    }
+}
+
+/// Checks if value has type matching field and operator is applicable to it
+fn validate_comparison(field: Field, operator: Operator, value: &Value) -> Result<(), String> {
+    let valid = match (field, value) {
+        (Field::Artist | Field::Title | Field::Album | Field::Genre, Value::Text(_)) => true,
+        (Field::Year | Field::Rating | Field::Plays | Field::LastPlayed, Value::Number(_)) => {
+            operator != Operator::Contains
+        }
+        (Field::Favourite, Value::Bool(_)) => {
+            matches!(operator, Operator::Equal | Operator::NotEqual)
+        }
+        _ => false,
+    };
+    match valid {
+        true => Ok(()),
+        false => Err(format!("Invalid comparison of {field:?} with {value:?}")),
+    }
+}
+
+impl FromStr for Rule {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser {
+            tokens: tokenize(input)?,
+            position: 0,
+        };
+        parser.parse_rule()
+    }
+}
+
+/// Named rule saved in playlist file
+#[derive(Debug, Clone, PartialEq)]
+pub struct SmartPlaylist {
+    pub name: String,
+    pub definition: String,
+    pub rule: Rule,
+}
+
+/// Reads smart playlists.
+/// Each line of file contains playlist in format `<name> = <rule>`,
+/// lines starting with `#` are treated as comments.
+pub fn read_smart_playlists(path: &Path) -> io::Result<Vec<SmartPlaylist>> {
+    let content = fs::read_to_string(path)?;
+    Ok(content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !is_blank_or_comment(line))
+        .filter_map(|(number, line)| {
+            let playlist = parse_playlist_line(line);
+            if let Err(e) = &playlist {
+                warn!("Skipping invalid playlist in line {}: {e}", number + 1);
+            }
+            playlist.ok()
+        })
+        .collect())
+}
+
+fn parse_playlist_line(line: &str) -> Result<SmartPlaylist, String> {
+    let (name, definition) = line
+        .split_once('=')
+        .ok_or_else(|| String::from("Missing '='"))?;
+    let (name, definition) = (name.trim(), definition.trim());
+    if name.is_empty() {
+        return Err(String::from("Missing playlist name"));
+    }
+    Ok(SmartPlaylist {
+        name: String::from(name),
+        definition: String::from(definition),
+        rule: definition.parse()?,
+    })
+}
+
+fn is_blank_or_comment(line: &str) -> bool {
+    let line = line.trim();
+    line.is_empty() || line.starts_with('#')
+}
+
+/// Track found in library directory, ready to be queued, with rating read from its tags
+#[derive(Clone)]
+pub struct LibraryTrack {
+    pub song: SongFile,
+    pub rating: TrackRating,
+}
+
+/// Reads tags and durations of all mp3 files below library directory.
+/// Reads every file, so it should not be called on interface thread.
+pub fn scan_library(root: &Path) -> Vec<LibraryTrack> {
+    find_mp3_files(root)
+        .iter()
+        .filter_map(|path| SongFile::new(&FileEntry::new(path)).ok())
+        .map(|song| LibraryTrack {
+            rating: read_tag_rating(&song.file_entry.path),
+            song,
+        })
+        .collect()
+}
+
+/// Combines scanned library with current ratings and play history
+pub fn read_library(
+    library: &[LibraryTrack],
+    ratings: &mut Ratings,
+    records: &[PlayRecord],
+) -> Vec<Track> {
+    let mut history: HashMap<&str, (usize, u64)> = HashMap::new();
+    for record in records {
+        let entry = history.entry(&record.path).or_insert((0, 0));
+        entry.0 += 1;
+        entry.1 = entry.1.max(record.timestamp);
+    }
+    library
+        .iter()
+        .map(|track| {
+            let path = track.song.file_entry.path.clone();
+            let (plays, last_played) = match history.get(path.as_str()) {
+                Some((plays, last_played)) => (*plays, Some(*last_played)),
+                None => (0, None),
+            };
+            Track {
+                rating: ratings.get_or_insert(&path, track.rating),
+                path,
+                metadata: track.song.metadata.clone(),
+                plays,
+                last_played,
+            }
+        })
+        .collect()
+}
+
+/// Returns paths of all mp3 files below given directory.
+/// Symlinked directories are skipped, so links pointing to their parents cannot loop.
+pub fn find_mp3_files(directory: &Path) -> Vec<PathBuf> {
+    let mut files = vec![];
+    let Ok(entries) = fs::read_dir(directory) else {
+        warn!("Cannot read library directory {}", directory.display());
+        return files;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if entry.file_type().is_ok_and(|t| t.is_dir()) {
+            files.extend(find_mp3_files(&path));
+        } else if path.extension().is_some_and(|e| e == "mp3") && path.is_file() {
+            files.push(path);
+        }
+    }
+    files
+}
@@ -0,0 +1,242 @@
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs,
+        os::unix::fs::symlink,
+        thread,
+        time::{Duration, Instant},
+    };
+
+    use tempdir::TempDir;
+
+    use crate::library::{
+        ratings::TrackRating,
+        smart_playlist::{
+            find_mp3_files, read_smart_playlists, Condition, Field, Operator, Order, Rule, Track,
+            Value,
+        },
+        Ratings, SmartPlaylistView,
+    };
+    use crate::player::metadata::Mp3Metadata;
+
+    const NOW: u64 = 1_700_000_000;
+    const DAY: u64 = 24 * 60 * 60;
+
+    fn track(
+        path: &str,
+        genre: &str,
+        year: i32,
+        stars: Option<u8>,
+        last_played: Option<u64>,
+    ) -> Track {
+        Track {
+            path: String::from(path),
+            metadata: Mp3Metadata {
+                artist: Some(String::from("Artist")),
+                title: Some(String::from(path)),
+                album: None,
+                genre: Some(String::from(genre)),
+                year: Some(year),
+                track: None,
+                file_path: String::from(path),
+            },
+            rating: TrackRating {
+                stars,
+                favourite: false,
+            },
+            plays: last_played.map_or(0, |_| 1),
+            last_played,
+        }
+    }
+
+    fn library() -> Vec<Track> {
+        vec![
+            track("kind_of_blue", "Jazz", 1959, Some(5), Some(NOW - 2 * DAY)),
+            track("a_love_supreme", "jazz", 1965, Some(3), None),
+            track("bitches_brew", "Jazz", 1970, Some(4), Some(NOW - 10 * DAY)),
+            track("nevermind", "Grunge", 1991, None, Some(NOW - DAY)),
+        ]
+    }
+
+    #[test]
+    fn should_parse_rule_with_and_binding_stronger_than_or() {
+        // given
+        let definition = r#"genre = "Jazz" or year < 1970 and not rating >= 4"#;
+
+        // when
+        let result: Result<Rule, String> = definition.parse();
+
+        // then
+        let compare = |field, operator, value| Box::new(Condition::Compare(field, operator, value));
+        assert_eq!(
+            result,
+            Ok(Rule {
+                condition: Some(Condition::Or(
+                    compare(
+                        Field::Genre,
+                        Operator::Equal,
+                        Value::Text(String::from("Jazz"))
+                    ),
+                    Box::new(Condition::And(
+                        compare(Field::Year, Operator::Less, Value::Number(1970)),
+                        Box::new(Condition::Not(compare(
+                            Field::Rating,
+                            Operator::GreaterOrEqual,
+                            Value::Number(4)
+                        )))
+                    ))
+                )),
+                order: None,
+                limit: None,
+            })
+        );
+    }
+
+    #[test]
+    fn should_reject_invalid_rules() {
+        // given
+        let definitions = [
+            r#"genre = "Jazz" and"#,
+            "tempo > 120",
+            r#"year = "1970""#,
+            "rating ~ 3",
+            r#"(genre = "Jazz""#,
+            "limit many",
+            r#"artist = "Miles"#,
+        ];
+
+        // when
+        let result: Vec<bool> = definitions
+            .iter()
+            .map(|d| d.parse::<Rule>().is_err())
+            .collect();
+
+        // then
+        assert!(result.iter().all(|invalid| *invalid), "{result:?}");
+    }
+
+    #[test]
+    fn should_select_tracks_matching_conditions() -> Result<(), String> {
+        // given
+        let rule: Rule = r#"genre = "Jazz" and year < 1970 and rating >= 4"#.parse()?;
+
+        // when
+        let result = rule.evaluate(&library(), NOW);
+
+        // then
+        assert_eq!(result, vec!["kind_of_blue"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_select_least_recently_played_tracks() -> Result<(), String> {
+        // given
+        let rule: Rule = "3 least recently played".parse()?;
+
+        // when
+        let result = rule.evaluate(&library(), NOW);
+
+        // then
+        assert_eq!(
+            rule.order,
+            Some(Order {
+                field: Field::LastPlayed,
+                descending: true
+            })
+        );
+        assert_eq!(
+            result,
+            vec!["a_love_supreme", "bitches_brew", "kind_of_blue"]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_order_and_limit_filtered_tracks() -> Result<(), String> {
+        // given
+        let rule: Rule =
+            r#"genre ~ "jaz" and last_played > 1 order by year desc limit 2"#.parse()?;
+
+        // when
+        let result = rule.evaluate(&library(), NOW);
+
+        // then
+        assert_eq!(result, vec!["bitches_brew", "a_love_supreme"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_read_playlists_skipping_invalid_lines() -> Result<(), Box<dyn std::error::Error>> {
+        // given
+        let tmp_dir = TempDir::new("playlists")?;
+        let playlist_file = tmp_dir.path().join("playlists");
+        fs::write(
+            &playlist_file,
+            "# smart playlists\nOld jazz = genre = \"Jazz\" and year < 1970\n\nBroken = year <\nForgotten = 50 least recently played\n",
+        )?;
+
+        // when
+        let result = read_smart_playlists(&playlist_file)?;
+
+        // then
+        let names: Vec<&str> = result.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["Old jazz", "Forgotten"]);
+        assert_eq!(result[1].rule.limit, Some(50));
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_skip_symlinked_directories_when_searching_library() -> Result<(), std::io::Error> {
+        // given
+        let tmp_dir = TempDir::new("library")?;
+        let album = tmp_dir.path().join("album");
+        fs::create_dir(&album)?;
+        fs::write(tmp_dir.path().join("single.mp3"), "")?;
+        fs::write(album.join("track.mp3"), "")?;
+        symlink(tmp_dir.path(), album.join("loop"))?;
+
+        // when
+        let mut result = find_mp3_files(tmp_dir.path());
+
+        // then
+        result.sort();
+        assert_eq!(
+            result,
+            vec![album.join("track.mp3"), tmp_dir.path().join("single.mp3")]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_evaluate_playlist_after_library_scan() -> Result<(), Box<dyn std::error::Error>> {
+        // given
+        let tmp_dir = TempDir::new("library")?;
+        let playlist_file = tmp_dir.path().join("playlists");
+        fs::write(&playlist_file, "Jazz = genre ~ \"jaz\"\n")?;
+        let mut ratings = Ratings::load(&tmp_dir.path().join("library_index"));
+        let mut view = SmartPlaylistView::new(&playlist_file, &tmp_dir.path().join("music"));
+        view.state.select(Some(0));
+
+        // when
+        let immediate = view.evaluate_selected(&mut ratings, &[], NOW);
+        view.reload();
+        let start = Instant::now();
+        let mut scanned = None;
+        while scanned.is_none() && start.elapsed() < Duration::from_secs(5) {
+            scanned = view.update(&mut ratings, &[], NOW);
+            thread::sleep(Duration::from_millis(10));
+        }
+
+        // then
+        assert!(immediate.is_none());
+        assert_eq!(scanned.map(|songs| songs.len()), Some(0));
+        assert_eq!(view.playlists[0].name, "Jazz");
+
+        Ok(())
+    }
+}
@@ -0,0 +1,181 @@
+use log::{debug, error, warn};
+use ratatui::widgets::ListState;
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    thread,
+};
+
+use crate::{application::actions::Action, history::PlayRecord, queue::SongFile};
+
+use super::{
+    smart_playlist::{
+        read_library, read_smart_playlists, scan_library, LibraryTrack, SmartPlaylist,
+    },
+    Ratings,
+};
+
+/// View listing smart playlists saved in playlist file
+pub struct SmartPlaylistView {
+    pub state: ListState,
+    pub playlists: Vec<SmartPlaylist>,
+    playlist_file: PathBuf,
+    /// Directory searched for tracks matching playlist rules
+    library_directory: PathBuf,
+    /// Tracks found by last library scan, kept until next scan finishes
+    library: Arc<Mutex<Option<Vec<LibraryTrack>>>>,
+    scanning: Arc<AtomicBool>,
+    /// Playlist chosen while library was not scanned yet, evaluated once scan finishes
+    pending: Option<usize>,
+}
+
+impl SmartPlaylistView {
+    pub fn new(playlist_file: &Path, library_directory: &Path) -> Self {
+        SmartPlaylistView {
+            state: ListState::default(),
+            playlists: vec![],
+            playlist_file: playlist_file.to_path_buf(),
+            library_directory: library_directory.to_path_buf(),
+            library: Arc::new(Mutex::new(None)),
+            scanning: Arc::new(AtomicBool::new(false)),
+            pending: None,
+        }
+    }
+
+    pub fn do_action(&mut self, action: Action) {
+        match action {
+            Action::ViewerUp => self.previous(),
+            Action::ViewerDown => self.next(),
+            _ => error!("Unsupported smart playlist view action: {action:?}"),
+        }
+    }
+
+    /// Reads playlists again, so changes made in playlist file are visible
+    pub fn reload(&mut self) {
+        self.playlists = match read_smart_playlists(&self.playlist_file) {
+            Ok(playlists) => playlists,
+            Err(e) => {
+                warn!(
+                    "Cannot read smart playlists from {}: {e}",
+                    self.playlist_file.display()
+                );
+                vec![]
+            }
+        };
+        match self.state.selected() {
+            Some(i) if i < self.playlists.len() => {}
+            _ if self.playlists.is_empty() => self.state = ListState::default(),
+            _ => self.state.select(Some(0)),
+        }
+        self.scan();
+    }
+
+    /// Reads tags and durations of library tracks in background thread
+    pub fn scan(&mut self) {
+        if self.scanning.swap(true, Ordering::Relaxed) {
+            return;
+        }
+        let directory = self.library_directory.clone();
+        let library = self.library.clone();
+        let scanning = self.scanning.clone();
+        thread::spawn(move || {
+            let tracks = scan_library(&directory);
+            debug!(
+                "Scanned {} library tracks in {}",
+                tracks.len(),
+                directory.display()
+            );
+            *library.lock().unwrap() = Some(tracks);
+            scanning.store(false, Ordering::Relaxed);
+        });
+    }
+
+    pub fn is_scanning(&self) -> bool {
+        self.scanning.load(Ordering::Relaxed)
+    }
+
+    /// Returns tracks matching selected playlist, read by library scan.
+    /// If library was not scanned yet, starts scan and returns `None`,
+    /// playlist is then evaluated by [update](SmartPlaylistView::update).
+    pub fn evaluate_selected(
+        &mut self,
+        ratings: &mut Ratings,
+        records: &[PlayRecord],
+        now: u64,
+    ) -> Option<Vec<SongFile>> {
+        let index = self.state.selected()?;
+        match self.evaluate(index, ratings, records, now) {
+            Some(songs) => Some(songs),
+            None => {
+                self.pending = Some(index);
+                self.scan();
+                None
+            }
+        }
+    }
+
+    /// Evaluates playlist chosen before library scan finished.
+    /// Returns its tracks once library is available.
+    pub fn update(
+        &mut self,
+        ratings: &mut Ratings,
+        records: &[PlayRecord],
+        now: u64,
+    ) -> Option<Vec<SongFile>> {
+        let index = self.pending?;
+        let songs = self.evaluate(index, ratings, records, now)?;
+        self.pending = None;
+        Some(songs)
+    }
+
+    fn evaluate(
+        &self,
+        index: usize,
+        ratings: &mut Ratings,
+        records: &[PlayRecord],
+        now: u64,
+    ) -> Option<Vec<SongFile>> {
+        let library = self.library.lock().unwrap();
+        let library = library.as_ref()?;
+        let tracks = read_library(library, ratings, records);
+        let paths = match self.playlists.get(index) {
+            Some(playlist) => playlist.rule.evaluate(&tracks, now),
+            None => vec![],
+        };
+        // Songs were read by scan, so queueing them does not read files on interface thread
+        let songs: HashMap<&str, &SongFile> = library
+            .iter()
+            .map(|t| (t.song.file_entry.path.as_str(), &t.song))
+            .collect();
+        Some(
+            paths
+                .iter()
+                .filter_map(|path| songs.get(path.as_str()).map(|song| (*song).clone()))
+                .collect(),
+        )
+    }
+
+    fn next(&mut self) {
+        let i = match self.state.selected() {
+            Some(i) if i + 1 < self.playlists.len() => i + 1,
+            _ => 0,
+        };
+        if !self.playlists.is_empty() {
+            self.state.select(Some(i));
+        }
+    }
+
+    fn previous(&mut self) {
+        let i = match self.state.selected() {
+            Some(0) | None => self.playlists.len().saturating_sub(1),
+            Some(i) => i - 1,
+        };
+        if !self.playlists.is_empty() {
+            self.state.select(Some(i));
+        }
+    }
+}
@@ -1,8 +1,9 @@
 use application::App;
 use clap::Parser;
 use cli::config::Config;
+use daemon::{RemoteClient, RemoteCommand};
 use input::EventBus;
-use runner::run_app;
+use runner::{run_app, run_attached, run_daemon};
 use std::{
     io,
     sync::{Arc, Mutex},
@@ -11,25 +12,51 @@ use std::{
 
 mod application;
 mod cli;
+mod clock;
+mod daemon;
+mod error;
 mod external;
 mod files;
+mod history;
 mod input;
+mod library;
 mod player;
+mod podcast;
 mod queue;
 mod runner;
 
 fn main() -> io::Result<()> {
     let config = Config::parse();
     let tick_rate = Duration::from_millis(150);
-    let events = Arc::new(Mutex::new(EventBus::new(tick_rate)));
-    match App::new(&config, events.clone()) {
-        Some(mut app) => {
-            run_app(&mut app, events)?;
-        }
-        None => {
-            println!("Failed to open {}, terminating.", config.starting_directory)
-        }
+    if let Some(command) = &config.remote {
+        return send_remote_command(&config.socket, command);
+    }
+    if config.attach {
+        return run_attached(&config.socket, tick_rate);
+    }
+    let event_bus = match config.daemon {
+        true => EventBus::headless(tick_rate),
+        false => EventBus::new(tick_rate, Duration::from_secs(1) / config.frame_rate),
     };
+    let sender = event_bus.sender();
+    let events = Arc::new(Mutex::new(event_bus));
+    match App::new(&config, sender) {
+        Ok(mut app) => match config.daemon {
+            true => run_daemon(&mut app, events, &config.socket)?,
+            false => run_app(&mut app, events)?,
+        },
+        Err(e) => println!("{e}, terminating."),
+    };
+
+    Ok(())
+}
+
+fn send_remote_command(socket_path: &str, command: &str) -> io::Result<()> {
+    let command = command
+        .parse::<RemoteCommand>()
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+    let response = RemoteClient::connect(socket_path)?.send(&command)?;
+    println!("{response}");
 
     Ok(())
 }
@@ -9,6 +9,8 @@ pub enum DurationFormat {
     /// "01:15"
     /// ```
     MmSs,
+    /// Formats duration as "HH:MM:SS" with leading zeros, used for longer spans like whole queue
+    HhMmSs,
 }
 
 pub trait DurationFormatter {
@@ -23,6 +25,15 @@ impl DurationFormatter for Duration {
                 let seconds = self.as_secs();
                 format!("{:0>2}:{:0>2}", seconds / 60, seconds % 60)
             }
+            DurationFormat::HhMmSs => {
+                let seconds = self.as_secs();
+                format!(
+                    "{:0>2}:{:0>2}:{:0>2}",
+                    seconds / 3600,
+                    seconds / 60 % 60,
+                    seconds % 60
+                )
+            }
         }
     }
 }
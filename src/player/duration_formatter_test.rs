@@ -15,4 +15,16 @@ mod tests {
         // then
         assert_eq!(result, "01:15");
     }
+
+    #[test]
+    fn should_format_duration_with_hours() {
+        // given
+        let duration = Duration::from_secs(3 * 3600 + 75);
+
+        // when
+        let result = duration.format(DurationFormat::HhMmSs);
+
+        // then
+        assert_eq!(result, "03:01:15");
+    }
 }
@@ -1,10 +1,7 @@
 use minimp3::Frame;
 use minimp3_fixed as minimp3;
 use rodio::{cpal::Sample, Source};
-use std::{
-    num::NonZero,
-    time::Duration,
-};
+use std::{num::NonZero, time::Duration};
 
 /// Implementation of Rodio's [Source](rodio::Source) trait
 /// for feeding [Sink](rodio::Sink) one frame at a time.
@@ -3,10 +3,15 @@ use id3::{Tag, TagLike};
 use std::path::Path;
 
 /// Metadata of mp3 file read from ID3 tags
-#[derive(Clone)]
+#[derive(Clone, Debug)]
 pub struct Mp3Metadata {
     pub artist: Option<String>,
     pub title: Option<String>,
+    pub album: Option<String>,
+    pub genre: Option<String>,
+    pub year: Option<i32>,
+    /// Number of track on its album
+    pub track: Option<u32>,
     pub file_path: String,
 }
 
@@ -15,6 +20,12 @@ impl Mp3Metadata {
         Mp3Metadata {
             artist: tag.artist().map(String::from),
             title: tag.title().map(String::from),
+            album: tag.album().map(String::from),
+            genre: tag.genre_parsed().map(String::from),
+            year: tag
+                .year()
+                .or_else(|| tag.date_recorded().map(|date| date.year)),
+            track: tag.track(),
             file_path: String::from(file_name),
         }
     }
@@ -1,10 +1,13 @@
 //! Module handling playback of mp3 files and extracting mp3
 //! information for display
-mod duration_formatter;
+pub mod duration_formatter;
 mod frame_decoder;
 pub mod metadata;
 pub mod mp3_player;
-mod spectrum_analyzer;
+mod playback_control;
+pub mod spectrum_analyzer;
+pub mod stream;
+pub mod waveform;
 
 use frame_decoder::FrameDecoder;
 pub use metadata::MetadataReader;
@@ -14,3 +17,13 @@ pub use mp3_player::Mp3Player;
 mod duration_formatter_test;
 #[cfg(test)]
 mod metadata_test;
+#[cfg(test)]
+mod mp3_player_test;
+#[cfg(test)]
+mod playback_control_test;
+#[cfg(test)]
+mod spectrum_analyzer_test;
+#[cfg(test)]
+mod stream_test;
+#[cfg(test)]
+mod waveform_test;
@@ -1,21 +1,34 @@
 use crate::{
     application::actions::Action,
-    external::notifier::{notify_playback_start, notify_playback_stopped},
-    input::{events::PlaybackEvent::SongFinished, EventBus},
-    player::{frame_decoder::FrameDuration, spectrum_analyzer::SpectrumAnalyzer, FrameDecoder},
+    clock::unix_timestamp,
+    error::AppError,
+    external::notifier::notify_playback_stopped,
+    input::{
+        events::{publish, PlaybackEvent},
+        AppEvent,
+    },
+    player::{
+        frame_decoder::FrameDuration,
+        playback_control::PlaybackControl,
+        spectrum_analyzer::{Spectrum, SpectrumAnalyzer},
+        stream::{is_stream_url, open_stream, StreamInfo},
+        FrameDecoder,
+    },
     queue::SongFile,
 };
 use log::{debug, error};
-use minimp3::{Decoder, Error};
+use minimp3::{Decoder, Error, Frame};
 use minimp3_fixed as minimp3;
 use std::{
     f64,
     fs::File,
+    io::{self, Read},
     sync::{
-        atomic::{AtomicBool, Ordering},
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        mpsc::Sender,
         Arc, Mutex,
     },
-    thread,
+    thread::{self, JoinHandle},
     time::Duration,
 };
 
@@ -36,6 +49,65 @@ enum PlayerState {
     Stopped,
 }
 
+/// Summary of song playback, published when playback ends
+#[derive(Clone, Debug)]
+pub struct PlaybackReport {
+    pub song: SongFile,
+    /// Unix timestamp of playback start
+    pub started_at: u64,
+    /// Time between start of playback and its end, excluding skipped beginning
+    pub listened: Duration,
+    /// Song was played until its end
+    pub completed: bool,
+}
+
+/// Thread playing selected song, with commands controlling it
+struct Playback {
+    control: Arc<PlaybackControl>,
+    thread: JoinHandle<()>,
+}
+
+impl Playback {
+    /// Requests end of playback and waits until thread finishes.
+    /// Thread still opening song is not waited for, as connecting to stream may take long.
+    fn stop(self, notify: bool) {
+        self.control.stop(notify);
+        if self.control.abandon_opening() {
+            debug!("Playback stopped while opening song, leaving it to finish in background");
+            return;
+        }
+        if self.thread.join().is_err() {
+            error!("Playback thread panicked");
+        }
+    }
+}
+
+/// Destination of decoded frames
+enum AudioOutput {
+    Device(rodio::MixerDeviceSink),
+    /// Mixer not connected to any device, discarding played audio
+    #[cfg(test)]
+    Detached(rodio::mixer::Mixer),
+}
+
+impl AudioOutput {
+    fn mixer(&self) -> &rodio::mixer::Mixer {
+        match self {
+            AudioOutput::Device(sink) => sink.mixer(),
+            #[cfg(test)]
+            AudioOutput::Detached(mixer) => mixer,
+        }
+    }
+}
+
+/// Raw samples of currently played frame
+#[derive(Clone, Default)]
+pub struct AudioFrame {
+    /// Samples of all channels, interleaved
+    pub samples: Vec<i16>,
+    pub channels: usize,
+}
+
 /// Structure responsible for playing mp3 files.
 /// Also allows to retrieve information about playback progress
 /// and selected song information.
@@ -44,28 +116,59 @@ pub struct Mp3Player {
     current_playback_ms_elapsed: Arc<Mutex<f64>>,
     song: Option<SongFile>,
     state: Arc<Mutex<PlayerState>>,
-    /// Flag indicating that player should pause playback
-    paused: Arc<AtomicBool>,
-    /// Flag indicating that player should stop playback
-    stop: Arc<AtomicBool>,
+    /// Thread playing selected song, if playback was started
+    playback: Option<Playback>,
     /// current frame spectrum analyzed data
-    spectrum: Arc<Mutex<Vec<f32>>>,
-    /// struct allowing for sending application events
-    events: Arc<Mutex<EventBus>>,
-    notify_song_end: Arc<AtomicBool>,
+    spectrum: Arc<Mutex<Spectrum>>,
+    /// samples of currently played frame
+    audio_frame: Arc<Mutex<AudioFrame>>,
+    /// Flag indicating that spectrum of played frames should be computed
+    spectrum_enabled: Arc<AtomicBool>,
+    /// Number of frames which spectrum was computed, since player was created
+    analyzed_frames: Arc<AtomicU64>,
+    /// channel for publishing playback events in application
+    events: Sender<AppEvent>,
+    /// information about currently played network stream
+    stream_info: Arc<StreamInfo>,
+    /// Position from which next playback of selected song starts
+    start_position: Option<Duration>,
+    /// Position requested to jump to during playback
+    seek_request: Arc<Mutex<Option<Duration>>>,
+    /// Opens output for each playback
+    open_output: fn() -> Result<AudioOutput, AppError>,
 }
 
 impl Mp3Player {
-    pub fn new(events: Arc<Mutex<EventBus>>) -> Self {
+    pub fn new(events: Sender<AppEvent>) -> Self {
         Mp3Player {
             song: None,
             state: Arc::new(Mutex::new(PlayerState::New)),
-            paused: Arc::new(AtomicBool::new(false)),
-            stop: Arc::new(AtomicBool::new(false)),
+            playback: None,
             current_playback_ms_elapsed: Arc::new(Mutex::new(0.0)),
-            spectrum: Arc::new(Mutex::new(vec![])),
+            spectrum: Arc::new(Mutex::new(Spectrum::default())),
+            audio_frame: Arc::new(Mutex::new(AudioFrame::default())),
+            spectrum_enabled: Arc::new(AtomicBool::new(true)),
+            analyzed_frames: Arc::new(AtomicU64::new(0)),
+            stream_info: Arc::new(StreamInfo::with_events(events.clone())),
             events,
-            notify_song_end: Arc::new(AtomicBool::new(true)),
+            start_position: None,
+            seek_request: Arc::new(Mutex::new(None)),
+            open_output: Self::open_device,
+        }
+    }
+
+    /// Creates player discarding audio instead of sending it to audio device
+    #[cfg(test)]
+    pub fn detached(events: Sender<AppEvent>) -> Self {
+        Mp3Player {
+            open_output: || {
+                let (mixer, _) = rodio::mixer::mixer(
+                    std::num::NonZero::new(2).unwrap(),
+                    std::num::NonZero::new(44100).unwrap(),
+                );
+                Ok(AudioOutput::Detached(mixer))
+            },
+            ..Self::new(events)
         }
     }
 
@@ -74,9 +177,29 @@ impl Mp3Player {
         //! In case player is currently playing other file, stops it
         self.stop_playback(false);
         self.song = Some(song_file);
+        self.start_position = None;
         *self.state.lock().unwrap() = PlayerState::SongSelected;
     }
 
+    /// Sets position from which playback of selected song will start
+    pub fn set_start_position(&mut self, position: Duration) {
+        self.start_position = Some(position);
+    }
+
+    /// Moves playback of current song to given fraction of its duration.
+    /// Network streams cannot be seeked.
+    pub fn seek(&mut self, fraction: f64) {
+        let Some(song) = self.song.as_ref().filter(|s| !s.is_stream()) else {
+            return;
+        };
+        let position = song.duration.mul_f64(fraction.clamp(0.0, 1.0));
+        debug!("Seeking to {}", position.format(DurationFormat::MmSs));
+        match self.is_playing() {
+            true => *self.seek_request.lock().unwrap() = Some(position),
+            false => self.set_start_position(position),
+        }
+    }
+
     pub fn handle_action(&mut self, action: Action) {
         match action {
             Action::TogglePlayback => self.toggle_playback(),
@@ -85,6 +208,11 @@ impl Mp3Player {
         }
     }
 
+    /// Returns true if song is being played and not paused, so its progress changes
+    pub fn is_running(&self) -> bool {
+        *self.state.lock().unwrap() == PlayerState::Playing
+    }
+
     /// Returns true if player is currently playing song or in paused state
     pub fn is_playing(&self) -> bool {
         match *self.state.lock().unwrap() {
@@ -98,6 +226,9 @@ impl Mp3Player {
         match *self.state.lock().unwrap() {
             PlayerState::New => String::from(" \u{231B} "),
             PlayerState::SongSelected => String::from(" \u{23F9} Stop "),
+            PlayerState::Playing if self.stream_info.buffering.load(Ordering::Relaxed) => {
+                String::from(" \u{231B} Buffering ")
+            }
             PlayerState::Playing => String::from(" \u{23F5} Playing "),
             PlayerState::Paused => String::from(" \u{23F8} Paused "),
             PlayerState::Stopped => String::from(" \u{23F9} Stop "),
@@ -108,6 +239,20 @@ impl Mp3Player {
     /// or default information in case nothing is selected
     pub fn display_information(&mut self) -> Vec<String> {
         match &self.song {
+            Some(song_info) if song_info.is_stream() => {
+                let mut information = song_info.display();
+                if let Some(name) = self.stream_info.station_name.lock().unwrap().as_ref() {
+                    information.push(format!("Name   : {name}"));
+                }
+                if let Some(title) = self.stream_info.title.lock().unwrap().as_ref() {
+                    information.push(format!("Now    : {title}"));
+                }
+                if self.is_playing() {
+                    let buffered = *self.stream_info.buffered_bytes.lock().unwrap();
+                    information.push(format!("Buffer : {} KiB", buffered / 1024));
+                }
+                information
+            }
             Some(song_info) => song_info.display(),
             None => vec![String::from("Artist: --"), String::from("Title : --")],
         }
@@ -116,6 +261,7 @@ impl Mp3Player {
     /// Returns normalized fraction of finished playback [0..1]
     pub fn get_current_song_percentage_progress(&self) -> f64 {
         match &self.song {
+            Some(s) if s.duration.is_zero() => 0.0,
             Some(s) => {
                 let current_progress_mutex = self.get_song_elapsed_seconds();
                 let song_length = s.duration.as_secs();
@@ -128,80 +274,232 @@ impl Mp3Player {
     /// Returns text label for playback progress
     pub fn get_text_progress(&self) -> Option<String> {
         self.song.as_ref().map(|s| {
+            let total = match s.is_stream() {
+                true => String::from("live"),
+                false => s.duration.format(DurationFormat::MmSs),
+            };
             format!(
                 "{} / {}",
                 Duration::from_secs(self.get_song_elapsed_seconds() as u64)
                     .format(DurationFormat::MmSs),
-                s.duration.format(DurationFormat::MmSs)
+                total
             )
         })
     }
 
-    pub fn get_audio_spectrum(&self) -> Vec<f32> {
+    /// Returns time elapsed since start of current song
+    pub fn get_elapsed(&self) -> Duration {
+        Duration::from_secs_f64(self.get_song_elapsed_seconds())
+    }
+
+    /// Returns path of selected song, if any
+    pub fn get_song_path(&self) -> Option<&str> {
+        self.song.as_ref().map(|s| s.file_entry.path.as_str())
+    }
+
+    /// Returns samples of currently played frame
+    pub fn get_audio_frame(&self) -> AudioFrame {
+        self.audio_frame.lock().unwrap().clone()
+    }
+
+    /// Enables or disables computing spectrum of played frames
+    pub fn set_spectrum_enabled(&mut self, enabled: bool) {
+        self.spectrum_enabled.store(enabled, Ordering::Relaxed);
+    }
+
+    /// Returns number of frames analyzed so far, increasing with every new spectrum
+    pub fn get_analyzed_frames(&self) -> u64 {
+        self.analyzed_frames.load(Ordering::Relaxed)
+    }
+
+    pub fn get_audio_spectrum(&self) -> Spectrum {
         (*self.spectrum.clone().lock().unwrap()).clone()
     }
 
     fn play(&mut self) {
-        let paused = self.paused.clone();
-        let should_stop = self.stop.clone();
+        if let Some(previous) = self.playback.take() {
+            previous.stop(false);
+        }
+        let control = Arc::new(PlaybackControl::default());
+        let thread_control = control.clone();
         let player_state = self.state.clone();
         let playback_progress = self.current_playback_ms_elapsed.clone();
         let spectrum_data = self.spectrum.clone();
+        let spectrum_enabled = self.spectrum_enabled.clone();
+        let analyzed_frames = self.analyzed_frames.clone();
+        let audio_frame = self.audio_frame.clone();
         let event_sender = self.events.clone();
-        let should_notify = self.notify_song_end.clone();
-        let mut decoder = self.get_file_decoder();
-        notify_playback_start(self.song.as_ref().unwrap());
-        thread::spawn(move || {
-            let mut sink = rodio::DeviceSinkBuilder::open_default_sink().unwrap();
-            sink.log_on_drop(false);
-            let player = rodio::Player::connect_new(sink.mixer());
+        // Each playback gets own stream information, so abandoned playback cannot overwrite it
+        self.stream_info = Arc::new(StreamInfo::with_events(self.events.clone()));
+        let stream_info = self.stream_info.clone();
+        let seek_request = self.seek_request.clone();
+        let open_output = self.open_output;
+        *seek_request.lock().unwrap() = None;
+        let start_position_ms = self
+            .start_position
+            .take()
+            .map(|p| p.as_millis() as f64)
+            .unwrap_or(0.0);
+        let song_path = self
+            .song
+            .as_ref()
+            .map(|s| s.file_entry.path.clone())
+            .unwrap();
+        let song = self.song.clone().unwrap();
+        let started_at = unix_timestamp();
+        let thread = thread::spawn(move || {
+            let control = thread_control;
             let mut spectrum_analyzer = SpectrumAnalyzer::new();
-            loop {
-                if should_stop.load(Ordering::Relaxed) {
-                    break;
+            let mut failure = None;
+            let (output, mut decoder) = match open_output().and_then(|output| {
+                Ok((
+                    output,
+                    Self::open_decoder(&song_path, stream_info.clone(), control.clone())?,
+                ))
+            }) {
+                Ok((output, decoder)) => (Some(output), Some(decoder)),
+                Err(e) => {
+                    failure = Some(e);
+                    (None, None)
                 }
-                if paused.load(Ordering::Relaxed) {
-                    *spectrum_data.lock().unwrap() = vec![];
+            };
+            if !control.finish_opening() {
+                debug!("Playback of {song_path} was abandoned while opening");
+                return;
+            }
+            let player = output
+                .as_ref()
+                .map(|o| rodio::Player::connect_new(o.mixer()));
+            if player.is_some() {
+                publish(
+                    &event_sender,
+                    PlaybackEvent::Started(Box::new(song.clone())),
+                );
+            }
+            let mut completed = false;
+            let mut played_frames = 0;
+            let mut reported_second = None;
+            let mut skip_until_ms = start_position_ms;
+            while let (Some(decoder), Some(player)) = (decoder.as_mut(), player.as_ref()) {
+                if control.is_paused() {
+                    *spectrum_data.lock().unwrap() = Spectrum::default();
+                    *audio_frame.lock().unwrap() = AudioFrame::default();
                 }
-                while paused.load(Ordering::Relaxed) {
-                    if should_stop.load(Ordering::Relaxed) {
-                        paused.store(false, Ordering::Relaxed);
-                        break;
+                if !control.wait_while_paused() {
+                    break;
+                }
+                if let Some(position) = seek_request.lock().unwrap().take() {
+                    skip_until_ms = position.as_millis() as f64;
+                    let mut progress = playback_progress.lock().unwrap();
+                    if skip_until_ms < *progress {
+                        // Decoder reads forward only, so file is decoded again from the start
+                        match Self::open_decoder(&song_path, stream_info.clone(), control.clone()) {
+                            Ok(reopened) => {
+                                *decoder = reopened;
+                                *progress = 0.0;
+                            }
+                            Err(e) => error!("Failed to seek in {song_path}: {e}"),
+                        }
                     }
                 }
                 let frame_duration;
+                let skipping = *playback_progress.lock().unwrap() < skip_until_ms;
                 match decoder.next_frame() {
+                    Ok(frame) if !is_playable(&frame) => {
+                        debug!("Skipping invalid frame in {song_path}");
+                        continue;
+                    }
+                    Ok(frame) if skipping => {
+                        *playback_progress.lock().unwrap() +=
+                            frame.get_duration().as_millis() as f64;
+                        continue;
+                    }
                     Ok(frame) => {
-                        {
-                            *spectrum_data.lock().unwrap() = spectrum_analyzer.analyze(&frame.data);
+                        if spectrum_enabled.load(Ordering::Relaxed) {
+                            *spectrum_data.lock().unwrap() = spectrum_analyzer.analyze(
+                                &frame.data,
+                                frame.channels,
+                                frame.sample_rate as u32,
+                            );
+                            *audio_frame.lock().unwrap() = AudioFrame {
+                                samples: frame.data.clone(),
+                                channels: frame.channels,
+                            };
+                            analyzed_frames.fetch_add(1, Ordering::Relaxed);
                         }
-                        frame_duration = frame.get_duration() - Duration::from_millis(1);
+                        frame_duration = frame
+                            .get_duration()
+                            .saturating_sub(Duration::from_millis(1));
                         let source = FrameDecoder::new(frame);
                         player.append(source);
+                        played_frames += 1;
                     }
-                    Err(Error::Eof) => break,
+                    Err(Error::Eof) if played_frames == 0 && skip_until_ms == 0.0 => {
+                        failure = Some(AppError::Decode {
+                            path: song_path.clone(),
+                            reason: String::from("no audio frames found"),
+                        });
+                        break;
+                    }
+                    Err(Error::Eof) => {
+                        completed = true;
+                        break;
+                    }
+                    // Stream reader gives up waiting for data once playback is stopped
+                    Err(_) if control.is_stopped() => break,
                     Err(e) => {
-                        error!("{e:?}");
+                        failure = Some(AppError::Decode {
+                            path: song_path.clone(),
+                            reason: e.to_string(),
+                        });
                         break;
                     }
                 }
-                std::thread::sleep(frame_duration);
-                {
-                    *playback_progress.lock().unwrap() += frame_duration.as_millis() as f64;
+                if !control.sleep(frame_duration) {
+                    break;
+                }
+                let elapsed_ms = {
+                    let mut progress = playback_progress.lock().unwrap();
+                    *progress += frame_duration.as_millis() as f64;
+                    *progress
+                };
+                let second = (elapsed_ms / 1000.0) as u64;
+                if reported_second != Some(second) {
+                    reported_second = Some(second);
+                    publish(
+                        &event_sender,
+                        PlaybackEvent::PositionChanged(Duration::from_secs(second)),
+                    );
                 }
             }
-            should_stop.store(false, Ordering::Relaxed);
-            paused.store(false, Ordering::Relaxed);
+            let listened_ms = *playback_progress.lock().unwrap() - start_position_ms;
             *playback_progress.lock().unwrap() = 0.0;
-            *spectrum_data.lock().unwrap() = vec![];
+            *spectrum_data.lock().unwrap() = Spectrum::default();
+            *audio_frame.lock().unwrap() = AudioFrame::default();
+            stream_info.reset();
             debug!("Playback finished.");
-            if should_notify.load(Ordering::Relaxed) {
-                event_sender.lock().unwrap().send(SongFinished);
+            // Reported also for playback which failed to start, with nothing listened
+            let report = PlaybackReport {
+                song,
+                started_at,
+                listened: Duration::from_millis(listened_ms.max(0.0) as u64),
+                completed,
+            };
+            publish(&event_sender, PlaybackEvent::Stopped(Box::new(report)));
+            match failure {
+                // Queue advances on failure only if song was not stopped in the meantime
+                Some(e) if control.should_notify() => {
+                    publish(&event_sender, PlaybackEvent::Error(e))
+                }
+                Some(e) => error!("{e}"),
+                None if control.should_notify() => {
+                    publish(&event_sender, PlaybackEvent::SongFinished)
+                }
+                None => {}
             }
-            should_notify.store(true, Ordering::Relaxed);
-            let mut state = player_state.lock().unwrap();
-            *state = PlayerState::Stopped;
+            *player_state.lock().unwrap() = PlayerState::Stopped;
         });
+        self.playback = Some(Playback { control, thread });
     }
 
     fn toggle_playback(&mut self) {
@@ -216,13 +514,19 @@ impl Mp3Player {
             }
             PlayerState::Playing => {
                 *state = PlayerState::Paused;
-                self.paused.store(true, Ordering::Relaxed);
+                if let Some(playback) = &self.playback {
+                    playback.control.pause();
+                }
                 debug!("Paused playback");
+                publish(&self.events, PlaybackEvent::Paused);
             }
             PlayerState::Paused => {
                 *state = PlayerState::Playing;
-                self.paused.store(false, Ordering::Relaxed);
+                if let Some(playback) = &self.playback {
+                    playback.control.resume();
+                }
                 debug!("Resumed playback");
+                publish(&self.events, PlaybackEvent::Resumed);
             }
             PlayerState::Stopped => {
                 debug!("Now playing {:?}", self.song.as_ref().unwrap().display());
@@ -233,37 +537,49 @@ impl Mp3Player {
     }
 
     pub fn stop_playback(&mut self, with_notification: bool) {
-        if !self.is_playing() {
-            return;
+        let was_playing = self.is_playing();
+        if let Some(playback) = self.playback.take() {
+            playback.stop(with_notification);
+        }
+        if was_playing {
+            // Abandoned playback thread does not update state itself
+            *self.state.lock().unwrap() = PlayerState::Stopped;
         }
-        self.notify_song_end
-            .store(with_notification, Ordering::Relaxed);
-        self.stop.store(true, Ordering::Relaxed);
-        self.wait_for_stopped_state();
-        if with_notification {
+        if was_playing && with_notification {
             notify_playback_stopped();
         }
     }
 
-    fn get_file_decoder(&self) -> Decoder<File> {
-        let song_path = self
-            .song
-            .as_ref()
-            .map(|s| s.file_entry.path.clone())
-            .unwrap();
-        Decoder::new(File::open(song_path).unwrap())
+    /// Opens default audio output device
+    fn open_device() -> Result<AudioOutput, AppError> {
+        let mut output = rodio::DeviceSinkBuilder::open_default_sink()
+            .map_err(|e| AppError::AudioDevice(e.to_string()))?;
+        output.log_on_drop(false);
+        Ok(AudioOutput::Device(output))
+    }
+
+    fn open_decoder(
+        song_path: &str,
+        stream_info: Arc<StreamInfo>,
+        control: Arc<PlaybackControl>,
+    ) -> Result<Decoder<Box<dyn Read + Send>>, AppError> {
+        let reader: io::Result<Box<dyn Read + Send>> = match is_stream_url(song_path) {
+            true => open_stream(song_path, stream_info, control)
+                .map(|s| Box::new(s) as Box<dyn Read + Send>),
+            false => File::open(song_path).map(|f| Box::new(f) as Box<dyn Read + Send>),
+        };
+        reader.map(Decoder::new).map_err(|source| AppError::Open {
+            path: String::from(song_path),
+            source,
+        })
     }
 
     fn get_song_elapsed_seconds(&self) -> f64 {
         *self.current_playback_ms_elapsed.lock().unwrap() / 1000.0
     }
+}
 
-    fn wait_for_stopped_state(&self) {
-        loop {
-            let state = self.state.lock().unwrap();
-            if *state == PlayerState::Stopped {
-                break;
-            }
-        }
-    }
+/// Returns true if frame contains samples which can be played and analyzed
+fn is_playable(frame: &Frame) -> bool {
+    frame.channels > 0 && frame.sample_rate > 0 && frame.data.len() >= frame.channels
 }
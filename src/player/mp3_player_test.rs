@@ -0,0 +1,185 @@
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs,
+        io::{BufRead, BufReader, Write},
+        net::TcpListener,
+        sync::mpsc::{self, Receiver},
+        thread,
+        time::{Duration, Instant},
+    };
+
+    use tempdir::TempDir;
+
+    use crate::{
+        application::actions::Action,
+        files::FileEntry,
+        input::{events::PlaybackEvent, AppEvent},
+        player::Mp3Player,
+        queue::SongFile,
+    };
+
+    /// Header of MPEG-1 Layer III frame, 128 kbps, 44.1 kHz, stereo
+    const FRAME_HEADER: [u8; 4] = [0xFF, 0xFB, 0x90, 0x00];
+    const FRAME_SIZE: usize = 417;
+
+    /// Writes mp3 file made of silent frames, each about 26 ms long
+    fn silent_mp3(
+        directory: &TempDir,
+        frames: usize,
+    ) -> Result<SongFile, Box<dyn std::error::Error>> {
+        let path = directory.path().join("silence.mp3");
+        let mut frame = vec![0u8; FRAME_SIZE];
+        frame[..4].copy_from_slice(&FRAME_HEADER);
+        fs::write(&path, frame.repeat(frames))?;
+        Ok(SongFile::new(&FileEntry::new(&path))?)
+    }
+
+    /// Collects playback events until song finishes or fails
+    fn playback_events(events: &Receiver<AppEvent>) -> Vec<PlaybackEvent> {
+        let mut received = vec![];
+        while let Ok(event) = events.recv_timeout(Duration::from_secs(5)) {
+            if let AppEvent::Playback(event) = event {
+                let last = matches!(event, PlaybackEvent::SongFinished | PlaybackEvent::Error(_));
+                received.push(event);
+                if last {
+                    break;
+                }
+            }
+        }
+        received
+    }
+
+    #[test]
+    fn should_publish_playback_events_in_order() -> Result<(), Box<dyn std::error::Error>> {
+        // given
+        let tmp_dir = TempDir::new("player")?;
+        let song = silent_mp3(&tmp_dir, 50)?;
+        let (sender, events) = mpsc::channel();
+        let mut player = Mp3Player::detached(sender);
+        player.set_song_file(song);
+
+        // when
+        player.handle_action(Action::TogglePlayback);
+        let received = playback_events(&events);
+
+        // then
+        let names: Vec<&str> = received
+            .iter()
+            .map(|e| match e {
+                PlaybackEvent::Started(_) => "started",
+                PlaybackEvent::PositionChanged(_) => "position",
+                PlaybackEvent::Stopped(_) => "stopped",
+                PlaybackEvent::SongFinished => "finished",
+                _ => "other",
+            })
+            .collect();
+        assert_eq!(names.first(), Some(&"started"));
+        assert_eq!(names[names.len() - 2..], ["stopped", "finished"]);
+        assert!(names[1..names.len() - 2].iter().all(|n| *n == "position"));
+        let positions: Vec<Duration> = received
+            .iter()
+            .filter_map(|e| match e {
+                PlaybackEvent::PositionChanged(position) => Some(*position),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(positions, vec![Duration::ZERO, Duration::from_secs(1)]);
+        match &received[received.len() - 2] {
+            PlaybackEvent::Stopped(report) => {
+                assert!(report.completed);
+                assert!(report.listened >= Duration::from_secs(1));
+            }
+            _ => panic!("Expected stopped event"),
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_report_stopped_playback_of_invalid_file() -> Result<(), Box<dyn std::error::Error>> {
+        // given
+        let tmp_dir = TempDir::new("player")?;
+        let path = tmp_dir.path().join("broken.mp3");
+        fs::write(&path, [0u8; 64])?;
+        let (sender, events) = mpsc::channel();
+        let mut player = Mp3Player::detached(sender);
+        player.set_song_file(SongFile::new(&FileEntry::new(&path))?);
+
+        // when
+        player.handle_action(Action::TogglePlayback);
+        let received = playback_events(&events);
+
+        // then
+        assert!(matches!(
+            received[..],
+            [PlaybackEvent::Started(_), PlaybackEvent::Stopped(ref report), PlaybackEvent::Error(_)]
+                if !report.completed && report.listened.is_zero()
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_stop_without_waiting_for_stream_connection() -> Result<(), Box<dyn std::error::Error>>
+    {
+        // given
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let url = format!("http://{}/radio.mp3", listener.local_addr()?);
+        let (sender, events) = mpsc::channel();
+        let mut player = Mp3Player::detached(sender);
+        player.set_song_file(SongFile::stream("Silent radio", &url));
+        player.handle_action(Action::TogglePlayback);
+
+        // when
+        let start = Instant::now();
+        player.stop_playback(false);
+
+        // then
+        assert!(start.elapsed() < Duration::from_secs(1));
+        assert!(!player.is_playing());
+        assert!(events.try_recv().is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_stop_while_stalled_stream_is_buffering() -> Result<(), Box<dyn std::error::Error>> {
+        // given
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let url = format!("http://{}/radio.mp3", listener.local_addr()?);
+        let server = thread::spawn(move || -> std::io::Result<_> {
+            let (mut stream, _) = listener.accept()?;
+            let mut reader = BufReader::new(stream.try_clone()?);
+            let mut line = String::new();
+            while reader.read_line(&mut line)? > 2 {
+                line.clear();
+            }
+            stream.write_all(b"HTTP/1.0 200 OK\r\nContent-Type: audio/mpeg\r\n\r\n")?;
+            stream.write_all(&FRAME_HEADER)?;
+            // Connection stays open without sending more data
+            Ok(stream)
+        });
+        let (sender, events) = mpsc::channel();
+        let mut player = Mp3Player::detached(sender);
+        player.set_song_file(SongFile::stream("Stalled radio", &url));
+        player.handle_action(Action::TogglePlayback);
+        let _connection = server.join().unwrap()?;
+        let started = events.recv_timeout(Duration::from_secs(5))?;
+        assert!(matches!(
+            started,
+            AppEvent::Playback(PlaybackEvent::Started(_))
+        ));
+        thread::sleep(Duration::from_millis(200));
+
+        // when
+        let start = Instant::now();
+        player.stop_playback(false);
+
+        // then
+        assert!(start.elapsed() < Duration::from_secs(1));
+        assert!(!player.is_playing());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,146 @@
+use std::{
+    sync::{Condvar, Mutex, MutexGuard},
+    time::Duration,
+};
+
+/// Command given to playback thread
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Command {
+    Play,
+    Pause,
+    /// Playback should end, publishing end of song if `notify` is set
+    Stop {
+        notify: bool,
+    },
+}
+
+/// Stage of playback thread, deciding if player waits for it to finish
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Phase {
+    /// Output and song are being opened, which may take long for network streams
+    Opening,
+    Playing,
+    /// Player stopped waiting for thread, which must not touch shared player state anymore
+    Abandoned,
+}
+
+/// Commands shared between player and its playback thread.
+/// Thread blocks on condition variable while paused, so it does not use CPU,
+/// and is woken up as soon as playback is resumed or stopped.
+/// Stop is final, new playback uses new instance.
+pub struct PlaybackControl {
+    command: Mutex<Command>,
+    changed: Condvar,
+    phase: Mutex<Phase>,
+}
+
+impl Default for PlaybackControl {
+    fn default() -> Self {
+        PlaybackControl {
+            command: Mutex::new(Command::Play),
+            changed: Condvar::new(),
+            phase: Mutex::new(Phase::Opening),
+        }
+    }
+}
+
+impl PlaybackControl {
+    /// Called by playback thread once song is opened.
+    /// Returns false if player abandoned playback in the meantime, so thread should quit.
+    pub fn finish_opening(&self) -> bool {
+        let mut phase = self.phase.lock().unwrap();
+        match *phase {
+            Phase::Opening => {
+                *phase = Phase::Playing;
+                true
+            }
+            Phase::Playing | Phase::Abandoned => false,
+        }
+    }
+
+    /// Abandons playback still opening song, so player does not wait for it.
+    /// Returns false if song is already opened, thread then finishes quickly after stop.
+    pub fn abandon_opening(&self) -> bool {
+        let mut phase = self.phase.lock().unwrap();
+        match *phase {
+            Phase::Opening => {
+                *phase = Phase::Abandoned;
+                true
+            }
+            Phase::Playing | Phase::Abandoned => false,
+        }
+    }
+
+    /// Pauses playback, returns true if it was playing
+    pub fn pause(&self) -> bool {
+        self.change(Command::Play, Command::Pause)
+    }
+
+    /// Resumes paused playback, returns true if it was paused
+    pub fn resume(&self) -> bool {
+        self.change(Command::Pause, Command::Play)
+    }
+
+    /// Requests end of playback, waking up waiting thread
+    pub fn stop(&self, notify: bool) {
+        *self.lock() = Command::Stop { notify };
+        self.changed.notify_all();
+    }
+
+    pub fn command(&self) -> Command {
+        *self.lock()
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.command() == Command::Pause
+    }
+
+    pub fn is_stopped(&self) -> bool {
+        matches!(self.command(), Command::Stop { .. })
+    }
+
+    /// Returns true if end of song should be published, which is not the case
+    /// when playback was stopped by user or by selecting other song
+    pub fn should_notify(&self) -> bool {
+        match self.command() {
+            Command::Stop { notify } => notify,
+            Command::Play | Command::Pause => true,
+        }
+    }
+
+    /// Blocks while playback is paused.
+    /// Returns false if playback should stop.
+    pub fn wait_while_paused(&self) -> bool {
+        let command = self
+            .changed
+            .wait_while(self.lock(), |c| *c == Command::Pause)
+            .unwrap();
+        *command == Command::Play
+    }
+
+    /// Sleeps for given time, or shorter if stop is requested in the meantime.
+    /// Returns false if playback should stop.
+    pub fn sleep(&self, duration: Duration) -> bool {
+        let (command, _) = self
+            .changed
+            .wait_timeout_while(self.lock(), duration, |c| {
+                !matches!(c, Command::Stop { .. })
+            })
+            .unwrap();
+        !matches!(*command, Command::Stop { .. })
+    }
+
+    fn change(&self, from: Command, to: Command) -> bool {
+        let mut command = self.lock();
+        if *command != from {
+            return false;
+        }
+        *command = to;
+        self.changed.notify_all();
+        true
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Command> {
+        self.command.lock().unwrap()
+    }
+}
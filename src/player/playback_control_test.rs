@@ -0,0 +1,109 @@
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::Arc,
+        thread,
+        time::{Duration, Instant},
+    };
+
+    use crate::player::playback_control::{Command, PlaybackControl};
+
+    #[test]
+    fn should_block_paused_thread_until_resumed() {
+        // given
+        let control = Arc::new(PlaybackControl::default());
+        assert!(control.pause());
+        let waiting = control.clone();
+        let thread = thread::spawn(move || waiting.wait_while_paused());
+
+        // when
+        thread::sleep(Duration::from_millis(50));
+        let finished_while_paused = thread.is_finished();
+        assert!(control.resume());
+
+        // then
+        assert!(!finished_while_paused);
+        assert!(thread.join().unwrap());
+        assert_eq!(control.command(), Command::Play);
+    }
+
+    #[test]
+    fn should_wake_paused_thread_on_stop() {
+        // given
+        let control = Arc::new(PlaybackControl::default());
+        control.pause();
+        let waiting = control.clone();
+        let thread = thread::spawn(move || waiting.wait_while_paused());
+
+        // when
+        control.stop(false);
+
+        // then
+        assert!(!thread.join().unwrap());
+        assert!(!control.should_notify());
+    }
+
+    #[test]
+    fn should_keep_stop_final() {
+        // given
+        let control = PlaybackControl::default();
+        control.stop(true);
+
+        // when
+        let paused = control.pause();
+        let resumed = control.resume();
+
+        // then
+        assert!(!paused);
+        assert!(!resumed);
+        assert_eq!(control.command(), Command::Stop { notify: true });
+        assert!(control.should_notify());
+    }
+
+    #[test]
+    fn should_abandon_playback_only_while_opening() {
+        // given
+        let opening = PlaybackControl::default();
+        let playing = PlaybackControl::default();
+        assert!(playing.finish_opening());
+
+        // when
+        let opening_abandoned = opening.abandon_opening();
+        let playing_abandoned = playing.abandon_opening();
+
+        // then
+        assert!(opening_abandoned);
+        assert!(!opening.finish_opening());
+        assert!(!playing_abandoned);
+    }
+
+    #[test]
+    fn should_interrupt_sleep_on_stop() {
+        // given
+        let control = Arc::new(PlaybackControl::default());
+        let sleeping = control.clone();
+        let started = Instant::now();
+        let thread = thread::spawn(move || sleeping.sleep(Duration::from_secs(10)));
+
+        // when
+        thread::sleep(Duration::from_millis(20));
+        control.stop(false);
+
+        // then
+        assert!(!thread.join().unwrap());
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn should_sleep_whole_duration_while_playing() {
+        // given
+        let control = PlaybackControl::default();
+
+        // when
+        let continues = control.sleep(Duration::from_millis(10));
+
+        // then
+        assert!(continues);
+        assert!(!control.is_paused());
+    }
+}
@@ -1,4 +1,41 @@
 use rustfft::{num_complex::Complex, FftPlanner};
+use std::f32::consts::PI;
+
+/// Lowest magnitude reported by analyzer, in dBFS
+pub const MIN_MAGNITUDE_DB: f32 = -100.0;
+
+/// Magnitudes of frequency bins of single frame
+#[derive(Clone, Default)]
+pub struct Spectrum {
+    /// Magnitude of each bin in dBFS, from 0 Hz up to half of sample rate, for all channels mixed
+    pub magnitudes: Vec<f32>,
+    /// Magnitudes of each channel separately, empty for mono frames
+    pub channel_magnitudes: Vec<Vec<f32>>,
+    pub sample_rate: u32,
+}
+
+impl Spectrum {
+    /// Returns spectrum of single channel, or of mixed channels if frame has no such channel
+    pub fn channel(&self, channel: usize) -> Spectrum {
+        Spectrum {
+            magnitudes: self
+                .channel_magnitudes
+                .get(channel)
+                .unwrap_or(&self.magnitudes)
+                .clone(),
+            channel_magnitudes: vec![],
+            sample_rate: self.sample_rate,
+        }
+    }
+
+    /// Returns center frequency of bin with given index
+    pub fn bin_frequency(&self, bin: usize) -> f64 {
+        match self.magnitudes.len() {
+            0 => 0.0,
+            len => bin as f64 * self.sample_rate as f64 / (2 * len) as f64,
+        }
+    }
+}
 
 pub struct SpectrumAnalyzer {
     planner: FftPlanner<f32>,
@@ -11,17 +48,64 @@ impl SpectrumAnalyzer {
         }
     }
 
-    pub fn analyze(&mut self, data: &[i16]) -> Vec<f32> {
-        let mut buffer = SpectrumAnalyzer::prepare_data(data);
-        let fft = self.planner.plan_fft_forward(buffer.len());
+    /// Computes spectrum of interleaved samples, both for mixed and separate channels
+    pub fn analyze(&mut self, data: &[i16], channels: usize, sample_rate: u32) -> Spectrum {
+        let channels = channels.max(1);
+        let frame_length = data.len() / channels;
+        if frame_length == 0 {
+            return Spectrum::default();
+        }
+        let mixed: Vec<f32> = data
+            .chunks_exact(channels)
+            .map(|chunk| chunk.iter().map(|s| *s as f32).sum::<f32>() / channels as f32)
+            .collect();
+        let channel_magnitudes = match channels {
+            1 => vec![],
+            _ => (0..channels)
+                .map(|channel| {
+                    let samples: Vec<f32> = data
+                        .iter()
+                        .skip(channel)
+                        .step_by(channels)
+                        .take(frame_length)
+                        .map(|s| *s as f32)
+                        .collect();
+                    self.magnitudes(&samples)
+                })
+                .collect(),
+        };
+        Spectrum {
+            magnitudes: self.magnitudes(&mixed),
+            channel_magnitudes,
+            sample_rate,
+        }
+    }
+
+    /// Returns magnitudes in dBFS of first half of FFT bins of samples
+    fn magnitudes(&mut self, samples: &[f32]) -> Vec<f32> {
+        let mut buffer = SpectrumAnalyzer::prepare_data(samples);
+        let len = buffer.len();
+        let fft = self.planner.plan_fft_forward(len);
         fft.process(&mut buffer);
-        buffer.into_iter().map(|c| c.re).collect::<Vec<f32>>()
+        // Hann window halves amplitude, so full scale sine reaches 0 dB after dividing by len / 4
+        let scale = len as f32 / 4.0;
+        buffer
+            .iter()
+            .take(len / 2)
+            .map(|c| (20.0 * (c.norm() / scale).log10()).max(MIN_MAGNITUDE_DB))
+            .collect()
     }
 
-    fn prepare_data(data: &[i16]) -> Vec<Complex<f32>> {
-        // TODO maybe passing channel count instead of hardcoded 2 would be better?
-        data.chunks(2)
-            .map(|chunk| Complex::new((chunk[0] as f32 + chunk[1] as f32) / 2.0, 0.0))
+    /// Scales samples to range [-1..1] and applies Hann window
+    fn prepare_data(samples: &[f32]) -> Vec<Complex<f32>> {
+        let last = samples.len().saturating_sub(1).max(1) as f32;
+        samples
+            .iter()
+            .enumerate()
+            .map(|(i, s)| {
+                let window = 0.5 - 0.5 * (2.0 * PI * i as f32 / last).cos();
+                Complex::new(s / i16::MAX as f32 * window, 0.0)
+            })
             .collect()
     }
 }
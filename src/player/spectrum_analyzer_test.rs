@@ -0,0 +1,92 @@
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::PI;
+
+    use crate::player::spectrum_analyzer::{SpectrumAnalyzer, MIN_MAGNITUDE_DB};
+
+    #[test]
+    fn should_find_full_scale_sine_at_its_frequency() {
+        // given
+        let sample_rate = 8192;
+        let frequency = 1024.0;
+        let samples: Vec<i16> = (0..1024)
+            .flat_map(|i| {
+                let value = (2.0 * PI * frequency * i as f64 / sample_rate as f64).sin();
+                let sample = (value * i16::MAX as f64) as i16;
+                [sample, sample]
+            })
+            .collect();
+        let mut analyzer = SpectrumAnalyzer::new();
+
+        // when
+        let result = analyzer.analyze(&samples, 2, sample_rate);
+
+        // then
+        assert_eq!(result.magnitudes.len(), 512);
+        let loudest = (0..result.magnitudes.len())
+            .max_by(|a, b| result.magnitudes[*a].total_cmp(&result.magnitudes[*b]))
+            .unwrap();
+        assert_eq!(result.bin_frequency(loudest), frequency);
+        assert!(result.magnitudes[loudest].abs() < 0.5);
+        assert!(result.magnitudes[loudest + 10] < -60.0);
+    }
+
+    #[test]
+    fn should_report_silence_as_lowest_magnitude() {
+        // given
+        let samples = vec![0; 2304];
+        let mut analyzer = SpectrumAnalyzer::new();
+
+        // when
+        let result = analyzer.analyze(&samples, 2, 44100);
+
+        // then
+        assert!(result.magnitudes.iter().all(|m| *m == MIN_MAGNITUDE_DB));
+    }
+
+    #[test]
+    fn should_analyze_channels_separately() {
+        // given
+        let sample_rate = 8192;
+        let samples: Vec<i16> = (0..1024)
+            .flat_map(|i| {
+                let value = (2.0 * PI * 512.0 * i as f64 / sample_rate as f64).sin();
+                [(value * i16::MAX as f64) as i16, 0]
+            })
+            .collect();
+        let mut analyzer = SpectrumAnalyzer::new();
+
+        // when
+        let result = analyzer.analyze(&samples, 2, sample_rate);
+
+        // then
+        let bin = 64;
+        assert_eq!(result.bin_frequency(bin), 512.0);
+        assert!(result.channel(0).magnitudes[bin].abs() < 0.5);
+        assert_eq!(result.channel(1).magnitudes[bin], MIN_MAGNITUDE_DB);
+        // mixed channels keep half of amplitude
+        assert!((result.magnitudes[bin] + 6.02).abs() < 0.5);
+    }
+
+    #[test]
+    fn should_analyze_mono_and_multichannel_frames() {
+        // given
+        let mono = vec![1000; 1152];
+        let surround = vec![1000; 1152 * 6 + 3];
+        let mut analyzer = SpectrumAnalyzer::new();
+
+        // when
+        let mono_result = analyzer.analyze(&mono, 1, 44100);
+        let surround_result = analyzer.analyze(&surround, 6, 44100);
+
+        // then
+        assert_eq!(mono_result.magnitudes.len(), 576);
+        assert!(mono_result.channel_magnitudes.is_empty());
+        assert_eq!(mono_result.channel(1).magnitudes, mono_result.magnitudes);
+        assert_eq!(surround_result.channel_magnitudes.len(), 6);
+        assert!(surround_result
+            .channel_magnitudes
+            .iter()
+            .all(|m| m.len() == 576));
+    }
+}
@@ -0,0 +1,361 @@
+use crate::{
+    input::{
+        events::{publish, PlaybackEvent},
+        AppEvent,
+    },
+    player::playback_control::PlaybackControl,
+};
+use log::{debug, error};
+use std::{
+    collections::VecDeque,
+    io::{self, BufRead, BufReader, Read, Write},
+    net::{TcpStream, ToSocketAddrs},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::Sender,
+        Arc, Condvar, Mutex,
+    },
+    thread,
+    time::Duration,
+};
+
+/// Amount of data to collect before handing stream over to decoder
+const PREBUFFER_SIZE: usize = 32 * 1024;
+/// Upper limit of data kept in memory ahead of decoder
+const MAX_BUFFER_SIZE: usize = 512 * 1024;
+const READ_CHUNK_SIZE: usize = 4096;
+/// Time allowed for connecting to stream server
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+/// Time without any data after which stream connection is considered lost
+const READ_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Returns true if provided path points to network stream instead of local file
+pub fn is_stream_url(path: &str) -> bool {
+    path.starts_with("http://") || path.starts_with("https://")
+}
+
+/// Information about currently played network stream,
+/// shared between player and thread downloading stream data
+#[derive(Default)]
+pub struct StreamInfo {
+    /// Station name announced by server in `icy-name` header
+    pub station_name: Mutex<Option<String>>,
+    /// Title announced in ICY metadata, usually "Artist - Title"
+    pub title: Mutex<Option<String>>,
+    /// Indicates that decoder is waiting for data from network
+    pub buffering: AtomicBool,
+    /// Number of bytes downloaded, but not yet consumed by decoder
+    pub buffered_bytes: Mutex<usize>,
+    /// Channel receiving [BufferUnderrun](PlaybackEvent::BufferUnderrun) events
+    events: Option<Sender<AppEvent>>,
+}
+
+impl StreamInfo {
+    pub fn with_events(events: Sender<AppEvent>) -> Self {
+        StreamInfo {
+            events: Some(events),
+            ..StreamInfo::default()
+        }
+    }
+
+    pub fn reset(&self) {
+        *self.station_name.lock().unwrap() = None;
+        *self.title.lock().unwrap() = None;
+        *self.buffered_bytes.lock().unwrap() = 0;
+        self.buffering.store(false, Ordering::Relaxed);
+    }
+}
+
+/// Response of stream server, with headers needed for playback
+struct StreamResponse {
+    metadata_interval: Option<usize>,
+    station_name: Option<String>,
+    body: Box<dyn Read + Send>,
+}
+
+/// Opens HTTP stream requesting ICY metadata.
+/// Returned reader yields pure mp3 data with metadata blocks stripped,
+/// while metadata is published in provided [StreamInfo].
+/// Reading fails once playback is stopped, so stalled stream does not keep playback thread waiting.
+pub fn open_stream(
+    url: &str,
+    info: Arc<StreamInfo>,
+    control: Arc<PlaybackControl>,
+) -> io::Result<StreamReader> {
+    let agent = ureq::AgentBuilder::new()
+        .timeout_connect(CONNECT_TIMEOUT)
+        .timeout_read(READ_TIMEOUT)
+        .build();
+    let response = match agent.get(url).set("Icy-MetaData", "1").call() {
+        Ok(response) => StreamResponse {
+            metadata_interval: response
+                .header("icy-metaint")
+                .and_then(parse_metadata_interval),
+            station_name: response.header("icy-name").map(String::from),
+            body: response.into_reader(),
+        },
+        // Shoutcast v1 servers answer with `ICY 200 OK` status line, which is not valid HTTP
+        Err(ureq::Error::Transport(e))
+            if e.kind() == ureq::ErrorKind::BadStatus && url.starts_with("http://") =>
+        {
+            debug!("Server of {url} does not speak HTTP, retrying as Shoutcast v1");
+            open_icy_stream(url)?
+        }
+        Err(e) => return Err(io::Error::other(e.to_string())),
+    };
+    *info.station_name.lock().unwrap() = response.station_name;
+    let metadata_interval = response.metadata_interval;
+    debug!("Opened stream {url}, metadata interval: {metadata_interval:?}");
+    let reader = IcyReader::new(response.body, metadata_interval, info.clone());
+
+    Ok(StreamReader::new(reader, info, control))
+}
+
+/// Requests stream from Shoutcast v1 server over plain TCP, accepting `ICY 200 OK` status line
+fn open_icy_stream(url: &str) -> io::Result<StreamResponse> {
+    let address = url.trim_start_matches("http://");
+    let (host, path) = match address.find('/') {
+        Some(index) => address.split_at(index),
+        None => (address, "/"),
+    };
+    let authority = match host.contains(':') {
+        true => String::from(host),
+        false => format!("{host}:80"),
+    };
+    let address = authority
+        .to_socket_addrs()?
+        .next()
+        .ok_or_else(|| io::Error::other(format!("Cannot resolve {authority}")))?;
+    let mut connection = TcpStream::connect_timeout(&address, CONNECT_TIMEOUT)?;
+    connection.set_read_timeout(Some(READ_TIMEOUT))?;
+    write!(
+        connection,
+        "GET {path} HTTP/1.0\r\nHost: {host}\r\nIcy-MetaData: 1\r\nUser-Agent: penny\r\n\r\n"
+    )?;
+    let mut reader = BufReader::new(connection);
+    let mut status = String::new();
+    reader.read_line(&mut status)?;
+    let accepted = status
+        .split_whitespace()
+        .nth(1)
+        .is_some_and(|code| code == "200");
+    if !accepted {
+        return Err(io::Error::other(format!(
+            "Unexpected response: {}",
+            status.trim()
+        )));
+    }
+    let mut metadata_interval = None;
+    let mut station_name = None;
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 || line.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            match name.trim().to_ascii_lowercase().as_str() {
+                "icy-metaint" => metadata_interval = parse_metadata_interval(value),
+                "icy-name" => station_name = Some(String::from(value.trim())),
+                _ => {}
+            }
+        }
+    }
+
+    Ok(StreamResponse {
+        metadata_interval,
+        station_name,
+        body: Box::new(reader),
+    })
+}
+
+/// Reader removing ICY metadata blocks from stream data.
+/// Metadata block is sent by server after every `metadata_interval` bytes of audio
+/// and is prefixed with single byte containing its length divided by 16.
+pub struct IcyReader<R: Read> {
+    inner: R,
+    metadata_interval: Option<usize>,
+    bytes_until_metadata: usize,
+    info: Arc<StreamInfo>,
+}
+
+impl<R: Read> IcyReader<R> {
+    pub fn new(inner: R, metadata_interval: Option<usize>, info: Arc<StreamInfo>) -> Self {
+        IcyReader {
+            inner,
+            metadata_interval,
+            bytes_until_metadata: metadata_interval.unwrap_or(0),
+            info,
+        }
+    }
+
+    /// Reads metadata block, returns false if stream ended instead
+    fn read_metadata(&mut self) -> io::Result<bool> {
+        let mut length = [0u8; 1];
+        if self.inner.read(&mut length)? == 0 {
+            return Ok(false);
+        }
+        let mut metadata = vec![0u8; length[0] as usize * 16];
+        match self.inner.read_exact(&mut metadata) {
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(false),
+            result => result?,
+        }
+        let metadata = String::from_utf8_lossy(&metadata);
+        if let Some(title) = parse_stream_title(&metadata) {
+            debug!("Stream title changed to {title}");
+            *self.info.title.lock().unwrap() = Some(title);
+        }
+        Ok(true)
+    }
+}
+
+impl<R: Read> Read for IcyReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let interval = match self.metadata_interval {
+            Some(interval) => interval,
+            None => return self.inner.read(buf),
+        };
+        if self.bytes_until_metadata == 0 {
+            // Stream may end right at metadata boundary
+            if !self.read_metadata()? {
+                return Ok(0);
+            }
+            self.bytes_until_metadata = interval;
+        }
+        let max_length = buf.len().min(self.bytes_until_metadata);
+        let read = self.inner.read(&mut buf[..max_length])?;
+        self.bytes_until_metadata -= read;
+        Ok(read)
+    }
+}
+
+/// Parses `icy-metaint` header. Interval of 0 is treated as missing header,
+/// as reader would otherwise return empty reads, which decoder takes for end of stream.
+pub fn parse_metadata_interval(value: &str) -> Option<usize> {
+    value.trim().parse::<usize>().ok().filter(|n| *n > 0)
+}
+
+/// Extracts `StreamTitle` value from ICY metadata block,
+/// e.g. `StreamTitle='Artist - Title';StreamUrl='';`
+pub fn parse_stream_title(metadata: &str) -> Option<String> {
+    let start = metadata.find("StreamTitle='")? + "StreamTitle='".len();
+    let rest = &metadata[start..];
+    let end = rest.find("';").or_else(|| rest.rfind('\''))?;
+    let title = rest[..end].trim();
+    match title.is_empty() {
+        true => None,
+        false => Some(String::from(title)),
+    }
+}
+
+struct SharedBuffer {
+    data: Mutex<VecDeque<u8>>,
+    changed: Condvar,
+    finished: AtomicBool,
+}
+
+/// Reader buffering network data in background thread,
+/// so short network hiccups do not interrupt playback.
+pub struct StreamReader {
+    buffer: Arc<SharedBuffer>,
+    info: Arc<StreamInfo>,
+    control: Arc<PlaybackControl>,
+    prebuffered: bool,
+}
+
+impl StreamReader {
+    pub fn new<R: Read + Send + 'static>(
+        mut source: R,
+        info: Arc<StreamInfo>,
+        control: Arc<PlaybackControl>,
+    ) -> Self {
+        let buffer = Arc::new(SharedBuffer {
+            data: Mutex::new(VecDeque::new()),
+            changed: Condvar::new(),
+            finished: AtomicBool::new(false),
+        });
+        let loop_buffer = buffer.clone();
+        let loop_info = info.clone();
+        thread::spawn(move || {
+            let mut chunk = [0u8; READ_CHUNK_SIZE];
+            loop {
+                // Stop downloading if reader was dropped
+                if Arc::strong_count(&loop_buffer) == 1 {
+                    break;
+                }
+                match source.read(&mut chunk) {
+                    Ok(0) => break,
+                    Ok(read) => {
+                        let mut data = loop_buffer.data.lock().unwrap();
+                        while data.len() > MAX_BUFFER_SIZE && Arc::strong_count(&loop_buffer) > 1 {
+                            data = loop_buffer
+                                .changed
+                                .wait_timeout(data, Duration::from_millis(100))
+                                .unwrap()
+                                .0;
+                        }
+                        data.extend(&chunk[..read]);
+                        *loop_info.buffered_bytes.lock().unwrap() = data.len();
+                        loop_buffer.changed.notify_all();
+                    }
+                    Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                    Err(e) => {
+                        error!("Stream connection failed: {e}");
+                        break;
+                    }
+                }
+            }
+            loop_buffer.finished.store(true, Ordering::Relaxed);
+            loop_buffer.changed.notify_all();
+        });
+
+        StreamReader {
+            buffer,
+            info,
+            control,
+            prebuffered: false,
+        }
+    }
+}
+
+impl Read for StreamReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let mut data = self.buffer.data.lock().unwrap();
+        // Buffer ran dry, collect some data again before continuing playback
+        if data.is_empty() {
+            if self.prebuffered && !self.buffer.finished.load(Ordering::Relaxed) {
+                debug!("Stream buffer ran out of data");
+                if let Some(events) = &self.info.events {
+                    publish(events, PlaybackEvent::BufferUnderrun);
+                }
+            }
+            self.prebuffered = false;
+        }
+        let required = match self.prebuffered {
+            true => 1,
+            false => PREBUFFER_SIZE,
+        };
+        while data.len() < required && !self.buffer.finished.load(Ordering::Relaxed) {
+            self.info.buffering.store(true, Ordering::Relaxed);
+            data = self
+                .buffer
+                .changed
+                .wait_timeout(data, Duration::from_millis(100))
+                .unwrap()
+                .0;
+            if self.control.is_stopped() {
+                self.info.buffering.store(false, Ordering::Relaxed);
+                return Err(io::Error::other("Playback stopped while buffering"));
+            }
+        }
+        self.info.buffering.store(false, Ordering::Relaxed);
+        self.prebuffered = true;
+
+        let length = buf.len().min(data.len());
+        for (target, byte) in buf.iter_mut().zip(data.drain(..length)) {
+            *target = byte;
+        }
+        *self.info.buffered_bytes.lock().unwrap() = data.len();
+        self.buffer.changed.notify_all();
+        Ok(length)
+    }
+}
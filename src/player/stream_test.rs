@@ -0,0 +1,172 @@
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{BufRead, BufReader, Read, Write},
+        net::TcpListener,
+        sync::Arc,
+        thread,
+    };
+
+    use crate::player::{
+        playback_control::PlaybackControl,
+        stream::{open_stream, parse_metadata_interval, parse_stream_title, IcyReader, StreamInfo},
+    };
+
+    #[test]
+    fn should_parse_stream_title() {
+        // given
+        let metadata = "StreamTitle='Rockers - Awesome medley';StreamUrl='';\0\0\0";
+
+        // when
+        let result = parse_stream_title(metadata);
+
+        // then
+        assert_eq!(result, Some(String::from("Rockers - Awesome medley")));
+    }
+
+    #[test]
+    fn should_ignore_zero_metadata_interval() {
+        // when
+        let result = ["16000", " 8192\r", "0", "none"].map(parse_metadata_interval);
+
+        // then
+        assert_eq!(result, [Some(16000), Some(8192), None, None]);
+    }
+
+    #[test]
+    fn should_strip_metadata_from_stream_data() -> Result<(), std::io::Error> {
+        // given
+        let info = Arc::new(StreamInfo::default());
+        let data = icy_stream(4, &[b"abcd", b"efgh"], "StreamTitle='Song';");
+        let mut reader = IcyReader::new(data.as_slice(), Some(4), info.clone());
+
+        // when
+        let mut result = vec![];
+        reader.read_to_end(&mut result)?;
+
+        // then
+        assert_eq!(result, b"abcdefgh");
+        assert_eq!(*info.title.lock().unwrap(), Some(String::from("Song")));
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_end_stream_at_metadata_boundary() -> Result<(), std::io::Error> {
+        // given
+        let info = Arc::new(StreamInfo::default());
+        let data = b"abcd";
+        let mut reader = IcyReader::new(data.as_slice(), Some(4), info);
+
+        // when
+        let mut result = vec![];
+        reader.read_to_end(&mut result)?;
+
+        // then
+        assert_eq!(result, b"abcd");
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_read_stream_from_http_server() -> Result<(), std::io::Error> {
+        // given
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let url = format!("http://{}/radio.mp3", listener.local_addr()?);
+        let body = icy_stream(8, &[b"mp3data1", b"mp3data2"], "StreamTitle='Live show';");
+        thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = BufReader::new(stream.try_clone().unwrap());
+            let mut line = String::new();
+            while request.read_line(&mut line).unwrap() > 2 {
+                line.clear();
+            }
+            write!(
+                stream,
+                "HTTP/1.0 200 OK\r\nContent-Type: audio/mpeg\r\nicy-name: Test FM\r\nicy-metaint: 8\r\n\r\n"
+            )
+            .unwrap();
+            stream.write_all(&body).unwrap();
+        });
+        let info = Arc::new(StreamInfo::default());
+
+        // when
+        let mut reader = open_stream(&url, info.clone(), Arc::new(PlaybackControl::default()))?;
+        let mut result = vec![];
+        reader.read_to_end(&mut result)?;
+
+        // then
+        assert_eq!(result, b"mp3data1mp3data2");
+        assert_eq!(
+            *info.station_name.lock().unwrap(),
+            Some(String::from("Test FM"))
+        );
+        assert_eq!(*info.title.lock().unwrap(), Some(String::from("Live show")));
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_read_stream_from_shoutcast_v1_server() -> Result<(), std::io::Error> {
+        // given
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let url = format!("http://{}/", listener.local_addr()?);
+        let body = icy_stream(8, &[b"mp3data1", b"mp3data2"], "StreamTitle='Old school';");
+        thread::spawn(move || {
+            // First connection is refused by HTTP client, second one comes from fallback
+            for _ in 0..2 {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut request = BufReader::new(stream.try_clone().unwrap());
+                let mut line = String::new();
+                while request.read_line(&mut line).unwrap() > 2 {
+                    line.clear();
+                }
+                write!(
+                    stream,
+                    "ICY 200 OK\r\nicy-name: Vintage FM\r\nicy-metaint: 8\r\n\r\n"
+                )
+                .unwrap();
+                let _ = stream.write_all(&body);
+            }
+        });
+        let info = Arc::new(StreamInfo::default());
+
+        // when
+        let mut reader = open_stream(&url, info.clone(), Arc::new(PlaybackControl::default()))?;
+        let mut result = vec![];
+        reader.read_to_end(&mut result)?;
+
+        // then
+        assert_eq!(result, b"mp3data1mp3data2");
+        assert_eq!(
+            *info.station_name.lock().unwrap(),
+            Some(String::from("Vintage FM"))
+        );
+        assert_eq!(
+            *info.title.lock().unwrap(),
+            Some(String::from("Old school"))
+        );
+
+        Ok(())
+    }
+
+    /// Builds stream data with metadata block inserted after first chunk,
+    /// and empty metadata block after following ones
+    fn icy_stream(interval: usize, chunks: &[&[u8]], metadata: &str) -> Vec<u8> {
+        let mut data = vec![];
+        for (index, chunk) in chunks.iter().enumerate() {
+            assert_eq!(chunk.len(), interval);
+            data.extend_from_slice(chunk);
+            if index == 0 {
+                let blocks = metadata.len().div_ceil(16);
+                let mut block = metadata.as_bytes().to_vec();
+                block.resize(blocks * 16, 0);
+                data.push(blocks as u8);
+                data.extend(block);
+            } else {
+                data.push(0);
+            }
+        }
+        data
+    }
+}
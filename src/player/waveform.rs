@@ -0,0 +1,224 @@
+use log::{debug, warn};
+use minimp3::{Decoder, Error};
+use minimp3_fixed as minimp3;
+use std::{
+    fs::{self, File},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    thread,
+};
+
+/// Number of buckets track is divided into when computing its waveform
+pub const WAVEFORM_BUCKETS: usize = 1024;
+
+/// Loudness overview of whole track, with levels normalized to [0..1]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Waveform {
+    /// Highest absolute sample of each bucket
+    pub peaks: Vec<f32>,
+    /// Root mean square of samples of each bucket
+    pub rms: Vec<f32>,
+}
+
+impl Waveform {
+    /// Parses waveform cache file with `<peak>\t<rms>` line for each bucket
+    pub fn parse(content: &str) -> Result<Self, String> {
+        let mut waveform = Waveform::default();
+        for (number, line) in content.lines().enumerate() {
+            let parse_level = |value: Option<&str>| {
+                value
+                    .and_then(|v| v.trim().parse::<f32>().ok())
+                    .ok_or_else(|| format!("Invalid waveform level in line {}", number + 1))
+            };
+            let mut values = line.split('\t');
+            waveform.peaks.push(parse_level(values.next())?);
+            waveform.rms.push(parse_level(values.next())?);
+        }
+        Ok(waveform)
+    }
+
+    /// Returns content of waveform cache file
+    pub fn serialize(&self) -> String {
+        self.peaks
+            .iter()
+            .zip(&self.rms)
+            .map(|(peak, rms)| format!("{peak:.4}\t{rms:.4}\n"))
+            .collect()
+    }
+
+    /// Returns `(peak, rms)` levels resampled to given number of columns
+    pub fn columns(&self, width: usize) -> Vec<(f32, f32)> {
+        let buckets = self.peaks.len();
+        if buckets == 0 {
+            return vec![(0.0, 0.0); width];
+        }
+        (0..width)
+            .map(|column| {
+                let start = column * buckets / width;
+                let end = ((column + 1) * buckets / width).clamp(start + 1, buckets);
+                let peak = self.peaks[start..end].iter().fold(0.0f32, |a, b| a.max(*b));
+                let mean_square =
+                    self.rms[start..end].iter().map(|r| r * r).sum::<f32>() / (end - start) as f32;
+                (peak, mean_square.sqrt())
+            })
+            .collect()
+    }
+}
+
+/// Collects levels of decoded frames, which are grouped into buckets once whole track is read
+#[derive(Default)]
+pub struct WaveformBuilder {
+    /// Peak, sum of squares and number of samples of every frame
+    frames: Vec<(f32, f64, usize)>,
+}
+
+impl WaveformBuilder {
+    /// Adds interleaved samples of single frame, channels are treated together
+    pub fn add_frame(&mut self, samples: &[i16]) {
+        let (peak, sum_squares) = samples.iter().fold((0.0f32, 0.0f64), |(peak, sum), s| {
+            let sample = *s as f32 / i16::MAX as f32;
+            (peak.max(sample.abs()), sum + (sample * sample) as f64)
+        });
+        self.frames.push((peak, sum_squares, samples.len()));
+    }
+
+    /// Returns waveform with given number of buckets, or fewer when track has fewer frames
+    pub fn build(&self, buckets: usize) -> Waveform {
+        let buckets = buckets.min(self.frames.len());
+        let mut waveform = Waveform::default();
+        for bucket in 0..buckets {
+            let start = bucket * self.frames.len() / buckets;
+            let end = (bucket + 1) * self.frames.len() / buckets;
+            let frames = &self.frames[start..end];
+            let peak = frames.iter().fold(0.0f32, |a, f| a.max(f.0));
+            let sum_squares: f64 = frames.iter().map(|f| f.1).sum();
+            let samples: usize = frames.iter().map(|f| f.2).sum();
+            waveform.peaks.push(peak.min(1.0));
+            waveform
+                .rms
+                .push((sum_squares / samples.max(1) as f64).sqrt().min(1.0) as f32);
+        }
+        waveform
+    }
+}
+
+/// Decodes whole mp3 file and computes its waveform
+pub fn compute_waveform(path: &Path) -> Result<Waveform, String> {
+    let file = File::open(path).map_err(|e| e.to_string())?;
+    let mut decoder = Decoder::new(file);
+    let mut builder = WaveformBuilder::default();
+    loop {
+        match decoder.next_frame() {
+            Ok(frame) => builder.add_frame(&frame.data),
+            Err(Error::Eof) => break,
+            Err(Error::SkippedData) => continue,
+            Err(e) => return Err(format!("{e:?}")),
+        }
+    }
+    Ok(builder.build(WAVEFORM_BUCKETS))
+}
+
+/// Computes waveforms of selected tracks in background and keeps them in cache directory.
+/// Cached waveform is recomputed when track file was modified after it was written.
+pub struct WaveformCache {
+    directory: PathBuf,
+    /// Path of track which waveform was requested last, shared with loading threads,
+    /// so result of outdated request does not replace waveform of current one
+    requested: Arc<Mutex<Option<String>>>,
+    /// Last computed waveform together with path of its track
+    waveform: Arc<Mutex<Option<(String, Waveform)>>>,
+    /// Number of requested waveforms being read or computed
+    loading: Arc<AtomicUsize>,
+}
+
+impl WaveformCache {
+    pub fn new(directory: &Path) -> Self {
+        WaveformCache {
+            directory: directory.to_path_buf(),
+            requested: Arc::new(Mutex::new(None)),
+            waveform: Arc::new(Mutex::new(None)),
+            loading: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    /// Starts loading waveform of given track, unless it was already requested
+    pub fn request(&mut self, path: &str) {
+        {
+            let mut requested = self.requested.lock().unwrap();
+            if requested.as_deref() == Some(path) {
+                return;
+            }
+            *requested = Some(String::from(path));
+        }
+        let cache_file = self.cache_file(path);
+        let requested = self.requested.clone();
+        let waveform = self.waveform.clone();
+        let loading = self.loading.clone();
+        loading.fetch_add(1, Ordering::Relaxed);
+        let path = String::from(path);
+        thread::spawn(move || {
+            let result = match read_cached(&cache_file, Path::new(&path)) {
+                Some(cached) => Ok(cached),
+                None => compute_waveform(Path::new(&path)).inspect(|computed| {
+                    debug!("Computed waveform of {path}");
+                    if let Err(e) = write_cached(&cache_file, computed) {
+                        warn!("Failed to cache waveform of {path}: {e}");
+                    }
+                }),
+            };
+            match result {
+                Ok(computed) => {
+                    // Lock is held while storing, so newer request cannot slip in between
+                    let requested = requested.lock().unwrap();
+                    if requested.as_deref() == Some(path.as_str()) {
+                        *waveform.lock().unwrap() = Some((path, computed));
+                    } else {
+                        debug!("Dropping waveform of {path}, other track was requested meanwhile");
+                    }
+                }
+                Err(e) => warn!("Failed to compute waveform of {path}: {e}"),
+            }
+            loading.fetch_sub(1, Ordering::Relaxed);
+        });
+    }
+
+    /// Returns true while requested waveform is read or computed
+    pub fn is_loading(&self) -> bool {
+        self.loading.load(Ordering::Relaxed) > 0
+    }
+
+    /// Returns `(peak, rms)` levels of given track resampled to given width, if already loaded
+    pub fn columns(&self, path: &str, width: usize) -> Option<Vec<(f32, f32)>> {
+        match self.waveform.lock().unwrap().as_ref() {
+            Some((loaded, waveform)) if loaded == path => Some(waveform.columns(width)),
+            _ => None,
+        }
+    }
+
+    /// Returns cache file of track, named after md5 of its path
+    fn cache_file(&self, path: &str) -> PathBuf {
+        self.directory.join(format!("{:x}", md5::compute(path)))
+    }
+}
+
+fn read_cached(cache_file: &Path, track: &Path) -> Option<Waveform> {
+    let cached = fs::metadata(cache_file).and_then(|m| m.modified()).ok()?;
+    let modified = fs::metadata(track).and_then(|m| m.modified()).ok()?;
+    if modified > cached {
+        return None;
+    }
+    let content = fs::read_to_string(cache_file).ok()?;
+    Waveform::parse(&content)
+        .inspect_err(|e| warn!("Ignoring invalid waveform {}: {e}", cache_file.display()))
+        .ok()
+}
+
+fn write_cached(cache_file: &Path, waveform: &Waveform) -> std::io::Result<()> {
+    if let Some(directory) = cache_file.parent() {
+        fs::create_dir_all(directory)?;
+    }
+    fs::write(cache_file, waveform.serialize())
+}
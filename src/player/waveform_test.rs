@@ -0,0 +1,132 @@
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs, thread,
+        time::{Duration, Instant},
+    };
+
+    use tempdir::TempDir;
+
+    use crate::player::waveform::{Waveform, WaveformBuilder, WaveformCache};
+
+    #[test]
+    fn should_group_frames_into_buckets() {
+        // given
+        let mut builder = WaveformBuilder::default();
+        builder.add_frame(&[i16::MAX, -i16::MAX]);
+        builder.add_frame(&[0, 0]);
+        builder.add_frame(&[i16::MAX / 2, i16::MAX / 2]);
+        builder.add_frame(&[-i16::MAX / 2, -i16::MAX / 2]);
+
+        // when
+        let result = builder.build(2);
+
+        // then
+        assert_eq!(result.peaks.len(), 2);
+        assert!((result.peaks[0] - 1.0).abs() < 0.001);
+        assert!((result.peaks[1] - 0.5).abs() < 0.001);
+        assert!((result.rms[0] - 0.5f32.sqrt()).abs() < 0.001);
+        assert!((result.rms[1] - 0.5).abs() < 0.001);
+    }
+
+    #[test]
+    fn should_not_create_more_buckets_than_frames() {
+        // given
+        let mut builder = WaveformBuilder::default();
+        builder.add_frame(&[100, 100]);
+
+        // when
+        let result = builder.build(1024);
+
+        // then
+        assert_eq!(result.peaks.len(), 1);
+    }
+
+    #[test]
+    fn should_read_serialized_waveform() {
+        // given
+        let waveform = Waveform {
+            peaks: vec![0.5, 1.0],
+            rms: vec![0.25, 0.75],
+        };
+
+        // when
+        let result = Waveform::parse(&waveform.serialize());
+
+        // then
+        assert_eq!(result, Ok(waveform));
+    }
+
+    #[test]
+    fn should_reject_invalid_cache_line() {
+        // when
+        let result = Waveform::parse("0.5\t0.2\n0.3\n");
+
+        // then
+        assert_eq!(
+            result,
+            Err(String::from("Invalid waveform level in line 2"))
+        );
+    }
+
+    #[test]
+    fn should_resample_buckets_to_columns() {
+        // given
+        let waveform = Waveform {
+            peaks: vec![0.2, 0.8, 0.4, 0.6],
+            rms: vec![0.0, 0.4, 0.3, 0.4],
+        };
+
+        // when
+        let narrow = waveform.columns(2);
+        let wide = waveform.columns(8);
+
+        // then
+        assert_eq!(narrow[0].0, 0.8);
+        assert!((narrow[0].1 - 0.08f32.sqrt()).abs() < 0.001);
+        assert_eq!(narrow[1].0, 0.6);
+        assert!((narrow[1].1 - 0.125f32.sqrt()).abs() < 0.001);
+        assert_eq!(wide.len(), 8);
+        assert_eq!(wide[2], (0.8, 0.4));
+        assert_eq!(wide[3], (0.8, 0.4));
+    }
+
+    #[test]
+    fn should_keep_waveform_of_last_requested_track() -> Result<(), Box<dyn std::error::Error>> {
+        // given
+        let tmp_dir = TempDir::new("waveform")?;
+        let cache_dir = tmp_dir.path().join("cache");
+        fs::create_dir_all(&cache_dir)?;
+        // Long track of silent MPEG frames takes a while to decode
+        let mut frame = vec![0u8; 417];
+        frame[..4].copy_from_slice(&[0xFF, 0xFB, 0x90, 0x00]);
+        let slow = tmp_dir.path().join("slow.mp3");
+        fs::write(&slow, frame.repeat(3000))?;
+        let cached = tmp_dir.path().join("cached.mp3");
+        fs::write(&cached, &frame)?;
+        let cached_path = cached.to_string_lossy().to_string();
+        let waveform = Waveform {
+            peaks: vec![0.5],
+            rms: vec![0.25],
+        };
+        fs::write(
+            cache_dir.join(format!("{:x}", md5::compute(&cached_path))),
+            waveform.serialize(),
+        )?;
+        let mut cache = WaveformCache::new(&cache_dir);
+
+        // when
+        cache.request(&slow.to_string_lossy());
+        cache.request(&cached_path);
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while cache.is_loading() && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(10));
+        }
+
+        // then
+        assert_eq!(cache.columns(&cached_path, 1), Some(vec![(0.5, 0.25)]));
+        assert_eq!(cache.columns(&slow.to_string_lossy(), 1), None);
+
+        Ok(())
+    }
+}
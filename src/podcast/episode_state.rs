@@ -0,0 +1,104 @@
+use log::warn;
+use std::{
+    collections::HashMap,
+    fs, io,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+/// Listening progress of single episode
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EpisodeState {
+    /// Position to resume playback from
+    pub position: Duration,
+    pub played: bool,
+}
+
+/// Listening progress of podcast episodes, identified by path of episode audio file.
+/// Stored in file with one episode per line in format `<path>\t<position seconds>\t<played>`
+pub struct EpisodeStates {
+    file: PathBuf,
+    states: HashMap<String, EpisodeState>,
+}
+
+impl EpisodeStates {
+    /// Loads states from file, starting with empty state if file does not exist
+    pub fn load(file: &Path) -> Self {
+        let states = fs::read_to_string(file)
+            .map(|content| content.lines().filter_map(Self::parse_line).collect())
+            .unwrap_or_default();
+        EpisodeStates {
+            file: file.to_path_buf(),
+            states,
+        }
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        if let Some(parent) = self.file.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut lines: Vec<String> = self
+            .states
+            .iter()
+            .map(|(path, state)| {
+                format!(
+                    "{path}\t{}\t{}",
+                    state.position.as_secs(),
+                    state.played as u8
+                )
+            })
+            .collect();
+        lines.sort();
+        fs::write(&self.file, lines.join("\n"))
+    }
+
+    pub fn get(&self, path: &str) -> Option<&EpisodeState> {
+        self.states.get(path)
+    }
+
+    /// Returns true if given path belongs to known episode
+    pub fn contains(&self, path: &str) -> bool {
+        self.states.contains_key(path)
+    }
+
+    /// Registers episode so its progress is tracked
+    pub fn track(&mut self, path: &str) {
+        self.states.entry(String::from(path)).or_default();
+    }
+
+    pub fn update_position(&mut self, path: &str, position: Duration) {
+        if let Some(state) = self.states.get_mut(path) {
+            state.position = position;
+        }
+    }
+
+    /// Marks episode as fully played, so next playback starts from beginning
+    pub fn mark_played(&mut self, path: &str) {
+        let state = self.states.entry(String::from(path)).or_default();
+        state.played = true;
+        state.position = Duration::ZERO;
+    }
+
+    pub fn toggle_played(&mut self, path: &str) {
+        let state = self.states.entry(String::from(path)).or_default();
+        state.played = !state.played;
+    }
+
+    fn parse_line(line: &str) -> Option<(String, EpisodeState)> {
+        let mut columns = line.split('\t');
+        let (path, position, played) = (columns.next()?, columns.next()?, columns.next()?);
+        match (position.parse::<u64>(), played) {
+            (Ok(position), "0" | "1") => Some((
+                String::from(path),
+                EpisodeState {
+                    position: Duration::from_secs(position),
+                    played: played == "1",
+                },
+            )),
+            _ => {
+                warn!("Skipping invalid podcast episode state: {line}");
+                None
+            }
+        }
+    }
+}
@@ -0,0 +1,53 @@
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use tempdir::TempDir;
+
+    use crate::podcast::episode_state::{EpisodeState, EpisodeStates};
+
+    #[test]
+    fn should_persist_episode_progress() -> Result<(), std::io::Error> {
+        // given
+        let tmp_dir = TempDir::new("episodes")?;
+        let state_file = tmp_dir.path().join("podcasts").join("episodes");
+        let mut states = EpisodeStates::load(&state_file);
+        states.track("/cache/ep1.mp3");
+        states.update_position("/cache/ep1.mp3", Duration::from_secs(125));
+        states.mark_played("/cache/ep2.mp3");
+        states.save()?;
+
+        // when
+        let result = EpisodeStates::load(&state_file);
+
+        // then
+        assert_eq!(
+            result.get("/cache/ep1.mp3"),
+            Some(&EpisodeState {
+                position: Duration::from_secs(125),
+                played: false
+            })
+        );
+        assert_eq!(
+            result.get("/cache/ep2.mp3"),
+            Some(&EpisodeState {
+                position: Duration::ZERO,
+                played: true
+            })
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_not_track_position_of_unknown_file() {
+        // given
+        let mut states = EpisodeStates::load(std::path::Path::new("/nonexistent/episodes"));
+
+        // when
+        states.update_position("/music/song.mp3", Duration::from_secs(10));
+
+        // then
+        assert!(!states.contains("/music/song.mp3"));
+    }
+}
@@ -0,0 +1,162 @@
+use std::{fs, io};
+
+/// Podcast feed read from RSS or Atom document
+#[derive(Debug, Clone, PartialEq)]
+pub struct Feed {
+    pub title: String,
+    pub episodes: Vec<Episode>,
+}
+
+/// Single podcast episode with audio enclosure
+#[derive(Debug, Clone, PartialEq)]
+pub struct Episode {
+    pub title: String,
+    /// Location of audio file, either URL or local path
+    pub enclosure: String,
+}
+
+/// Reads feed from URL or local file
+pub fn fetch_feed(location: &str) -> io::Result<Feed> {
+    let content = match location.starts_with("http://") || location.starts_with("https://") {
+        true => ureq::get(location)
+            .call()
+            .map_err(|e| io::Error::other(e.to_string()))?
+            .into_string()?,
+        false => fs::read_to_string(location.trim_start_matches("file://"))?,
+    };
+    parse_feed(&content).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("{location} is not valid RSS or Atom feed"),
+        )
+    })
+}
+
+/// Parses RSS 2.0 or Atom document.
+/// Only entries containing audio enclosure are included in returned feed.
+pub fn parse_feed(xml: &str) -> Option<Feed> {
+    if xml.contains("<rss") {
+        return Some(parse_entries(xml, "item", |item| {
+            let enclosure = find_tags(item, "enclosure")
+                .into_iter()
+                .find_map(|tag| attribute(tag, "url"))?;
+            Some(Episode {
+                title: element_text(item, "title").unwrap_or_else(|| enclosure.clone()),
+                enclosure,
+            })
+        }));
+    }
+    if xml.contains("<feed") {
+        return Some(parse_entries(xml, "entry", |entry| {
+            let enclosure = find_tags(entry, "link")
+                .into_iter()
+                .filter(|tag| attribute(tag, "rel").as_deref() == Some("enclosure"))
+                .find_map(|tag| attribute(tag, "href"))?;
+            Some(Episode {
+                title: element_text(entry, "title").unwrap_or_else(|| enclosure.clone()),
+                enclosure,
+            })
+        }));
+    }
+    None
+}
+
+fn parse_entries(xml: &str, entry_name: &str, parse: impl Fn(&str) -> Option<Episode>) -> Feed {
+    let mut fragments = split_elements(xml, entry_name);
+    let header = fragments.remove(0);
+    Feed {
+        title: element_text(header, "title").unwrap_or_else(|| String::from("Untitled")),
+        episodes: fragments.into_iter().filter_map(parse).collect(),
+    }
+}
+
+/// Splits document on every opening of given element.
+/// First returned fragment contains everything before first element.
+fn split_elements<'a>(xml: &'a str, name: &str) -> Vec<&'a str> {
+    let mut fragments = vec![];
+    let mut rest = xml;
+    while let Some(position) = find_opening(rest, name) {
+        fragments.push(&rest[..position]);
+        rest = &rest[position + 1..];
+    }
+    fragments.push(rest);
+    fragments
+}
+
+/// Finds position of opening tag, skipping tags which only start with given name
+fn find_opening(xml: &str, name: &str) -> Option<usize> {
+    let pattern = format!("<{name}");
+    let mut offset = 0;
+    while let Some(position) = xml[offset..].find(&pattern) {
+        let start = offset + position;
+        let next = xml[start + pattern.len()..].chars().next();
+        if matches!(
+            next,
+            Some('>') | Some('/') | Some(' ') | Some('\t') | Some('\n') | Some('\r')
+        ) {
+            return Some(start);
+        }
+        offset = start + pattern.len();
+    }
+    None
+}
+
+/// Returns contents of all tags with given name, e.g. `enclosure url="..." length="1"`
+fn find_tags<'a>(xml: &'a str, name: &str) -> Vec<&'a str> {
+    let mut tags = vec![];
+    let mut rest = xml;
+    while let Some(start) = find_opening(rest, name) {
+        let tag = &rest[start + 1..];
+        match tag.find('>') {
+            Some(end) => {
+                tags.push(tag[..end].trim_end_matches('/'));
+                rest = &tag[end..];
+            }
+            None => break,
+        }
+    }
+    tags
+}
+
+fn attribute(tag: &str, name: &str) -> Option<String> {
+    let mut rest = tag;
+    loop {
+        let position = rest.find(&format!("{name}="))?;
+        let preceding = rest[..position].chars().last();
+        let value = &rest[position + name.len() + 1..];
+        rest = value;
+        if !matches!(preceding, Some(' ') | Some('\t') | Some('\n') | Some('\r')) {
+            continue;
+        }
+        let quote = value.chars().next()?;
+        if quote != '"' && quote != '\'' {
+            continue;
+        }
+        let end = value[1..].find(quote)?;
+        return Some(decode_entities(&value[1..end + 1]));
+    }
+}
+
+fn element_text(xml: &str, name: &str) -> Option<String> {
+    let start = find_opening(xml, name)?;
+    let content_start = start + xml[start..].find('>')? + 1;
+    let content_end = content_start + xml[content_start..].find(&format!("</{name}>"))?;
+    let content = xml[content_start..content_end].trim();
+    let text = match content.strip_prefix("<![CDATA[") {
+        Some(cdata) => String::from(cdata.trim_end_matches("]]>")),
+        None => decode_entities(content),
+    };
+    match text.trim().is_empty() {
+        true => None,
+        false => Some(String::from(text.trim())),
+    }
+}
+
+fn decode_entities(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
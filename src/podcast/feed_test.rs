@@ -0,0 +1,83 @@
+#[cfg(test)]
+mod tests {
+    use crate::podcast::feed::{parse_feed, Episode};
+
+    #[test]
+    fn should_parse_rss_feed() {
+        // given
+        let xml = r#"<?xml version="1.0"?>
+            <rss version="2.0"><channel>
+              <title>Rust &amp; Friends</title>
+              <item>
+                <title><![CDATA[Episode 2: Traits]]></title>
+                <pubDate>Tue, 02 Jan 2024 10:00:00 GMT</pubDate>
+                <enclosure length="1024" type="audio/mpeg" url="http://localhost/ep2.mp3"/>
+              </item>
+              <item>
+                <title>Announcement without audio</title>
+              </item>
+              <item>
+                <title>Episode 1</title>
+                <enclosure url='/home/user/ep1.mp3' type="audio/mpeg"></enclosure>
+              </item>
+            </channel></rss>"#;
+
+        // when
+        let result = parse_feed(xml).unwrap();
+
+        // then
+        assert_eq!(result.title, "Rust & Friends");
+        assert_eq!(
+            result.episodes,
+            vec![
+                Episode {
+                    title: String::from("Episode 2: Traits"),
+                    enclosure: String::from("http://localhost/ep2.mp3"),
+                },
+                Episode {
+                    title: String::from("Episode 1"),
+                    enclosure: String::from("/home/user/ep1.mp3"),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn should_parse_atom_feed() {
+        // given
+        let xml = r#"<feed xmlns="http://www.w3.org/2005/Atom">
+              <title type="text">Atom cast</title>
+              <entry>
+                <title>First</title>
+                <link rel="alternate" href="http://localhost/first.html"/>
+                <link rel="enclosure" type="audio/mpeg" href="http://localhost/first.mp3"/>
+                <updated>2024-01-01T10:00:00Z</updated>
+              </entry>
+            </feed>"#;
+
+        // when
+        let result = parse_feed(xml).unwrap();
+
+        // then
+        assert_eq!(result.title, "Atom cast");
+        assert_eq!(
+            result.episodes,
+            vec![Episode {
+                title: String::from("First"),
+                enclosure: String::from("http://localhost/first.mp3"),
+            }]
+        );
+    }
+
+    #[test]
+    fn should_reject_non_feed_document() {
+        // given
+        let xml = "<html><title>Not a feed</title></html>";
+
+        // when
+        let result = parse_feed(xml);
+
+        // then
+        assert!(result.is_none());
+    }
+}
@@ -0,0 +1,14 @@
+//! Module handling podcast subscriptions, episode downloads
+//! and listening progress of episodes
+pub mod episode_state;
+pub mod feed;
+pub mod podcast_view;
+
+pub use podcast_view::PodcastView;
+
+#[cfg(test)]
+mod episode_state_test;
+#[cfg(test)]
+mod feed_test;
+#[cfg(test)]
+mod podcast_view_test;
@@ -0,0 +1,294 @@
+use log::{error, info, trace, warn};
+use ratatui::widgets::ListState;
+use std::{
+    fs::{self, File},
+    io,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    thread,
+};
+
+use crate::{application::actions::Action, files::FileEntry};
+
+use super::{
+    episode_state::{EpisodeState, EpisodeStates},
+    feed::{fetch_feed, Feed},
+};
+
+/// Episode listed in podcast view
+pub struct PodcastEpisode {
+    pub feed_title: String,
+    pub title: String,
+    /// Location of audio in feed
+    pub enclosure: String,
+    /// Location of audio file on disk, after download for remote episodes
+    pub local_path: PathBuf,
+}
+
+impl PodcastEpisode {
+    fn is_remote(&self) -> bool {
+        self.enclosure.starts_with("http://") || self.enclosure.starts_with("https://")
+    }
+
+    pub fn is_downloaded(&self) -> bool {
+        self.local_path.is_file()
+    }
+
+    pub fn path(&self) -> String {
+        self.local_path.to_string_lossy().to_string()
+    }
+}
+
+/// View listing episodes of subscribed podcasts.
+/// Subscriptions are read from file containing one feed URL or local feed path per line.
+pub struct PodcastView {
+    pub state: ListState,
+    pub episodes: Vec<PodcastEpisode>,
+    pub episode_states: EpisodeStates,
+    subscriptions_file: PathBuf,
+    cache_directory: PathBuf,
+    /// Feeds fetched by background refresh, waiting to be picked up by view
+    refreshed_feeds: Arc<Mutex<Option<Vec<Feed>>>>,
+    refreshing: Arc<AtomicBool>,
+    /// Episodes being downloaded, identified by local path
+    downloading: Arc<Mutex<Vec<String>>>,
+    /// Downloaded episodes waiting to be added to queue
+    finished_downloads: Arc<Mutex<Vec<String>>>,
+}
+
+impl PodcastView {
+    pub fn new(subscriptions_file: &Path, cache_directory: &Path, state_file: &Path) -> Self {
+        PodcastView {
+            state: ListState::default(),
+            episodes: vec![],
+            episode_states: EpisodeStates::load(state_file),
+            subscriptions_file: subscriptions_file.to_path_buf(),
+            cache_directory: cache_directory.to_path_buf(),
+            refreshed_feeds: Arc::new(Mutex::new(None)),
+            refreshing: Arc::new(AtomicBool::new(false)),
+            downloading: Arc::new(Mutex::new(vec![])),
+            finished_downloads: Arc::new(Mutex::new(vec![])),
+        }
+    }
+
+    pub fn do_action(&mut self, action: Action) {
+        match action {
+            Action::ViewerUp => self.previous(),
+            Action::ViewerDown => self.next(),
+            Action::RefreshPodcasts => self.refresh(),
+            Action::TogglePlayedEpisode => self.toggle_played(),
+            _ => error!("Unsupported podcast view action: {action:?}"),
+        }
+    }
+
+    pub fn is_refreshing(&self) -> bool {
+        self.refreshing.load(Ordering::Relaxed)
+    }
+
+    /// Fetches all subscribed feeds in background thread
+    pub fn refresh(&mut self) {
+        if self.refreshing.swap(true, Ordering::Relaxed) {
+            return;
+        }
+        let subscriptions = match read_subscriptions(&self.subscriptions_file) {
+            Ok(subscriptions) => subscriptions,
+            Err(e) => {
+                error!(
+                    "Failed to read podcast subscriptions {}: {e}",
+                    self.subscriptions_file.to_string_lossy()
+                );
+                self.refreshing.store(false, Ordering::Relaxed);
+                return;
+            }
+        };
+        let refreshed_feeds = self.refreshed_feeds.clone();
+        let refreshing = self.refreshing.clone();
+        thread::spawn(move || {
+            let feeds = subscriptions
+                .iter()
+                .filter_map(|location| match fetch_feed(location) {
+                    Ok(feed) => Some(feed),
+                    Err(e) => {
+                        warn!("Failed to refresh podcast {location}: {e}");
+                        None
+                    }
+                })
+                .collect();
+            *refreshed_feeds.lock().unwrap() = Some(feeds);
+            refreshing.store(false, Ordering::Relaxed);
+        });
+    }
+
+    /// Returns true while feeds are refreshed or episodes downloaded
+    pub fn is_busy(&self) -> bool {
+        self.refreshing.load(Ordering::Relaxed) || !self.downloading.lock().unwrap().is_empty()
+    }
+
+    /// Picks up results of background refresh and downloads.
+    /// Returns downloaded episodes which should be added to queue.
+    pub fn update(&mut self) -> Vec<FileEntry> {
+        let refreshed_feeds = self.refreshed_feeds.lock().unwrap().take();
+        if let Some(feeds) = refreshed_feeds {
+            self.set_feeds(feeds);
+        }
+        self.finished_downloads
+            .lock()
+            .unwrap()
+            .drain(..)
+            .map(|path| FileEntry::new(Path::new(&path)))
+            .collect()
+    }
+
+    /// Returns selected episode if it is available locally,
+    /// otherwise starts its download and returns `None`
+    pub fn select(&mut self) -> Option<FileEntry> {
+        let episode = self.state.selected().map(|i| &self.episodes[i])?;
+        let path = episode.path();
+        self.episode_states.track(&path);
+        if !episode.is_remote() || episode.is_downloaded() {
+            return Some(FileEntry::new(&episode.local_path));
+        }
+        if self.downloading.lock().unwrap().contains(&path) {
+            return None;
+        }
+        info!("Downloading episode {}", episode.title);
+        self.downloading.lock().unwrap().push(path.clone());
+        let url = episode.enclosure.clone();
+        let downloading = self.downloading.clone();
+        let finished_downloads = self.finished_downloads.clone();
+        thread::spawn(move || {
+            match download(&url, Path::new(&path)) {
+                Ok(()) => finished_downloads.lock().unwrap().push(path.clone()),
+                Err(e) => error!("Failed to download episode {url}: {e}"),
+            }
+            downloading.lock().unwrap().retain(|p| *p != path);
+        });
+        None
+    }
+
+    pub fn is_downloading(&self, episode: &PodcastEpisode) -> bool {
+        self.downloading.lock().unwrap().contains(&episode.path())
+    }
+
+    pub fn episode_state(&self, episode: &PodcastEpisode) -> Option<&EpisodeState> {
+        self.episode_states.get(&episode.path())
+    }
+
+    fn set_feeds(&mut self, feeds: Vec<Feed>) {
+        let selected_path = self
+            .state
+            .selected()
+            .and_then(|i| self.episodes.get(i))
+            .map(|e| e.path());
+        self.episodes = feeds
+            .into_iter()
+            .flat_map(|feed| {
+                let feed_directory = self.cache_directory.join(sanitize(&feed.title));
+                feed.episodes
+                    .into_iter()
+                    .map(move |episode| PodcastEpisode {
+                        local_path: match episode.enclosure.starts_with("http://")
+                            || episode.enclosure.starts_with("https://")
+                        {
+                            true => feed_directory
+                                .join(episode_file_name(&episode.title, &episode.enclosure)),
+                            false => PathBuf::from(episode.enclosure.trim_start_matches("file://")),
+                        },
+                        feed_title: feed.title.clone(),
+                        title: episode.title,
+                        enclosure: episode.enclosure,
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        info!(
+            "Refreshed podcasts, {} episodes available",
+            self.episodes.len()
+        );
+        let index = selected_path
+            .and_then(|path| self.episodes.iter().position(|e| e.path() == path))
+            .or(if self.episodes.is_empty() {
+                None
+            } else {
+                Some(0)
+            });
+        self.state.select(index);
+        trace!("Podcast view selection restored to {index:?}");
+    }
+
+    fn toggle_played(&mut self) {
+        if let Some(episode) = self.state.selected().map(|i| &self.episodes[i]) {
+            self.episode_states.toggle_played(&episode.path());
+        }
+    }
+
+    fn next(&mut self) {
+        let i = match self.state.selected() {
+            Some(i) if i + 1 < self.episodes.len() => i + 1,
+            _ => 0,
+        };
+        if !self.episodes.is_empty() {
+            self.state.select(Some(i));
+        }
+    }
+
+    fn previous(&mut self) {
+        let i = match self.state.selected() {
+            Some(0) | None => self.episodes.len().saturating_sub(1),
+            Some(i) => i - 1,
+        };
+        if !self.episodes.is_empty() {
+            self.state.select(Some(i));
+        }
+    }
+}
+
+/// Reads feed locations, one per line, skipping empty lines and `#` comments
+pub fn read_subscriptions(path: &Path) -> io::Result<Vec<String>> {
+    Ok(fs::read_to_string(path)?
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(String::from)
+        .collect())
+}
+
+fn download(url: &str, target: &Path) -> io::Result<()> {
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let partial_target = target.with_extension("part");
+    let response = ureq::get(url)
+        .call()
+        .map_err(|e| io::Error::other(e.to_string()))?;
+    io::copy(
+        &mut response.into_reader(),
+        &mut File::create(&partial_target)?,
+    )?;
+    fs::rename(partial_target, target)
+}
+
+/// Returns name of downloaded episode file.
+/// Hash of enclosure URL keeps episodes with same or similar titles apart.
+fn episode_file_name(title: &str, enclosure: &str) -> String {
+    // FNV-1a, stable across runs and Rust versions unlike std hashers
+    let hash = enclosure.bytes().fold(0xcbf29ce484222325u64, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    });
+    format!("{}-{:08x}.mp3", sanitize(title), hash as u32)
+}
+
+/// Makes name safe to use as file name
+fn sanitize(name: &str) -> String {
+    let sanitized: String = name
+        .chars()
+        .map(|c| match c.is_alphanumeric() || c == '-' || c == '_' {
+            true => c,
+            false => '_',
+        })
+        .collect();
+    sanitized.trim_matches('_').chars().take(100).collect()
+}
@@ -0,0 +1,167 @@
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs,
+        io::{BufRead, BufReader, Write},
+        net::TcpListener,
+        path::PathBuf,
+        thread,
+        time::{Duration, Instant},
+    };
+
+    use tempdir::TempDir;
+
+    use crate::podcast::PodcastView;
+
+    #[test]
+    fn should_refresh_local_feed_and_select_local_episode() -> Result<(), std::io::Error> {
+        // given
+        let tmp_dir = TempDir::new("podcasts")?;
+        let episode_path = tmp_dir.path().join("episode.mp3");
+        fs::File::create(&episode_path)?;
+        let feed_path = tmp_dir.path().join("feed.xml");
+        fs::write(
+            &feed_path,
+            format!(
+                r#"<rss><channel><title>Local cast</title>
+                <item><title>Local episode</title><enclosure url="{}"/></item>
+                </channel></rss>"#,
+                episode_path.to_string_lossy()
+            ),
+        )?;
+        let subscriptions = tmp_dir.path().join("podcasts");
+        fs::write(&subscriptions, feed_path.to_string_lossy().as_bytes())?;
+        let mut view = PodcastView::new(
+            &subscriptions,
+            &tmp_dir.path().join("cache"),
+            &tmp_dir.path().join("episodes"),
+        );
+
+        // when
+        view.refresh();
+        wait_until(|| {
+            view.update();
+            !view.episodes.is_empty()
+        });
+        let result = view.select();
+
+        // then
+        assert_eq!(view.episodes[0].feed_title, "Local cast");
+        assert_eq!(view.episodes[0].title, "Local episode");
+        assert_eq!(result.unwrap().path, episode_path.to_string_lossy());
+        assert!(view
+            .episode_states
+            .contains(&episode_path.to_string_lossy()));
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_download_remote_episode_from_http_server() -> Result<(), std::io::Error> {
+        // given
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let address = listener.local_addr()?;
+        thread::spawn(move || {
+            for stream in listener.incoming().take(2) {
+                let mut stream = stream.unwrap();
+                let mut request = BufReader::new(stream.try_clone().unwrap());
+                let mut request_line = String::new();
+                request.read_line(&mut request_line).unwrap();
+                let mut line = String::new();
+                while request.read_line(&mut line).unwrap() > 2 {
+                    line.clear();
+                }
+                let body = match request_line.contains("feed.xml") {
+                    true => format!(
+                        r#"<rss><channel><title>Remote cast</title>
+                        <item><title>Episode 1</title><enclosure url="http://{address}/ep1.mp3"/></item>
+                        </channel></rss>"#
+                    ),
+                    false => String::from("mp3 content"),
+                };
+                write!(
+                    stream,
+                    "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                )
+                .unwrap();
+            }
+        });
+        let tmp_dir = TempDir::new("podcasts")?;
+        let subscriptions = tmp_dir.path().join("podcasts");
+        fs::write(&subscriptions, format!("http://{address}/feed.xml\n"))?;
+        let cache = tmp_dir.path().join("cache");
+        let mut view = PodcastView::new(&subscriptions, &cache, &tmp_dir.path().join("episodes"));
+        view.refresh();
+        wait_until(|| {
+            view.update();
+            !view.episodes.is_empty()
+        });
+
+        // when
+        let selected = view.select();
+        let mut downloaded = vec![];
+        wait_until(|| {
+            downloaded.extend(view.update());
+            !downloaded.is_empty()
+        });
+
+        // then
+        assert!(selected.is_none());
+        let expected_path = PathBuf::from(&downloaded[0].path);
+        assert_eq!(
+            expected_path.parent(),
+            Some(cache.join("Remote_cast").as_path())
+        );
+        let file_name = expected_path.file_name().unwrap().to_string_lossy();
+        assert!(file_name.starts_with("Episode_1-") && file_name.ends_with(".mp3"));
+        assert_eq!(fs::read_to_string(expected_path)?, "mp3 content");
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_store_episodes_with_same_title_in_separate_files() -> Result<(), std::io::Error> {
+        // given
+        let tmp_dir = TempDir::new("podcasts")?;
+        let feed_path = tmp_dir.path().join("feed.xml");
+        fs::write(
+            &feed_path,
+            r#"<rss><channel><title>Cast</title>
+            <item><title>Trailer</title><enclosure url="http://example.com/1.mp3"/></item>
+            <item><title>Trailer</title><enclosure url="http://example.com/2.mp3"/></item>
+            </channel></rss>"#,
+        )?;
+        let subscriptions = tmp_dir.path().join("podcasts");
+        fs::write(&subscriptions, feed_path.to_string_lossy().as_bytes())?;
+        let mut view = PodcastView::new(
+            &subscriptions,
+            &tmp_dir.path().join("cache"),
+            &tmp_dir.path().join("episodes"),
+        );
+
+        // when
+        view.refresh();
+        wait_until(|| {
+            view.update();
+            !view.episodes.is_empty()
+        });
+
+        // then
+        assert_eq!(view.episodes.len(), 2);
+        assert_ne!(view.episodes[0].local_path, view.episodes[1].local_path);
+
+        Ok(())
+    }
+
+    fn wait_until(mut condition: impl FnMut() -> bool) {
+        let start = Instant::now();
+        while !condition() {
+            assert!(
+                start.elapsed() < Duration::from_secs(5),
+                "Timed out waiting"
+            );
+            thread::sleep(Duration::from_millis(10));
+        }
+    }
+}
@@ -1,7 +1,12 @@
 pub mod queue_view;
 pub mod song_file;
+pub mod station_list;
 
 pub use song_file::SongFile;
 
+#[cfg(test)]
+mod queue_view_test;
 #[cfg(test)]
 mod song_file_test;
+#[cfg(test)]
+mod station_list_test;
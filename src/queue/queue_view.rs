@@ -1,7 +1,55 @@
-use log::{error, trace};
+use clap::ValueEnum;
+use log::{error, trace, warn};
 use ratatui::widgets::ListState;
+use std::{path::Path, time::Duration};
+
+use crate::{
+    application::actions::Action,
+    player::duration_formatter::{DurationFormat, DurationFormatter},
+    queue::SongFile,
+};
+
+/// Columns available in queue table, selected with `--queue-columns`
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+pub enum QueueColumn {
+    /// Number of track on its album
+    Track,
+    Artist,
+    Title,
+    Album,
+    Duration,
+    Rating,
+}
 
-use crate::{application::actions::Action, files::FileEntry, queue::SongFile};
+impl QueueColumn {
+    pub fn header(self) -> &'static str {
+        match self {
+            QueueColumn::Track => "#",
+            QueueColumn::Artist => "Artist",
+            QueueColumn::Title => "Title",
+            QueueColumn::Album => "Album",
+            QueueColumn::Duration => "Time",
+            QueueColumn::Rating => "Rating",
+        }
+    }
+
+    /// Returns value of column for given song, rating is passed already formatted
+    pub fn value(self, song: &SongFile, rating: &str) -> String {
+        let metadata = &song.metadata;
+        match self {
+            QueueColumn::Track => metadata.track.map(|t| t.to_string()).unwrap_or_default(),
+            QueueColumn::Artist => metadata.artist.clone().unwrap_or_default(),
+            QueueColumn::Title => metadata
+                .title
+                .clone()
+                .unwrap_or_else(|| song.file_entry.name.clone()),
+            QueueColumn::Album => metadata.album.clone().unwrap_or_default(),
+            QueueColumn::Duration if song.is_stream() => String::from("live"),
+            QueueColumn::Duration => song.duration.format(DurationFormat::MmSs),
+            QueueColumn::Rating => String::from(rating),
+        }
+    }
+}
 
 pub struct QueueView {
     pub state: ListState,
@@ -41,8 +89,8 @@ impl QueueView {
         }
     }
 
-    pub fn add(&mut self, file_entry: &FileEntry) {
-        self.items.push(SongFile::new(file_entry));
+    pub fn add(&mut self, song: SongFile) {
+        self.items.push(song);
     }
 
     fn next(&mut self) {
@@ -95,6 +143,38 @@ impl QueueView {
         self.now_playing.map(|i| &self.items[i])
     }
 
+    /// Flags songs whose files no longer exist, or exist again.
+    /// Returns number of songs found missing since previous check.
+    pub fn update_missing(&mut self) -> usize {
+        let mut newly_missing = 0;
+        for song in self.items.iter_mut().filter(|s| !s.is_stream()) {
+            let missing = !Path::new(&song.file_entry.path).exists();
+            if missing && !song.missing {
+                warn!("Queued file {} is missing", song.file_entry.path);
+                newly_missing += 1;
+            }
+            song.missing = missing;
+        }
+        newly_missing
+    }
+
+    /// Returns summed duration of all songs in queue
+    pub fn total_duration(&self) -> Duration {
+        self.items.iter().map(|s| s.duration).sum()
+    }
+
+    /// Returns time left until end of queue, when now playing song is at given position
+    pub fn remaining_duration(&self, elapsed: Duration) -> Duration {
+        match self.now_playing {
+            Some(index) => self.items[index..]
+                .iter()
+                .map(|s| s.duration)
+                .sum::<Duration>()
+                .saturating_sub(elapsed),
+            None => self.total_duration(),
+        }
+    }
+
     fn update_now_playing(&mut self, direction: UpdateDirection) {
         if let Some(index) = self.now_playing {
             match direction {
@@ -0,0 +1,101 @@
+#[cfg(test)]
+mod tests {
+    use std::{fs, io, time::Duration};
+
+    use tempdir::TempDir;
+
+    use crate::queue::{
+        queue_view::{QueueColumn, QueueView},
+        SongFile,
+    };
+
+    fn song(title: &str, seconds: u64) -> SongFile {
+        let mut song = SongFile::stream(title, &format!("/music/{title}.mp3"));
+        song.duration = Duration::from_secs(seconds);
+        song
+    }
+
+    #[test]
+    fn should_sum_total_and_remaining_time() {
+        // given
+        let mut queue = QueueView::new();
+        queue.add(song("first", 100));
+        queue.add(song("second", 200));
+        queue.add(song("third", 300));
+        queue.now_playing = Some(1);
+
+        // when
+        let total = queue.total_duration();
+        let remaining = queue.remaining_duration(Duration::from_secs(50));
+
+        // then
+        assert_eq!(total, Duration::from_secs(600));
+        assert_eq!(remaining, Duration::from_secs(450));
+    }
+
+    #[test]
+    fn should_count_whole_queue_as_remaining_when_nothing_is_playing() {
+        // given
+        let mut queue = QueueView::new();
+        queue.add(song("first", 100));
+
+        // when
+        let result = queue.remaining_duration(Duration::from_secs(50));
+
+        // then
+        assert_eq!(result, Duration::from_secs(100));
+    }
+
+    #[test]
+    fn should_format_column_values() {
+        // given
+        let mut song = song("Blue in green", 337);
+        song.metadata.artist = Some(String::from("Miles Davis"));
+        song.metadata.track = Some(3);
+
+        // when
+        let result: Vec<String> = [
+            QueueColumn::Track,
+            QueueColumn::Artist,
+            QueueColumn::Title,
+            QueueColumn::Album,
+            QueueColumn::Duration,
+            QueueColumn::Rating,
+        ]
+        .iter()
+        .map(|column| column.value(&song, "★★★☆☆"))
+        .collect();
+
+        // then
+        assert_eq!(
+            result,
+            vec!["3", "Miles Davis", "Blue in green", "", "05:37", "★★★☆☆"]
+        );
+    }
+
+    #[test]
+    fn should_flag_songs_with_missing_files() -> Result<(), io::Error> {
+        // given
+        let tmp_dir = TempDir::new("queue")?;
+        let path = tmp_dir.path().join("song.mp3");
+        fs::write(&path, [])?;
+        let mut queue = QueueView::new();
+        let mut existing = song("song", 100);
+        existing.file_entry.path = path.to_string_lossy().to_string();
+        queue.add(existing);
+        queue.add(song("removed", 100));
+        queue.add(SongFile::stream("Radio", "http://radio.example/stream"));
+
+        // when
+        let first_check = queue.update_missing();
+        let second_check = queue.update_missing();
+
+        // then
+        assert_eq!(first_check, 1);
+        assert_eq!(second_check, 0);
+        let missing: Vec<bool> = queue.items.iter().map(|s| s.missing).collect();
+        assert_eq!(missing, vec![false, true, false]);
+
+        Ok(())
+    }
+}
@@ -1,32 +1,76 @@
 use crate::{
+    error::AppError,
     files::FileEntry,
-    player::{metadata::Mp3Metadata, MetadataReader},
+    player::{metadata::Mp3Metadata, stream::is_stream_url, MetadataReader},
 };
-use std::{path::Path, time::Duration};
+use std::{fs::File, io, path::Path, time::Duration};
 
 /// Information about currently selected song in mp3 player
-#[derive(Clone)]
+#[derive(Clone, Debug)]
 pub struct SongFile {
     pub metadata: Mp3Metadata,
     pub duration: Duration,
     pub file_entry: FileEntry,
+    /// File was removed or renamed after song was queued
+    pub missing: bool,
 }
 
 impl SongFile {
-    pub fn new(file_entry: &FileEntry) -> Self {
+    /// Reads song information from file, failing if file cannot be read
+    pub fn new(file_entry: &FileEntry) -> Result<Self, AppError> {
+        let open_error = |source| AppError::Open {
+            path: file_entry.path.clone(),
+            source,
+        };
+        File::open(&file_entry.path).map_err(open_error)?;
+        let metadata = MetadataReader::read_metadata(file_entry)
+            .ok_or_else(|| open_error(io::Error::new(io::ErrorKind::InvalidInput, "not a file")))?;
         let duration =
             mp3_duration::from_path(Path::new(&file_entry.path)).unwrap_or(Duration::ZERO);
-        SongFile {
-            metadata: MetadataReader::read_metadata(file_entry).unwrap(),
+        Ok(SongFile {
+            metadata,
             duration,
             file_entry: file_entry.clone(),
+            missing: false,
+        })
+    }
+
+    /// Creates entry for network stream, using station name as title
+    pub fn stream(name: &str, url: &str) -> Self {
+        SongFile {
+            metadata: Mp3Metadata {
+                artist: None,
+                title: Some(String::from(name)),
+                album: None,
+                genre: None,
+                year: None,
+                track: None,
+                file_path: String::from(url),
+            },
+            duration: Duration::ZERO,
+            file_entry: FileEntry {
+                path: String::from(url),
+                name: String::from(name),
+                is_file: true,
+            },
+            missing: false,
         }
     }
 
+    /// Returns true if song is played from network stream
+    pub fn is_stream(&self) -> bool {
+        is_stream_url(&self.file_entry.path)
+    }
+
     /// Returns vector of information to be displayed about selected song
     /// Informations are strings in format `<label> : <value>`
     pub fn display(&self) -> Vec<String> {
         let mut formatted = vec![];
+        if self.is_stream() {
+            formatted.push(format!("Station: {}", self.file_entry.name));
+            formatted.push(format!("URL    : {}", self.file_entry.path));
+            return formatted;
+        }
         if let Some(a) = &self.metadata.artist {
             formatted.push(format!("Artist: {a}"));
         }
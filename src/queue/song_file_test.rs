@@ -5,7 +5,7 @@ mod tests {
     use id3::{Tag, TagLike};
     use tempdir::TempDir;
 
-    use crate::{files::FileEntry, queue::song_file::SongFile};
+    use crate::{error::AppError, files::FileEntry, queue::song_file::SongFile};
 
     #[test]
     fn should_format_with_artist_and_title() -> Result<(), Box<dyn std::error::Error>> {
@@ -16,7 +16,7 @@ mod tests {
         let file_entry = FileEntry::new(&dummy_mp3_file_path);
 
         // when
-        let result = SongFile::new(&file_entry);
+        let result = SongFile::new(&file_entry)?;
 
         // then
         assert_eq!(
@@ -38,7 +38,7 @@ mod tests {
         let file_entry = FileEntry::new(&dummy_mp3_file_path);
 
         // when
-        let result = SongFile::new(&file_entry);
+        let result = SongFile::new(&file_entry)?;
 
         // then
         assert_eq!(
@@ -49,6 +49,21 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn should_fail_for_missing_file() -> Result<(), Box<dyn std::error::Error>> {
+        // given
+        let tmp_dir = TempDir::new("missing_song")?;
+        let file_entry = FileEntry::new(&tmp_dir.path().join("removed.mp3"));
+
+        // when
+        let result = SongFile::new(&file_entry);
+
+        // then
+        assert!(matches!(result, Err(AppError::Open { .. })));
+
+        Ok(())
+    }
+
     fn prepare_dummy_mp3(path: &str) -> Result<(), Box<dyn std::error::Error>> {
         File::create(path)?;
 
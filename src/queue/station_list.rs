@@ -0,0 +1,40 @@
+use log::warn;
+use std::{fs, io, path::Path};
+
+use super::SongFile;
+
+/// Reads list of internet radio stations.
+/// Each line of file contains station in format `<name> = <url>`,
+/// lines starting with `#` are treated as comments.
+pub fn read_station_list(path: &Path) -> io::Result<Vec<SongFile>> {
+    let content = fs::read_to_string(path)?;
+    Ok(content
+        .lines()
+        .enumerate()
+        .filter_map(|(number, line)| {
+            let station = parse_station_line(line);
+            if station.is_none() && !is_blank_or_comment(line) {
+                warn!("Skipping invalid station in line {}: {line}", number + 1);
+            }
+            station
+        })
+        .map(|(name, url)| SongFile::stream(&name, &url))
+        .collect())
+}
+
+fn parse_station_line(line: &str) -> Option<(String, String)> {
+    if is_blank_or_comment(line) {
+        return None;
+    }
+    let (name, url) = line.split_once('=')?;
+    let (name, url) = (name.trim(), url.trim());
+    if name.is_empty() || !(url.starts_with("http://") || url.starts_with("https://")) {
+        return None;
+    }
+    Some((String::from(name), String::from(url)))
+}
+
+fn is_blank_or_comment(line: &str) -> bool {
+    let line = line.trim();
+    line.is_empty() || line.starts_with('#')
+}
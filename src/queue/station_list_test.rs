@@ -0,0 +1,37 @@
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use tempdir::TempDir;
+
+    use crate::queue::station_list::read_station_list;
+
+    #[test]
+    fn should_read_stations_skipping_comments_and_invalid_lines(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        // given
+        let tmp_dir = TempDir::new("stations")?;
+        let station_file = tmp_dir.path().join("stations");
+        fs::write(
+            &station_file,
+            "# My radios\n\
+             Jazz Radio = http://localhost:8000/jazz.mp3\n\
+             \n\
+             broken line\n\
+             Rock FM=https://example.com/rock\n",
+        )?;
+
+        // when
+        let result = read_station_list(&station_file)?;
+
+        // then
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[0].file_entry.name, "Jazz Radio");
+        assert_eq!(result[0].file_entry.path, "http://localhost:8000/jazz.mp3");
+        assert!(result[0].is_stream());
+        assert_eq!(result[1].file_entry.name, "Rock FM");
+        assert_eq!(result[1].file_entry.path, "https://example.com/rock");
+
+        Ok(())
+    }
+}
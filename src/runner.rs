@@ -1,53 +1,58 @@
 use crate::{
     application::{
         actions::{Action, Actions},
-        ui, App,
+        ui, App, AppActionResult,
         AppActionResult::Exit,
     },
+    daemon::{ControlServer, RemoteClient, RemoteCommand},
     input::{AppEvent, EventBus},
 };
 use crossterm::{
-    event::DisableMouseCapture,
+    event::{
+        DisableBracketedPaste, DisableFocusChange, DisableMouseCapture, EnableBracketedPaste,
+        EnableFocusChange, EnableMouseCapture,
+    },
     execute,
     terminal::{
         disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen, SetTitle,
     },
     ExecutableCommand,
 };
-use log::{info, LevelFilter};
+use log::{debug, info, LevelFilter};
 use ratatui::{backend::CrosstermBackend, Terminal};
 use std::{
-    io,
+    io::{self, Stdout},
     sync::{Arc, Mutex},
+    time::Duration,
 };
 
+type PennyTerminal = Terminal<CrosstermBackend<Stdout>>;
+
+/// Interface is redrawn only on every n-th frame while terminal is not focused
+const UNFOCUSED_REDRAW_FRAMES: usize = 10;
+
 /// Application runner handling terminal setup as well as managing app lifetime
 pub fn run_app(app: &mut App, events: Arc<Mutex<EventBus>>) -> io::Result<()> {
-    let stdout = io::stdout();
-    enable_raw_mode()?;
-
     tui_logger::init_logger(LevelFilter::Trace).unwrap();
     tui_logger::set_default_level(app.state.log_level);
 
-    let mut backend = CrosstermBackend::new(stdout);
-    backend.execute(SetTitle("penny"))?;
-    let mut terminal = Terminal::new(backend)?;
-    execute!(
-        terminal.backend_mut(),
-        EnterAlternateScreen,
-        DisableMouseCapture
-    )?;
-
-    terminal.clear()?;
-    terminal.hide_cursor()?;
+    let mut terminal = setup_terminal()?;
     info!("Welcome to penny!");
 
+    let mut redraw = true;
+    let mut unfocused_frames = 0;
     loop {
-        terminal.draw(|f| ui(f, app))?;
+        if redraw {
+            terminal.draw(|f| ui(f, app))?;
+        }
+        redraw = true;
 
         let mut events_ref = events.lock().unwrap();
 
         match events_ref.next() {
+            AppEvent::Input(key_code) if app.state.popup.is_some() => {
+                app.handle_popup_key(key_code)
+            }
             AppEvent::Input(key_code) => {
                 if let Some(action) = Actions::from(key_code) {
                     if let Exit = app.do_action(action) {
@@ -57,20 +62,172 @@ pub fn run_app(app: &mut App, events: Arc<Mutex<EventBus>>) -> io::Result<()> {
                     }
                 }
             }
-            AppEvent::Tick => {}
+            AppEvent::Mouse(mouse_event) => app.handle_mouse(mouse_event),
+            // Layout is recomputed for new terminal size during redraw
+            AppEvent::Resize(columns, rows) => debug!("Terminal resized to {columns}x{rows}"),
+            AppEvent::FocusChanged(focused) => {
+                app.set_terminal_focused(focused);
+                unfocused_frames = 0;
+            }
+            AppEvent::Paste(text) => app.handle_paste(&text),
+            AppEvent::Tick => redraw = app.on_tick(),
+            AppEvent::Frame if !app.state.terminal_focused => {
+                unfocused_frames += 1;
+                redraw = unfocused_frames % UNFOCUSED_REDRAW_FRAMES == 0;
+            }
+            AppEvent::Frame => {}
             AppEvent::Playback(event) => {
-                app.do_action(Actions::from_event(event));
+                if let Some(action) = Actions::from_event(event) {
+                    app.do_action(action);
+                }
+            }
+            AppEvent::Remote(_, reply) => {
+                let _ = reply.send(String::from("ERR penny is not running as daemon"));
+                redraw = false;
             }
         };
+        // Frames keep interface moving during playback, other changes redraw it on their events
+        events_ref.set_frames_enabled(app.is_animated());
     }
 
+    restore_terminal(terminal)
+}
+
+/// Runs application without terminal interface, executing commands received
+/// through control socket until `quit` command is received
+pub fn run_daemon(
+    app: &mut App,
+    events: Arc<Mutex<EventBus>>,
+    socket_path: &str,
+) -> io::Result<()> {
+    let sender = events.lock().unwrap().sender();
+    let _server = ControlServer::start(socket_path, sender)?;
+    println!("penny daemon listening on {socket_path}");
+
+    loop {
+        let mut events_ref = events.lock().unwrap();
+
+        match events_ref.next() {
+            AppEvent::Remote(command, reply) => {
+                let (response, result) = execute_remote_command(app, command);
+                let _ = reply.send(response);
+                if let Exit = result {
+                    app.do_action(Action::StopPlayback);
+                    events_ref.close();
+                    break;
+                }
+            }
+            AppEvent::Playback(event) => {
+                if let Some(action) = Actions::from_event(event) {
+                    app.do_action(action);
+                }
+            }
+            // Downloads podcast episodes, checks queued files and expires messages
+            AppEvent::Tick => {
+                app.on_tick();
+            }
+            AppEvent::Input(_)
+            | AppEvent::Mouse(_)
+            | AppEvent::Resize(_, _)
+            | AppEvent::FocusChanged(_)
+            | AppEvent::Paste(_)
+            | AppEvent::Frame => {}
+        };
+    }
+
+    Ok(())
+}
+
+/// Opens terminal interface displaying state of running daemon
+/// and forwarding playback control keys to it.
+/// Quitting interface only detaches from daemon, leaving playback running.
+pub fn run_attached(socket_path: &str, tick_rate: Duration) -> io::Result<()> {
+    let mut client = RemoteClient::connect(socket_path)?;
+    // Frames are never enabled, status of daemon is requested on every tick
+    let mut events = EventBus::new(tick_rate, tick_rate);
+    let mut terminal = setup_terminal()?;
+
+    loop {
+        let status = client
+            .send(&RemoteCommand::Status)
+            .unwrap_or_else(|e| format!("ERR {e}"));
+        terminal.draw(|f| ui::remote_ui(f, socket_path, &status))?;
+
+        match events.next() {
+            AppEvent::Input(key_code) => match Actions::from(key_code) {
+                Some(Action::Quit) => {
+                    events.close();
+                    break;
+                }
+                Some(action) => {
+                    if let Some(command) = RemoteCommand::from_action(&action) {
+                        client.send(&command)?;
+                    }
+                }
+                None => {}
+            },
+            AppEvent::Mouse(_)
+            | AppEvent::Resize(_, _)
+            | AppEvent::FocusChanged(_)
+            | AppEvent::Paste(_)
+            | AppEvent::Tick
+            | AppEvent::Frame
+            | AppEvent::Playback(_)
+            | AppEvent::Remote(_, _) => {}
+        }
+    }
+
+    restore_terminal(terminal)
+}
+
+fn execute_remote_command(app: &mut App, command: RemoteCommand) -> (String, AppActionResult) {
+    match command {
+        RemoteCommand::Status => (format!("OK {}", app.status()), AppActionResult::Continue),
+        RemoteCommand::Add(path) => (
+            format!("OK added {} song(s)", app.enqueue_path(&path)),
+            AppActionResult::Continue,
+        ),
+        command => match Actions::from_remote(&command) {
+            Some(action) => (String::from("OK"), app.do_action(action)),
+            None => (
+                format!("ERR unsupported command {command}"),
+                AppActionResult::Continue,
+            ),
+        },
+    }
+}
+
+fn setup_terminal() -> io::Result<PennyTerminal> {
+    let stdout = io::stdout();
+    enable_raw_mode()?;
+
+    let mut backend = CrosstermBackend::new(stdout);
+    backend.execute(SetTitle("penny"))?;
+    let mut terminal = Terminal::new(backend)?;
+    execute!(
+        terminal.backend_mut(),
+        EnterAlternateScreen,
+        EnableMouseCapture,
+        EnableFocusChange,
+        EnableBracketedPaste
+    )?;
+
+    terminal.clear()?;
+    terminal.hide_cursor()?;
+
+    Ok(terminal)
+}
+
+fn restore_terminal(mut terminal: PennyTerminal) -> io::Result<()> {
     terminal.clear()?;
     terminal.show_cursor()?;
     disable_raw_mode()?;
     execute!(
         terminal.backend_mut(),
         LeaveAlternateScreen,
-        DisableMouseCapture
+        DisableMouseCapture,
+        DisableFocusChange,
+        DisableBracketedPaste
     )?;
 
     Ok(())